@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    Attribute, BinOp, Data, DeriveInput, Error, Expr, ExprPath, Fields, GenericArgument, Ident,
+    Path, PathArguments, Type, parse_macro_input, parse_quote,
+};
+
+struct Field {
+    ident: Ident,
+    ty: Type,
+    // The T in Option<T>, for optional properties
+    optional: Option<Type>,
+    key: Path,
+    flags: Expr,
+}
+
+// Flags may be given as bare PropertyFlags names (READ_ONLY | HARDWARE), or any expression
+fn flags_expr(expr: Expr) -> Expr {
+    match expr {
+        Expr::Path(ExprPath { path, .. }) if path.get_ident().is_some() => {
+            parse_quote! { PropertyFlags::#path }
+        }
+        Expr::Binary(mut b) if matches!(b.op, BinOp::BitOr(_)) => {
+            *b.left = flags_expr(*b.left);
+            *b.right = flags_expr(*b.right);
+            Expr::Binary(b)
+        }
+        e => e,
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<Type> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let segment = p.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(t) => Some(t.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_object_attrs(attrs: &[Attribute]) -> Result<(Expr, Expr), Error> {
+    let mut object_type = None;
+    let mut id = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("pod")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("object_type") {
+                object_type = Some(meta.value()?.parse::<Expr>()?);
+            } else if meta.path.is_ident("id") {
+                id = Some(meta.value()?.parse::<Expr>()?);
+            } else {
+                return Err(meta.error("expected `object_type` or `id`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    match (object_type, id) {
+        (Some(object_type), Some(id)) => Ok((object_type, id)),
+        _ => Err(Error::new(
+            Span::call_site(),
+            "expected #[pod(object_type = ..., id = ...)] on the struct",
+        )),
+    }
+}
+
+fn parse_field(field: syn::Field) -> Result<Field, Error> {
+    let mut key = None;
+    let mut flags = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("pod")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                key = Some(meta.value()?.parse::<Path>()?);
+            } else if meta.path.is_ident("flags") {
+                flags = Some(flags_expr(meta.value()?.parse::<Expr>()?));
+            } else {
+                return Err(meta.error("expected `key` or `flags`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let ident = field.ident.unwrap();
+    let key = key.ok_or_else(|| Error::new(ident.span(), "expected #[pod(key = ...)]"))?;
+    if key.segments.len() < 2 {
+        return Err(Error::new_spanned(
+            key,
+            "expected a key such as `Prop::Volume`",
+        ));
+    }
+
+    Ok(Field {
+        ident,
+        optional: option_inner(&field.ty),
+        ty: field.ty,
+        key,
+        flags: flags.unwrap_or_else(|| parse_quote! { PropertyFlags::empty() }),
+    })
+}
+
+fn derive(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let DeriveInput {
+        ident,
+        attrs,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let fields = match data {
+        Data::Struct(s) => match s.fields {
+            Fields::Named(f) => f.named,
+            f => return Err(Error::new_spanned(f, "PodObject needs named fields")),
+        },
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "PodObject can only be derived for structs",
+            ));
+        }
+    };
+
+    let (object_type, id) = parse_object_attrs(&attrs)?;
+    let fields = fields
+        .into_iter()
+        .map(parse_field)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The key enum is the path to the first key, without the variant
+    let key_type = match fields.first() {
+        Some(f) => {
+            let mut path = f.key.clone();
+            path.segments.pop();
+            path.segments.pop_punct();
+            path
+        }
+        None => {
+            return Err(Error::new(
+                ident.span(),
+                "PodObject needs at least one field",
+            ));
+        }
+    };
+
+    let object_parser_ident: Ident = parse_quote! { object_parser };
+    let mut fields_encode = vec![];
+    let mut fields_decode = vec![];
+    let mut fields_match = vec![];
+    let mut fields_init = vec![];
+
+    for f in fields {
+        let Field {
+            ident: field_name,
+            ty,
+            optional,
+            key,
+            flags,
+        } = f;
+
+        match optional {
+            // Absent optional fields are not written out at all
+            Some(inner) => {
+                fields_encode.push(quote! {
+                    let object_builder = match &self.#field_name {
+                        Some(value) => object_builder.push_property(#key, #flags, value.clone()),
+                        None => object_builder,
+                    };
+                });
+                fields_decode.push(quote! {
+                    let mut #field_name: Option<#inner> = None;
+                });
+                fields_match.push(quote! {
+                    #key => #field_name = Some(value.decode::<#inner>()?),
+                });
+                fields_init.push(quote! {
+                    #field_name,
+                });
+            }
+            None => {
+                fields_encode.push(quote! {
+                    let object_builder = object_builder.push_property(#key, #flags, self.#field_name.clone());
+                });
+                fields_decode.push(quote! {
+                    let mut #field_name: Option<#ty> = None;
+                });
+                fields_match.push(quote! {
+                    #key => #field_name = Some(value.decode::<#ty>()?),
+                });
+                fields_init.push(quote! {
+                    #field_name: #field_name.ok_or(Error::Invalid)?,
+                });
+            }
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics Pod for #ident #ty_generics #where_clause {
+            type DecodesTo = Self;
+
+            fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+                let builder = Builder::new(data);
+
+                builder.push_object(#object_type, #id, |object_builder| {
+                    #(#fields_encode)*
+                    object_builder
+                })
+                .build().map(|res| res.len())
+            }
+
+            fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error> {
+                let mut parser = Parser::new(data);
+
+                parser.pop_object::<#key_type, _>(|#object_parser_ident, _id| {
+                    #(#fields_decode)*
+
+                    while let Some((key, _flags, value)) = #object_parser_ident.pop_property::<#key_type>()? {
+                        match key {
+                            #(#fields_match)*
+                            // Properties we don't know about are ignored
+                            _ => {}
+                        }
+                    }
+
+                    Ok(Self {
+                        #(#fields_init)*
+                    })
+                })
+            }
+        }
+    })
+}
+
+pub fn derive_pod_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -4,69 +4,229 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Ident};
+use syn::{
+    Attribute, BinOp, Data, DataEnum, DataStruct, DeriveInput, Error, Expr, ExprBinary, Fields,
+    Generics, Ident, Index, parse_macro_input, parse_quote,
+};
 
-pub fn derive_pod_struct(input: TokenStream) -> TokenStream {
-    let DeriveInput {
-        ident,
-        generics,
-        data,
-        ..
-    } = parse_macro_input!(input);
+// Every type parameter must itself be a pod that decodes to the same type
+fn add_bounds(mut generics: Generics) -> Generics {
+    let params = generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect::<Vec<_>>();
+    let where_clause = generics.make_where_clause();
 
-    let data = match data {
-        Data::Struct(s) => s,
-        _ => return quote! {}.into(),
-    };
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: Pod<DecodesTo = #param> });
+    }
 
-    let fields = match data.fields {
-        Fields::Named(f) => f.named,
-        Fields::Unnamed(_) => return quote! {}.into(),
-        Fields::Unit => return quote! {}.into(),
-    };
+    generics
+}
 
+fn derive_struct(data: DataStruct) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let struct_parser_ident: Ident = parse_quote! { struct_parser };
     let mut fields_encode = vec![];
     let mut fields_decode = vec![];
 
     /* Each line pushes the field into to the StructBuilder */
-    for f in fields {
-        let field_name = f.ident.unwrap();
-        let field_type = f.ty;
+    for (i, f) in data.fields.iter().enumerate() {
+        let field_type = &f.ty;
 
-        fields_encode.push(quote! {
-            .push_pod(&self.#field_name)
-        });
+        match &f.ident {
+            Some(field_name) => {
+                fields_encode.push(quote! {
+                    .push_pod(&self.#field_name)
+                });
+                fields_decode.push(quote! {
+                    #field_name: #struct_parser_ident.pop_pod::<#field_type>()?,
+                });
+            }
+            None => {
+                let index = Index::from(i);
+                fields_encode.push(quote! {
+                    .push_pod(&self.#index)
+                });
+                fields_decode.push(quote! {
+                    #struct_parser_ident.pop_pod::<#field_type>()?,
+                });
+            }
+        }
+    }
+
+    let value = match &data.fields {
+        Fields::Named(_) => quote! { Self { #(#fields_decode)* } },
+        Fields::Unnamed(_) => quote! { Self( #(#fields_decode)* ) },
+        Fields::Unit => quote! { Self },
+    };
+
+    // Avoid an unused variable for empty structs
+    let parser_arg = if data.fields.is_empty() {
+        quote! { _ }
+    } else {
+        quote! { #struct_parser_ident }
+    };
+
+    let encode = quote! {
+        let builder = Builder::new(data);
+
+        builder.push_struct(|struct_builder| {
+            struct_builder
+                #(#fields_encode)*
+        })
+        .build().map(|res| res.len())
+    };
+
+    let decode = quote! {
+        let mut parser = Parser::new(data);
+
+        parser.pop_struct(|#parser_arg| {
+            Ok(#value)
+        })
+    };
+
+    (encode, decode)
+}
+
+// Whether a field-less enum is encoded as an Id (the default) or an Int
+fn enum_is_int(attrs: &[Attribute]) -> Result<bool, Error> {
+    let mut is_int = false;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("pod")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("int") {
+                is_int = true;
+            } else if meta.path.is_ident("id") {
+                is_int = false;
+            } else {
+                return Err(meta.error("expected `id` or `int`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(is_int)
+}
+
+fn derive_enum(
+    data: DataEnum,
+    attrs: &[Attribute],
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream), Error> {
+    let is_int = enum_is_int(attrs)?;
+    let value_ident: Ident = parse_quote! { value };
+    let mut encode_arms = vec![];
+    let mut decode_arms = vec![];
+    let mut last_value: Option<Expr> = None;
 
-        fields_decode.push(quote! {
-            #field_name: #struct_parser_ident.pop_pod::<#field_type>()?,
+    for v in data.variants {
+        if !v.fields.is_empty() {
+            return Err(Error::new_spanned(
+                v.fields,
+                "PodStruct enums can't have fields",
+            ));
+        }
+
+        let id = v.ident;
+        // Same rules as for the discriminant, so that this matches `as u32`
+        let value: Expr = if let Some(d) = v.discriminant {
+            d.1
+        } else if let Some(v) = last_value {
+            Expr::from(ExprBinary {
+                attrs: vec![],
+                left: Box::new(v),
+                op: BinOp::Add(parse_quote! { + }),
+                right: Box::new(parse_quote! { 1 }),
+            })
+        } else {
+            parse_quote! { 0 }
+        };
+
+        encode_arms.push(quote! {
+            Self::#id => (#value) as u32,
+        });
+        decode_arms.push(quote! {
+            if #value_ident == (#value) as u32 {
+                return Ok((Self::#id, size));
+            }
         });
+        last_value = Some(value);
     }
 
-    quote! {
-        impl #generics Pod for #ident {
-            type DecodesTo = #ident;
+    let (encode_value, decode_value) = if is_int {
+        (
+            quote! { (#value_ident as i32).encode(data) },
+            quote! { <i32 as Pod>::decode(data).map(|(v, size)| (v as u32, size))? },
+        )
+    } else {
+        (
+            quote! { Id(#value_ident).encode(data) },
+            quote! { <Id<u32> as Pod>::decode(data).map(|(v, size)| (v.0, size))? },
+        )
+    };
 
-            fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
-                let builder = Builder::new(data);
+    let encode = quote! {
+        let #value_ident: u32 = match self {
+            #(#encode_arms)*
+        };
+
+        #encode_value
+    };
 
-                builder.push_struct(|struct_builder| {
-                    struct_builder
-                        #(#fields_encode)*
-                })
-                .build().map(|res| res.len())
+    let decode = quote! {
+        let (#value_ident, size) = #decode_value;
+
+        #(#decode_arms)*
+
+        Err(Error::Invalid)
+    };
+
+    Ok((encode, decode))
+}
+
+fn derive(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let DeriveInput {
+        ident,
+        attrs,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let (encode, decode) = match data {
+        Data::Struct(s) => derive_struct(s),
+        Data::Enum(e) => derive_enum(e, &attrs)?,
+        Data::Union(u) => {
+            return Err(Error::new_spanned(
+                u.union_token,
+                "PodStruct can't be derived for unions",
+            ));
+        }
+    };
+
+    let generics = add_bounds(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics Pod for #ident #ty_generics #where_clause {
+            type DecodesTo = Self;
+
+            fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+                #encode
             }
 
             fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error> {
-                let mut parser = Parser::new(data);
-
-                parser.pop_struct(|#struct_parser_ident| {
-                    Ok(Self {
-                        #(#fields_decode)*
-                    })
-                })
+                #decode
             }
         }
-    }
-    .into()
+    })
+}
+
+pub fn derive_pod_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
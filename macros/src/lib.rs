@@ -5,6 +5,7 @@
 use proc_macro::TokenStream;
 
 mod derive_enum_u32;
+mod derive_pod_object;
 mod derive_pod_struct;
 
 #[proc_macro_derive(EnumU32)]
@@ -12,7 +13,12 @@ pub fn proc_macro_enum_u32(item: TokenStream) -> TokenStream {
     derive_enum_u32::derive_enum_u32(item)
 }
 
-#[proc_macro_derive(PodStruct)]
+#[proc_macro_derive(PodObject, attributes(pod))]
+pub fn proc_macro_pod_object(item: TokenStream) -> TokenStream {
+    derive_pod_object::derive_pod_object(item)
+}
+
+#[proc_macro_derive(PodStruct, attributes(pod))]
 pub fn proc_macro_pod_struct(item: TokenStream) -> TokenStream {
     derive_pod_struct::derive_pod_struct(item)
 }
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use pipewire_native_spa::param::ParamType;
+use pipewire_native_spa::param::props::Prop;
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::{ObjectType, PropertyFlags};
+use pipewire_native_spa::pod::{Error, Pod};
+
+use pipewire_native_macros::PodObject;
+
+#[derive(Debug, Default, PartialEq, PodObject)]
+#[pod(object_type = ObjectType::Props, id = ParamType::Props)]
+struct TestProps {
+    #[pod(key = Prop::Volume)]
+    volume: f32,
+    #[pod(key = Prop::Mute, flags = READ_ONLY | HARDWARE)]
+    mute: Option<bool>,
+    #[pod(key = Prop::DeviceName)]
+    device_name: Option<String>,
+}
+
+#[test]
+fn test_derive_pod_object() {
+    let mut buf = [0u8; 1024];
+
+    let value = TestProps {
+        volume: 0.5,
+        mute: Some(true),
+        device_name: None,
+    };
+
+    let pod_size = value.encode(&mut buf).unwrap();
+
+    let mut expected = [0u8; 1024];
+    let builder = Builder::new(&mut expected);
+    let res = builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Volume, PropertyFlags::empty(), 0.5f32)
+                .push_property(
+                    Prop::Mute,
+                    PropertyFlags::READ_ONLY | PropertyFlags::HARDWARE,
+                    true,
+                )
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(&buf[..pod_size], res);
+
+    let (decoded, dec_size) = TestProps::decode(&buf).unwrap();
+
+    assert_eq!(pod_size, dec_size);
+    assert_eq!(value, decoded);
+}
+
+#[test]
+fn test_derive_pod_object_decode() {
+    let mut buf = [0u8; 1024];
+
+    // Properties that are not in the struct, or not known at all, are skipped
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Frequency, PropertyFlags::empty(), 440.0f32)
+                .push_property(0x1000001u32, PropertyFlags::empty(), 1i32)
+                .push_property(Prop::DeviceName, PropertyFlags::empty(), "hw:0")
+                .push_property(Prop::Volume, PropertyFlags::empty(), 1.0f32)
+        })
+        .build()
+        .unwrap();
+
+    let (decoded, _) = TestProps::decode(res).unwrap();
+    assert_eq!(
+        decoded,
+        TestProps {
+            volume: 1.0,
+            mute: None,
+            device_name: Some("hw:0".to_string()),
+        }
+    );
+
+    // Missing mandatory fields are an error
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Mute, PropertyFlags::empty(), false)
+        })
+        .build()
+        .unwrap();
+
+    assert!(TestProps::decode(res).is_err());
+}
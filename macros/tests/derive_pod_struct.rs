@@ -4,6 +4,7 @@
 
 use pipewire_native_spa::pod::builder::Builder;
 use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::Id;
 use pipewire_native_spa::pod::{Error, Pod};
 
 use pipewire_native_macros::PodStruct;
@@ -32,3 +33,114 @@ fn test_derive_pod_struct() {
     assert_eq!(pod_size, dec_size);
     assert_eq!(value, decoded);
 }
+
+#[derive(Debug, Eq, PartialEq, PodStruct)]
+struct TestTuple(i32, Option<String>, Option<i64>);
+
+#[derive(Debug, Eq, PartialEq, PodStruct)]
+struct TestUnit;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PodStruct)]
+enum TestId {
+    First = 1,
+    Second,
+    Tenth = 10,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PodStruct)]
+#[pod(int)]
+enum TestInt {
+    Zero,
+    Hundred = 100,
+}
+
+#[derive(Debug, PartialEq, PodStruct)]
+struct TestGeneric<T, U>
+where
+    U: Copy,
+{
+    t: T,
+    u: U,
+    id: TestId,
+    int: TestInt,
+}
+
+fn roundtrip<T>(value: &T) -> Vec<u8>
+where
+    T: Pod<DecodesTo = T> + std::fmt::Debug + PartialEq,
+{
+    let mut buf = [0u8; 1024];
+
+    let pod_size = value.encode(&mut buf).unwrap();
+    let (decoded, dec_size) = T::decode(&buf).unwrap();
+
+    assert_eq!(pod_size, dec_size);
+    assert_eq!(value, &decoded);
+
+    buf[..pod_size].to_vec()
+}
+
+#[test]
+fn test_derive_pod_struct_tuple() {
+    let bytes = roundtrip(&TestTuple(1, Some("test".to_string()), None));
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_struct(|b| b.push_int(1).push_string("test").push_none())
+        .build()
+        .unwrap();
+    assert_eq!(bytes, res);
+
+    roundtrip(&TestTuple(-1, None, Some(2)));
+
+    let bytes = roundtrip(&TestUnit);
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder.push_struct(|b| b).build().unwrap();
+    assert_eq!(bytes, res);
+}
+
+#[test]
+fn test_derive_pod_struct_enum() {
+    let mut buf = [0u8; 1024];
+
+    let bytes = roundtrip(&TestId::Second);
+    let builder = Builder::new(&mut buf);
+    assert_eq!(bytes, builder.push_id(Id(2u32)).build().unwrap());
+
+    let bytes = roundtrip(&TestInt::Hundred);
+    let builder = Builder::new(&mut buf);
+    assert_eq!(bytes, builder.push_int(100).build().unwrap());
+
+    roundtrip(&TestId::First);
+    roundtrip(&TestId::Tenth);
+    roundtrip(&TestInt::Zero);
+
+    // Unknown values are rejected
+    let builder = Builder::new(&mut buf);
+    let res = builder.push_id(Id(3u32)).build().unwrap().to_vec();
+    assert!(TestId::decode(&res).is_err());
+
+    // ... as is the wrong type
+    let builder = Builder::new(&mut buf);
+    let res = builder.push_int(1).build().unwrap().to_vec();
+    assert!(TestId::decode(&res).is_err());
+}
+
+#[test]
+fn test_derive_pod_struct_generic() {
+    roundtrip(&TestGeneric {
+        t: "generic".to_string(),
+        u: 1.5f64,
+        id: TestId::Tenth,
+        int: TestInt::Zero,
+    });
+
+    roundtrip(&TestGeneric {
+        t: TestTuple(2, None, None),
+        u: 3i32,
+        id: TestId::First,
+        int: TestInt::Hundred,
+    });
+}
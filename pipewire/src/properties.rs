@@ -6,6 +6,8 @@ use std::collections::HashMap;
 
 use pipewire_native_spa::atob;
 use pipewire_native_spa::dict::Dict;
+use pipewire_native_spa::param::tag::ParamTag;
+use pipewire_native_spa::param::Direction;
 use tinyjson::{JsonParseError, JsonValue};
 
 #[derive(Clone, Debug)]
@@ -42,6 +44,12 @@ impl Properties {
         Self { map }
     }
 
+    pub fn new_tag(tag: &ParamTag) -> Self {
+        Self {
+            map: tag.info.iter().cloned().collect(),
+        }
+    }
+
     pub fn new_string(args: &str) -> Result<Self, String> {
         let mut p = Self::new();
 
@@ -64,6 +72,18 @@ impl Properties {
         )
     }
 
+    // The properties as a Tag param, with the keys sorted so the param is always the same
+    pub fn tag(&self, direction: Direction) -> ParamTag {
+        let mut info = self
+            .map
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<(String, String)>>();
+        info.sort();
+
+        ParamTag { direction, info }
+    }
+
     pub fn set(&mut self, key: &str, value: String) {
         self.map.insert(key.to_string(), value);
     }
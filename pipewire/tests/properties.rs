@@ -3,6 +3,10 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
 
 use pipewire_native::properties::Properties;
+use pipewire_native_spa::param::tag::ParamTag;
+use pipewire_native_spa::param::Direction;
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::parser::Parser;
 
 #[test]
 fn test_properties_simple() {
@@ -46,3 +50,28 @@ fn test_properties_json() {
         Some(&r#"[{"factory":"spa-node-factory"}]"#.to_string())
     );
 }
+
+#[test]
+fn test_properties_tag() {
+    let mut props = Properties::new();
+    props.set("media.title", "Blue in Green".to_string());
+    props.set("media.artist", "Miles Davis".to_string());
+
+    let tag = props.tag(Direction::Output);
+    assert_eq!(
+        tag.info,
+        vec![
+            ("media.artist".to_string(), "Miles Davis".to_string()),
+            ("media.title".to_string(), "Blue in Green".to_string()),
+        ]
+    );
+
+    let mut buf = [0u8; 1024];
+    let pod = tag.build(Builder::new(&mut buf)).build().unwrap();
+    let tag = ParamTag::parse(&mut Parser::new(pod)).unwrap();
+    assert_eq!(tag.direction, Direction::Output);
+
+    let props = Properties::new_tag(&tag);
+    assert_eq!(props.get("media.title"), Some(&"Blue in Green".to_string()));
+    assert_eq!(props.get("media.artist"), Some(&"Miles Davis".to_string()));
+}
@@ -45,6 +45,12 @@ impl Dict {
 
     pub fn items(&self) -> Vec<(&str, &str)> {
         let mut ret = vec![];
+
+        // An empty dict has no items array
+        if self.items.is_null() {
+            return ret;
+        }
+
         let boxed = unsafe {
             let slice = std::slice::from_raw_parts_mut(self.items, self.n_items as usize);
             Box::from_raw(slice)
@@ -67,6 +73,11 @@ impl Dict {
 
     pub fn lookup<'a>(&'a self, key: &str) -> Option<&'a str> {
         let mut ret = None;
+
+        if self.items.is_null() {
+            return ret;
+        }
+
         let boxed = unsafe {
             let slice = std::slice::from_raw_parts_mut(self.items, self.n_items as usize);
             Box::from_raw(slice)
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Direct Stream Digital audio, following spa/param/audio/dsd.h and dsd-utils.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::format_param;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::{Id, PropertyFlags};
+use crate::pod::Error;
+
+use super::raw::{decode_position, push_position, AudioChannel, AudioFlags};
+
+// Order of the bits in each byte of DSD data
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum BitOrder {
+    #[default]
+    Unknown,
+    // Most significant bit first
+    Msb,
+    // Least significant bit first
+    Lsb,
+}
+
+// Like spa_audio_info_dsd. As with raw audio, the position has one entry for each channel unless
+// the format is unpositioned.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoDsd {
+    pub bitorder: BitOrder,
+    pub flags: AudioFlags,
+    // Number of bytes of a channel before the next channel follows, negative if the bytes of each
+    // group are in reverse order
+    pub interleave: i32,
+    // In bytes per second, not bits
+    pub rate: u32,
+    pub channels: u32,
+    pub position: Vec<AudioChannel>,
+}
+
+impl AudioInfoDsd {
+    // Write the properties of the format, as spa_format_audio_dsd_build() does. Fields that are
+    // not set are left out.
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let mut builder = builder
+            .push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Dsd),
+            );
+
+        if self.bitorder != BitOrder::Unknown {
+            builder = builder.push_property(
+                Format::AudioBitorder,
+                PropertyFlags::empty(),
+                Id(self.bitorder),
+            );
+        }
+        if self.interleave != 0 {
+            builder = builder.push_property(
+                Format::AudioInterleave,
+                PropertyFlags::empty(),
+                self.interleave,
+            );
+        }
+        if self.rate != 0 {
+            builder =
+                builder.push_property(Format::AudioRate, PropertyFlags::empty(), self.rate as i32);
+        }
+        if self.channels != 0 {
+            builder = builder.push_property(
+                Format::AudioChannels,
+                PropertyFlags::empty(),
+                self.channels as i32,
+            );
+            builder = push_position(builder, self.flags, self.channels, &self.position);
+        }
+
+        builder
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoDsd, Error> {
+        let mut info = AudioInfoDsd {
+            flags: AudioFlags::UNPOSITIONED,
+            ..AudioInfoDsd::default()
+        };
+
+        while let Some((key, _, value)) = parser.pop_property::<Format>()? {
+            match key {
+                Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Audio => {
+                    return Err(Error::Invalid)
+                }
+                Format::MediaSubtype
+                    if value.decode::<Id<MediaSubtype>>()?.0 != MediaSubtype::Dsd =>
+                {
+                    return Err(Error::Invalid)
+                }
+                Format::AudioBitorder => info.bitorder = value.decode::<Id<BitOrder>>()?.0,
+                Format::AudioInterleave => info.interleave = value.decode::<i32>()?,
+                Format::AudioRate => {
+                    info.rate = u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Format::AudioChannels => {
+                    info.channels =
+                        u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Format::AudioPosition => {
+                    info.position = decode_position(value)?;
+                    info.flags
+                        .set(AudioFlags::UNPOSITIONED, info.position.is_empty());
+                }
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoDsd);
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Compressed audio formats, following spa/param/audio/aac.h, mp3.h, wma.h, amr.h and the other
+// codec headers, along with their *-utils.h. All of them use the Encoded audio format.
+
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::{format_param, push_id, push_int};
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::{Id, PropertyFlags};
+use crate::pod::{Error, RawPod};
+
+use super::raw::AudioFormat;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum AacStreamFormat {
+    #[default]
+    Unknown,
+    // Raw AAC frames
+    Raw,
+    // ISO/IEC 13818-7 MPEG-2 Audio Data Transport Stream
+    Mp2Adts,
+    // ISO/IEC 14496-3 MPEG-4 Audio Data Transport Stream
+    Mp4Adts,
+    // ISO/IEC 14496-3 Low Overhead Audio Stream
+    Mp4Loas,
+    // ISO/IEC 14496-3 Low Overhead Audio Transport Multiplex
+    Mp4Latm,
+    // ISO/IEC 14496-3 Audio Data Interchange Format
+    Adif,
+    // ISO/IEC 14496-12 MPEG-4 file format
+    Mp4ff,
+
+    Custom = 0x10000,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum Mp3ChannelMode {
+    #[default]
+    Unknown,
+    Mono,
+    Stereo,
+    JointStereo,
+    Dual,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum WmaProfile {
+    #[default]
+    Unknown,
+    Wma7,
+    Wma8,
+    Wma9,
+    Wma10,
+    Wma9Pro,
+    Wma9Lossless,
+    Wma10Lossless,
+
+    Custom = 0x10000,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum AmrBandMode {
+    #[default]
+    Unknown,
+    // Narrow band
+    Nb,
+    // Wide band
+    Wb,
+}
+
+fn push_encoded(builder: ObjectBuilder<'_>, subtype: MediaSubtype) -> ObjectBuilder<'_> {
+    builder
+        .push_property(
+            Format::MediaType,
+            PropertyFlags::empty(),
+            Id(MediaType::Audio),
+        )
+        .push_property(Format::MediaSubtype, PropertyFlags::empty(), Id(subtype))
+        .push_property(
+            Format::AudioFormat,
+            PropertyFlags::empty(),
+            Id(AudioFormat::Encoded),
+        )
+}
+
+// Go through the properties of a fixated format of the given subtype, handing the ones that are
+// not about the media type to parse_property
+fn parse_encoded(
+    parser: &mut ObjectParser,
+    subtype: MediaSubtype,
+    mut parse_property: impl FnMut(Format, RawPod) -> Result<(), Error>,
+) -> Result<(), Error> {
+    while let Some((key, _, value)) = parser.pop_property::<Format>()? {
+        match key {
+            Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Audio => {
+                return Err(Error::Invalid)
+            }
+            Format::MediaSubtype if value.decode::<Id<MediaSubtype>>()?.0 != subtype => {
+                return Err(Error::Invalid)
+            }
+            Format::MediaType | Format::MediaSubtype | Format::AudioFormat => (),
+            key => parse_property(key, value)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_int(value: RawPod) -> Result<u32, Error> {
+    value.decode::<i32>().map(|v| v as u32)
+}
+
+// Formats that only have a rate and number of channels
+macro_rules! rate_channels_format {
+    ($($name:ident, $subtype:ident;)*) => {
+        $(
+            #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+            pub struct $name {
+                pub rate: u32,
+                pub channels: u32,
+            }
+
+            impl $name {
+                pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+                    let builder = push_encoded(builder, MediaSubtype::$subtype);
+                    let builder = push_int(builder, Format::AudioRate, self.rate);
+                    push_int(builder, Format::AudioChannels, self.channels)
+                }
+
+                pub fn parse_properties(parser: &mut ObjectParser) -> Result<$name, Error> {
+                    let mut info = $name::default();
+
+                    parse_encoded(parser, MediaSubtype::$subtype, |key, value| {
+                        match key {
+                            Format::AudioRate => info.rate = decode_int(value)?,
+                            Format::AudioChannels => info.channels = decode_int(value)?,
+                            _ => (),
+                        }
+                        Ok(())
+                    })?;
+
+                    Ok(info)
+                }
+            }
+
+            format_param!($name);
+        )*
+    };
+}
+
+rate_channels_format! {
+    AudioInfoMp3, Mp3;
+    AudioInfoOpus, Opus;
+    AudioInfoFlac, Flac;
+    AudioInfoVorbis, Vorbis;
+    AudioInfoAlac, Alac;
+    AudioInfoApe, Ape;
+    AudioInfoRa, Ra;
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoAac {
+    pub rate: u32,
+    pub channels: u32,
+    pub bitrate: u32,
+    pub stream_format: AacStreamFormat,
+}
+
+impl AudioInfoAac {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let builder = push_encoded(builder, MediaSubtype::Aac);
+        let builder = push_int(builder, Format::AudioRate, self.rate);
+        let builder = push_int(builder, Format::AudioChannels, self.channels);
+        let builder = push_int(builder, Format::AudioBitrate, self.bitrate);
+        push_id(builder, Format::AudioAacStreamFormat, self.stream_format)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoAac, Error> {
+        let mut info = AudioInfoAac::default();
+
+        parse_encoded(parser, MediaSubtype::Aac, |key, value| {
+            match key {
+                Format::AudioRate => info.rate = decode_int(value)?,
+                Format::AudioChannels => info.channels = decode_int(value)?,
+                Format::AudioBitrate => info.bitrate = decode_int(value)?,
+                Format::AudioAacStreamFormat => {
+                    info.stream_format = value.decode::<Id<AacStreamFormat>>()?.0
+                }
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoAac);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoWma {
+    pub rate: u32,
+    pub channels: u32,
+    pub bitrate: u32,
+    pub block_align: u32,
+    pub profile: WmaProfile,
+}
+
+impl AudioInfoWma {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let builder = push_encoded(builder, MediaSubtype::Wma);
+        let builder = push_int(builder, Format::AudioRate, self.rate);
+        let builder = push_int(builder, Format::AudioChannels, self.channels);
+        let builder = push_int(builder, Format::AudioBitrate, self.bitrate);
+        let builder = push_int(builder, Format::AudioBlockAlign, self.block_align);
+        push_id(builder, Format::AudioWmaProfile, self.profile)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoWma, Error> {
+        let mut info = AudioInfoWma::default();
+
+        parse_encoded(parser, MediaSubtype::Wma, |key, value| {
+            match key {
+                Format::AudioRate => info.rate = decode_int(value)?,
+                Format::AudioChannels => info.channels = decode_int(value)?,
+                Format::AudioBitrate => info.bitrate = decode_int(value)?,
+                Format::AudioBlockAlign => info.block_align = decode_int(value)?,
+                Format::AudioWmaProfile => info.profile = value.decode::<Id<WmaProfile>>()?.0,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoWma);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoAmr {
+    pub rate: u32,
+    pub channels: u32,
+    pub band_mode: AmrBandMode,
+}
+
+impl AudioInfoAmr {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let builder = push_encoded(builder, MediaSubtype::Amr);
+        let builder = push_int(builder, Format::AudioRate, self.rate);
+        let builder = push_int(builder, Format::AudioChannels, self.channels);
+        push_id(builder, Format::AudioAmrBandMode, self.band_mode)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoAmr, Error> {
+        let mut info = AudioInfoAmr::default();
+
+        parse_encoded(parser, MediaSubtype::Amr, |key, value| {
+            match key {
+                Format::AudioRate => info.rate = decode_int(value)?,
+                Format::AudioChannels => info.channels = decode_int(value)?,
+                Format::AudioAmrBandMode => info.band_mode = value.decode::<Id<AmrBandMode>>()?.0,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoAmr);
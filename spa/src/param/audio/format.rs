@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Audio formats of any subtype, following spa/param/audio/format.h and format-utils.h
+
+use crate::param::format::{Format, MediaSubtype};
+use crate::param::format_param;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::Id;
+use crate::pod::Error;
+
+use super::dsd::AudioInfoDsd;
+use super::encoded::{
+    AudioInfoAac, AudioInfoAlac, AudioInfoAmr, AudioInfoApe, AudioInfoFlac, AudioInfoMp3,
+    AudioInfoOpus, AudioInfoRa, AudioInfoVorbis, AudioInfoWma,
+};
+use super::iec958::AudioInfoIec958;
+use super::raw::AudioInfoRaw;
+
+// Like spa_audio_info, for places that take an audio format of whichever subtype, such as the
+// format of a PortConfig param
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioInfo {
+    Raw(AudioInfoRaw),
+    Dsd(AudioInfoDsd),
+    Iec958(AudioInfoIec958),
+    Mp3(AudioInfoMp3),
+    Aac(AudioInfoAac),
+    Vorbis(AudioInfoVorbis),
+    Wma(AudioInfoWma),
+    Ra(AudioInfoRa),
+    Amr(AudioInfoAmr),
+    Alac(AudioInfoAlac),
+    Flac(AudioInfoFlac),
+    Ape(AudioInfoApe),
+    Opus(AudioInfoOpus),
+}
+
+impl AudioInfo {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        match self {
+            AudioInfo::Raw(info) => info.push_properties(builder),
+            AudioInfo::Dsd(info) => info.push_properties(builder),
+            AudioInfo::Iec958(info) => info.push_properties(builder),
+            AudioInfo::Mp3(info) => info.push_properties(builder),
+            AudioInfo::Aac(info) => info.push_properties(builder),
+            AudioInfo::Vorbis(info) => info.push_properties(builder),
+            AudioInfo::Wma(info) => info.push_properties(builder),
+            AudioInfo::Ra(info) => info.push_properties(builder),
+            AudioInfo::Amr(info) => info.push_properties(builder),
+            AudioInfo::Alac(info) => info.push_properties(builder),
+            AudioInfo::Flac(info) => info.push_properties(builder),
+            AudioInfo::Ape(info) => info.push_properties(builder),
+            AudioInfo::Opus(info) => info.push_properties(builder),
+        }
+    }
+
+    // Read a fixated format with the parser of its subtype, as spa_format_audio_parse() does
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfo, Error> {
+        // The subtype is looked up ahead, the parser of the subtype then goes through all the
+        // properties and reports any that are invalid
+        let mut lookahead = parser.clone();
+        let subtype = loop {
+            match lookahead.pop_property::<Format>() {
+                Ok(Some((Format::MediaSubtype, _, value))) => {
+                    break value.decode::<Id<MediaSubtype>>().ok().map(|id| id.0)
+                }
+                Ok(Some(_)) => (),
+                Ok(None) | Err(_) => break None,
+            }
+        };
+
+        match subtype {
+            Some(MediaSubtype::Raw) => AudioInfoRaw::parse_properties(parser).map(AudioInfo::Raw),
+            Some(MediaSubtype::Dsd) => AudioInfoDsd::parse_properties(parser).map(AudioInfo::Dsd),
+            Some(MediaSubtype::Iec958) => {
+                AudioInfoIec958::parse_properties(parser).map(AudioInfo::Iec958)
+            }
+            Some(MediaSubtype::Mp3) => AudioInfoMp3::parse_properties(parser).map(AudioInfo::Mp3),
+            Some(MediaSubtype::Aac) => AudioInfoAac::parse_properties(parser).map(AudioInfo::Aac),
+            Some(MediaSubtype::Vorbis) => {
+                AudioInfoVorbis::parse_properties(parser).map(AudioInfo::Vorbis)
+            }
+            Some(MediaSubtype::Wma) => AudioInfoWma::parse_properties(parser).map(AudioInfo::Wma),
+            Some(MediaSubtype::Ra) => AudioInfoRa::parse_properties(parser).map(AudioInfo::Ra),
+            Some(MediaSubtype::Amr) => AudioInfoAmr::parse_properties(parser).map(AudioInfo::Amr),
+            Some(MediaSubtype::Alac) => {
+                AudioInfoAlac::parse_properties(parser).map(AudioInfo::Alac)
+            }
+            Some(MediaSubtype::Flac) => {
+                AudioInfoFlac::parse_properties(parser).map(AudioInfo::Flac)
+            }
+            Some(MediaSubtype::Ape) => AudioInfoApe::parse_properties(parser).map(AudioInfo::Ape),
+            Some(MediaSubtype::Opus) => {
+                AudioInfoOpus::parse_properties(parser).map(AudioInfo::Opus)
+            }
+            _ => Err(Error::Invalid),
+        }
+    }
+}
+
+format_param!(AudioInfo);
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Compressed audio passed through over S/PDIF or HDMI, following spa/param/audio/iec958.h and
+// iec958-utils.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::format_param;
+use crate::param::props::Prop;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, PropertyFlags};
+use crate::pod::{Error, RawPod};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum Iec958Codec {
+    #[default]
+    Unknown,
+    Pcm,
+    Dts,
+    Ac3,
+    // MPEG-1 or MPEG-2 (Part 3, not AAC)
+    Mpeg,
+    Mpeg2Aac,
+    Eac3,
+    TrueHd,
+    DtsHd,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoIec958 {
+    pub codec: Iec958Codec,
+    // Not used yet, always 0
+    pub flags: u32,
+    pub rate: u32,
+}
+
+impl AudioInfoIec958 {
+    // Write the properties of the format, as spa_format_audio_iec958_build() does. The codec is
+    // always written.
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let builder = builder
+            .push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Iec958),
+            )
+            .push_property(
+                Format::AudioIec958Codec,
+                PropertyFlags::empty(),
+                Id(self.codec),
+            );
+
+        if self.rate == 0 {
+            return builder;
+        }
+
+        builder.push_property(Format::AudioRate, PropertyFlags::empty(), self.rate as i32)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoIec958, Error> {
+        let mut info = AudioInfoIec958::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Format>()? {
+            match key {
+                Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Audio => {
+                    return Err(Error::Invalid)
+                }
+                Format::MediaSubtype
+                    if value.decode::<Id<MediaSubtype>>()?.0 != MediaSubtype::Iec958 =>
+                {
+                    return Err(Error::Invalid)
+                }
+                Format::AudioIec958Codec => info.codec = value.decode::<Id<Iec958Codec>>()?.0,
+                Format::AudioRate => {
+                    info.rate = u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoIec958);
+
+// The codecs that a device can pass through are set with the Iec958Codecs property of Props
+pub fn push_codecs_property<'a>(
+    builder: ObjectBuilder<'a>,
+    codecs: &[Iec958Codec],
+) -> ObjectBuilder<'a> {
+    let codecs = codecs.iter().map(|c| Id(*c)).collect::<Vec<_>>();
+
+    builder.push_property(
+        Prop::Iec958Codecs,
+        PropertyFlags::empty(),
+        codecs.as_slice(),
+    )
+}
+
+pub fn decode_codecs_property(value: RawPod) -> Result<Vec<Iec958Codec>, Error> {
+    let codecs = value.decode::<&[Id<Iec958Codec>]>()?;
+
+    Ok(codecs.into_iter().map(|c| c.0).collect())
+}
+
+// Find the codecs in a Props object, if they are set
+pub fn parse_codecs(parser: &mut Parser) -> Result<Option<Vec<Iec958Codec>>, Error> {
+    parser
+        .pop_object::<Prop, _>(|p, _| {
+            let mut codecs = None;
+
+            while let Some((key, _, value)) = p.pop_property::<Prop>()? {
+                if key == Prop::Iec958Codecs {
+                    codecs = Some(decode_codecs_property(value)?);
+                }
+            }
+
+            Ok(codecs)
+        })
+        .map(|(codecs, _)| codecs)
+}
@@ -0,0 +1,403 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Raw audio formats, following spa/param/audio/raw.h and raw-utils.h
+
+use bitflags::bitflags;
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::format_param;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::{Id, PropertyFlags};
+use crate::pod::{Error, RawPod};
+
+pub const MAX_CHANNELS: usize = 64;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum AudioFormat {
+    #[default]
+    Unknown,
+    Encoded,
+
+    StartInterleaved = 0x100,
+    S8,
+    U8,
+    S16LE,
+    S16BE,
+    U16LE,
+    U16BE,
+    S24_32LE,
+    S24_32BE,
+    U24_32LE,
+    U24_32BE,
+    S32LE,
+    S32BE,
+    U32LE,
+    U32BE,
+    S24LE,
+    S24BE,
+    U24LE,
+    U24BE,
+    S20LE,
+    S20BE,
+    U20LE,
+    U20BE,
+    S18LE,
+    S18BE,
+    U18LE,
+    U18BE,
+    F32LE,
+    F32BE,
+    F64LE,
+    F64BE,
+    Ulaw,
+    Alaw,
+
+    StartPlanar = 0x200,
+    U8P,
+    S16P,
+    S24_32P,
+    S32P,
+    S24P,
+    F32P,
+    F64P,
+    S8P,
+
+    StartOther = 0x400,
+}
+
+// Native and opposite endian aliases, like SPA_AUDIO_FORMAT_S16 and SPA_AUDIO_FORMAT_S16_OE
+macro_rules! endian_aliases {
+    ($($name:ident, $oe:ident => $le:ident, $be:ident;)*) => {
+        impl AudioFormat {
+            $(
+                pub const $name: AudioFormat = if cfg!(target_endian = "little") {
+                    AudioFormat::$le
+                } else {
+                    AudioFormat::$be
+                };
+                pub const $oe: AudioFormat = if cfg!(target_endian = "little") {
+                    AudioFormat::$be
+                } else {
+                    AudioFormat::$le
+                };
+            )*
+        }
+    };
+}
+
+endian_aliases! {
+    S16, S16_OE => S16LE, S16BE;
+    U16, U16_OE => U16LE, U16BE;
+    S24_32, S24_32_OE => S24_32LE, S24_32BE;
+    U24_32, U24_32_OE => U24_32LE, U24_32BE;
+    S32, S32_OE => S32LE, S32BE;
+    U32, U32_OE => U32LE, U32BE;
+    S24, S24_OE => S24LE, S24BE;
+    U24, U24_OE => U24LE, U24BE;
+    S20, S20_OE => S20LE, S20BE;
+    U20, U20_OE => U20LE, U20BE;
+    S18, S18_OE => S18LE, S18BE;
+    U18, U18_OE => U18LE, U18BE;
+    F32, F32_OE => F32LE, F32BE;
+    F64, F64_OE => F64LE, F64BE;
+}
+
+impl AudioFormat {
+    // The formats used for DSP processing
+    pub const DSP_S32: AudioFormat = AudioFormat::S24_32P;
+    pub const DSP_F32: AudioFormat = AudioFormat::F32P;
+    pub const DSP_F64: AudioFormat = AudioFormat::F64P;
+
+    pub fn is_interleaved(&self) -> bool {
+        (AudioFormat::StartInterleaved as u32..AudioFormat::StartPlanar as u32)
+            .contains(&(*self as u32))
+    }
+
+    pub fn is_planar(&self) -> bool {
+        (AudioFormat::StartPlanar as u32..AudioFormat::StartOther as u32).contains(&(*self as u32))
+    }
+
+    // Size of one sample in bytes, or None for formats that are not raw samples
+    pub fn sample_size(&self) -> Option<usize> {
+        use AudioFormat::*;
+
+        match self {
+            S8 | U8 | Ulaw | Alaw | U8P | S8P => Some(1),
+            S16LE | S16BE | U16LE | U16BE | S16P => Some(2),
+            // 18 and 20 bit samples are packed in 3 bytes
+            S24LE | S24BE | U24LE | U24BE | S20LE | S20BE | U20LE | U20BE | S18LE | S18BE
+            | U18LE | U18BE | S24P => Some(3),
+            S24_32LE | S24_32BE | U24_32LE | U24_32BE | S32LE | S32BE | U32LE | U32BE | F32LE
+            | F32BE | S24_32P | S32P | F32P => Some(4),
+            F64LE | F64BE | F64P => Some(8),
+            Unknown | Encoded | StartInterleaved | StartPlanar | StartOther => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum AudioChannel {
+    Unknown,
+    // Silent
+    NA,
+    Mono,
+    FL,
+    FR,
+    FC,
+    LFE,
+    SL,
+    SR,
+    FLC,
+    FRC,
+    RC,
+    RL,
+    RR,
+    TC,
+    TFL,
+    TFC,
+    TFR,
+    TRL,
+    TRC,
+    TRR,
+    RLC,
+    RRC,
+    FLW,
+    FRW,
+    LFE2,
+    FLH,
+    FCH,
+    FRH,
+    TFLC,
+    TFRC,
+    TSL,
+    TSR,
+    LLFE,
+    RLFE,
+    BC,
+    BLC,
+    BRC,
+
+    Aux0 = 0x1000,
+    Aux1,
+    Aux2,
+    Aux3,
+    Aux4,
+    Aux5,
+    Aux6,
+    Aux7,
+    Aux8,
+    Aux9,
+    Aux10,
+    Aux11,
+    Aux12,
+    Aux13,
+    Aux14,
+    Aux15,
+    Aux16,
+    Aux17,
+    Aux18,
+    Aux19,
+    Aux20,
+    Aux21,
+    Aux22,
+    Aux23,
+    Aux24,
+    Aux25,
+    Aux26,
+    Aux27,
+    Aux28,
+    Aux29,
+    Aux30,
+    Aux31,
+    Aux32,
+    Aux33,
+    Aux34,
+    Aux35,
+    Aux36,
+    Aux37,
+    Aux38,
+    Aux39,
+    Aux40,
+    Aux41,
+    Aux42,
+    Aux43,
+    Aux44,
+    Aux45,
+    Aux46,
+    Aux47,
+    Aux48,
+    Aux49,
+    Aux50,
+    Aux51,
+    Aux52,
+    Aux53,
+    Aux54,
+    Aux55,
+    Aux56,
+    Aux57,
+    Aux58,
+    Aux59,
+    Aux60,
+    Aux61,
+    Aux62,
+    Aux63,
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct AudioFlags: u32 {
+        // The channels have no position, or it is not known
+        const UNPOSITIONED = 0x0000_0001;
+    }
+}
+
+// Like spa_audio_info_raw. The position has one entry for each channel, unless the format is
+// unpositioned.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AudioInfoRaw {
+    pub format: AudioFormat,
+    pub flags: AudioFlags,
+    pub rate: u32,
+    pub channels: u32,
+    pub position: Vec<AudioChannel>,
+}
+
+// Like libspa, positions are only written if there is one for each channel, and dropped
+// otherwise. Raw and DSD audio share this.
+pub(crate) fn push_position<'a>(
+    builder: ObjectBuilder<'a>,
+    flags: AudioFlags,
+    channels: u32,
+    position: &[AudioChannel],
+) -> ObjectBuilder<'a> {
+    if flags.contains(AudioFlags::UNPOSITIONED)
+        || position.len() != channels as usize
+        || position.len() > MAX_CHANNELS
+    {
+        return builder;
+    }
+
+    let position = position.iter().map(|p| Id(*p)).collect::<Vec<_>>();
+    builder.push_property(
+        Format::AudioPosition,
+        PropertyFlags::empty(),
+        position.as_slice(),
+    )
+}
+
+// Positions we have no name for, such as Aux64 and up or custom ones, are kept as unknown rather
+// than failing the whole format
+pub(crate) fn decode_position(value: RawPod) -> Result<Vec<AudioChannel>, Error> {
+    let position = value.decode::<&[Id<u32>]>()?;
+
+    Ok(position
+        .into_iter()
+        .take(MAX_CHANNELS)
+        .map(|p| AudioChannel::try_from(p.0).unwrap_or(AudioChannel::Unknown))
+        .collect())
+}
+
+impl AudioInfoRaw {
+    // Bytes per frame in each buffer: all channels for interleaved formats, one channel for planar
+    // formats, where each channel has its own buffer
+    pub fn frame_size(&self) -> Option<usize> {
+        let sample_size = self.format.sample_size()?;
+
+        if self.format.is_planar() {
+            Some(sample_size)
+        } else {
+            Some(sample_size * self.channels as usize)
+        }
+    }
+
+    // The number of buffers (planes) that a buffer of this format has
+    pub fn n_planes(&self) -> u32 {
+        if self.format.is_planar() {
+            self.channels
+        } else {
+            1
+        }
+    }
+
+    // Write the properties of the format, as spa_format_audio_raw_build() does. Fields that are
+    // not set are left out.
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let mut builder = builder
+            .push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Raw),
+            );
+
+        if self.format != AudioFormat::Unknown {
+            builder =
+                builder.push_property(Format::AudioFormat, PropertyFlags::empty(), Id(self.format));
+        }
+        if self.rate != 0 {
+            builder =
+                builder.push_property(Format::AudioRate, PropertyFlags::empty(), self.rate as i32);
+        }
+        if self.channels != 0 {
+            builder = builder.push_property(
+                Format::AudioChannels,
+                PropertyFlags::empty(),
+                self.channels as i32,
+            );
+
+            builder = push_position(builder, self.flags, self.channels, &self.position);
+        }
+
+        builder
+    }
+
+    // Read a fixated format, as spa_format_audio_raw_parse() does. Without a position, the format
+    // is marked as unpositioned.
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<AudioInfoRaw, Error> {
+        let mut info = AudioInfoRaw {
+            flags: AudioFlags::UNPOSITIONED,
+            ..AudioInfoRaw::default()
+        };
+
+        while let Some((key, _, value)) = parser.pop_property::<Format>()? {
+            match key {
+                Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Audio => {
+                    return Err(Error::Invalid)
+                }
+                Format::MediaSubtype
+                    if value.decode::<Id<MediaSubtype>>()?.0 != MediaSubtype::Raw =>
+                {
+                    return Err(Error::Invalid)
+                }
+                Format::AudioFormat => info.format = value.decode::<Id<AudioFormat>>()?.0,
+                Format::AudioRate => {
+                    info.rate = u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Format::AudioChannels => {
+                    info.channels =
+                        u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Format::AudioPosition => {
+                    info.position = decode_position(value)?;
+                    info.flags
+                        .set(AudioFlags::UNPOSITIONED, info.position.is_empty());
+                }
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+format_param!(AudioInfoRaw);
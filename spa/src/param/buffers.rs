@@ -2,11 +2,15 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
 // SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
 
+use bitflags::bitflags;
 use pipewire_native_macros::EnumU32;
 
-use crate::pod::types::ObjectType;
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Choice, Id, ObjectType, PropertyFlags, Type};
+use crate::pod::{read_choice_header, read_u32, Error, RawPod};
 
-use super::ParamObject;
+use super::{push_int, push_int_choice, ParamObject, ParamType};
 
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
@@ -48,3 +52,244 @@ pub enum Io {
 impl ParamObject for Io {
     const TYPE: ObjectType = ObjectType::ParamIo;
 }
+
+// Memory types that buffer data can be in, from spa/buffer/buffer.h. The DataType property of the
+// Buffers param is a mask of 1 << spa_data_type for each of the types that are supported.
+bitflags! {
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct DataType: u32 {
+        const MEM_PTR = 1 << 1;
+        const MEM_FD = 1 << 2;
+        const DMA_BUF = 1 << 3;
+        const MEM_ID = 1 << 4;
+        const SYNC_OBJ = 1 << 5;
+    }
+}
+
+// spa/buffer/meta.h: SPA_META_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum MetaType {
+    #[default]
+    Invalid,
+    Header,
+    VideoCrop,
+    VideoDamage,
+    Bitmap,
+    Cursor,
+    Control,
+    Busy,
+    VideoTransform,
+    SyncTimeline,
+}
+
+// spa/node/io.h: SPA_IO_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum IoType {
+    #[default]
+    Invalid,
+    Buffers,
+    Range,
+    Clock,
+    Latency,
+    Control,
+    Notify,
+    Position,
+    RateMatch,
+    Memory,
+    AsyncBuffers,
+}
+
+// Fixated values can still come wrapped in a choice, in which case the first value is used. As
+// with spa_pod_get_values(), only the header of the choice is checked, as choices don't always
+// have all the values their type calls for: SPA_POD_CHOICE_FLAGS_Int() only has the mask.
+fn decode_int(value: RawPod) -> Result<u32, Error> {
+    if value.type_() != Type::Choice {
+        return value.decode::<i32>().map(|v| v as u32);
+    }
+
+    read_choice_header(value.as_bytes(), Type::Int, 4)
+        .and_then(|(_, values, _)| read_u32(values, 0))
+        .map_err(|e| e.at(0, None))
+}
+
+// Like the Buffers param that a node puts together with spa_pod_builder_add_object(). Each count
+// can be offered as a range or list of values with BuffersChoices, and fields that are zero are
+// left out.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParamBuffers {
+    pub buffers: u32,
+    pub blocks: u32,
+    pub size: u32,
+    pub stride: u32,
+    pub align: u32,
+    pub data_type: DataType,
+    // A mask of 1 << MetaType for each kind of metadata that the buffers must carry
+    pub meta_type: u32,
+}
+
+// Values to offer instead of the fixed ones in ParamBuffers
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BuffersChoices {
+    pub buffers: Option<Choice<i32>>,
+    pub blocks: Option<Choice<i32>>,
+    pub size: Option<Choice<i32>>,
+    pub stride: Option<Choice<i32>>,
+    pub align: Option<Choice<i32>>,
+}
+
+impl ParamBuffers {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        self.push_choice_properties(builder, &BuffersChoices::default())
+    }
+
+    pub fn push_choice_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &BuffersChoices,
+    ) -> ObjectBuilder<'a> {
+        let builder = push_int_choice(
+            builder,
+            Buffers::Buffers,
+            self.buffers,
+            choices.buffers.as_ref(),
+        );
+        let builder = push_int_choice(
+            builder,
+            Buffers::Blocks,
+            self.blocks,
+            choices.blocks.as_ref(),
+        );
+        let builder = push_int_choice(builder, Buffers::Size, self.size, choices.size.as_ref());
+        let builder = push_int_choice(
+            builder,
+            Buffers::Stride,
+            self.stride,
+            choices.stride.as_ref(),
+        );
+        let builder = push_int_choice(builder, Buffers::Align, self.align, choices.align.as_ref());
+
+        let builder = push_int(builder, Buffers::DataType, self.data_type.bits());
+        push_int(builder, Buffers::MetaType, self.meta_type)
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        self.build_choices(builder, &BuffersChoices::default())
+    }
+
+    pub fn build_choices<'a>(&self, builder: Builder<'a>, choices: &BuffersChoices) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamBuffers, ParamType::Buffers, |b| {
+            self.push_choice_properties(b, choices)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamBuffers, Error> {
+        let mut param = ParamBuffers::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Buffers>()? {
+            match key {
+                Buffers::Buffers => param.buffers = decode_int(value)?,
+                Buffers::Blocks => param.blocks = decode_int(value)?,
+                Buffers::Size => param.size = decode_int(value)?,
+                Buffers::Stride => param.stride = decode_int(value)?,
+                Buffers::Align => param.align = decode_int(value)?,
+                Buffers::DataType => {
+                    param.data_type = DataType::from_bits_retain(decode_int(value)?)
+                }
+                Buffers::MetaType => param.meta_type = decode_int(value)?,
+                Buffers::Start => (),
+            }
+        }
+
+        Ok(param)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamBuffers, Error> {
+        parser
+            .pop_object::<Buffers, _>(|p, _| ParamBuffers::parse_properties(p))
+            .map(|(param, _)| param)
+    }
+}
+
+// A kind of metadata to add to each buffer, and its size
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParamMeta {
+    pub type_: MetaType,
+    pub size: u32,
+}
+
+impl ParamMeta {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        builder
+            .push_property(Meta::Type, PropertyFlags::empty(), Id(self.type_))
+            .push_property(Meta::Size, PropertyFlags::empty(), self.size as i32)
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamMeta, ParamType::Meta, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamMeta, Error> {
+        let mut param = ParamMeta::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Meta>()? {
+            match key {
+                Meta::Type => param.type_ = value.decode::<Id<MetaType>>()?.0,
+                Meta::Size => param.size = decode_int(value)?,
+                _ => (),
+            }
+        }
+
+        Ok(param)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamMeta, Error> {
+        parser
+            .pop_object::<Meta, _>(|p, _| ParamMeta::parse_properties(p))
+            .map(|(param, _)| param)
+    }
+}
+
+// An IO area that the node can be given, and its size
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParamIo {
+    pub id: IoType,
+    pub size: u32,
+}
+
+impl ParamIo {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        builder
+            .push_property(Io::Id, PropertyFlags::empty(), Id(self.id))
+            .push_property(Io::Size, PropertyFlags::empty(), self.size as i32)
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamIo, ParamType::IO, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamIo, Error> {
+        let mut param = ParamIo::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Io>()? {
+            match key {
+                Io::Id => param.id = value.decode::<Id<IoType>>()?.0,
+                Io::Size => param.size = decode_int(value)?,
+                _ => (),
+            }
+        }
+
+        Ok(param)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamIo, Error> {
+        parser
+            .pop_object::<Io, _>(|p, _| ParamIo::parse_properties(p))
+            .map(|(param, _)| param)
+    }
+}
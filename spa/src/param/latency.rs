@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Latency reporting, following spa/param/latency.h and latency-utils.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, ObjectType, PropertyFlags};
+use crate::pod::Error;
+
+use super::{Direction, ParamObject, ParamType};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum Latency {
+    Start,
+    Direction,
+    MinQuantum,
+    MaxQuantum,
+    MinRate,
+    MaxRate,
+    MinNs,
+    MaxNs,
+}
+
+impl ParamObject for Latency {
+    const TYPE: ObjectType = ObjectType::ParamLatency;
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum ProcessLatency {
+    Start,
+    Quantum,
+    Rate,
+    Ns,
+}
+
+impl ParamObject for ProcessLatency {
+    const TYPE: ObjectType = ObjectType::ParamProcessLatency;
+}
+
+// Like spa_latency_info. The latency is the sum of a part relative to the quantum, one in samples
+// at the graph rate, and one in nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LatencyInfo {
+    pub direction: Direction,
+    pub min_quantum: f32,
+    pub max_quantum: f32,
+    pub min_rate: u32,
+    pub max_rate: u32,
+    pub min_ns: u64,
+    pub max_ns: u64,
+}
+
+impl LatencyInfo {
+    pub fn new(direction: Direction) -> LatencyInfo {
+        LatencyInfo {
+            direction,
+            ..LatencyInfo::default()
+        }
+    }
+
+    // The starting point for combining the latencies of several ports, as
+    // spa_latency_info_combine_start() does. The minimums start out as large as possible, so
+    // combine_finish() must be called once all ports are combined.
+    pub fn combine_start(direction: Direction) -> LatencyInfo {
+        LatencyInfo {
+            direction,
+            min_quantum: f32::MAX,
+            max_quantum: 0.0,
+            min_rate: u32::MAX,
+            max_rate: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+        }
+    }
+
+    // Widen the range to include the other latency, which must be in the same direction
+    pub fn combine(&mut self, other: &LatencyInfo) -> Result<(), Error> {
+        if self.direction != other.direction {
+            return Err(Error::Invalid);
+        }
+
+        self.min_quantum = self.min_quantum.min(other.min_quantum);
+        self.max_quantum = self.max_quantum.max(other.max_quantum);
+        self.min_rate = self.min_rate.min(other.min_rate);
+        self.max_rate = self.max_rate.max(other.max_rate);
+        self.min_ns = self.min_ns.min(other.min_ns);
+        self.max_ns = self.max_ns.max(other.max_ns);
+
+        Ok(())
+    }
+
+    // Reset the minimums that nothing was combined into
+    pub fn combine_finish(&mut self) {
+        if self.min_quantum == f32::MAX {
+            self.min_quantum = 0.0;
+        }
+        if self.min_rate == u32::MAX {
+            self.min_rate = 0;
+        }
+        if self.min_ns == u64::MAX {
+            self.min_ns = 0;
+        }
+    }
+
+    // Write all of the properties, as spa_latency_build() does
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+
+        builder
+            .push_property(Latency::Direction, empty, Id(self.direction))
+            .push_property(Latency::MinQuantum, empty, self.min_quantum)
+            .push_property(Latency::MaxQuantum, empty, self.max_quantum)
+            .push_property(Latency::MinRate, empty, self.min_rate as i32)
+            .push_property(Latency::MaxRate, empty, self.max_rate as i32)
+            .push_property(Latency::MinNs, empty, self.min_ns as i64)
+            .push_property(Latency::MaxNs, empty, self.max_ns as i64)
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamLatency, ParamType::Latency, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    // The direction must be set, everything else is optional
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<LatencyInfo, Error> {
+        let mut info = LatencyInfo::default();
+        let mut direction = None;
+
+        while let Some((key, _, value)) = parser.pop_property::<Latency>()? {
+            match key {
+                Latency::Direction => {
+                    let id = value.decode::<Id<u32>>()?.0;
+                    direction = Direction::try_from(id & 1).ok();
+                }
+                Latency::MinQuantum => info.min_quantum = value.decode::<f32>()?,
+                Latency::MaxQuantum => info.max_quantum = value.decode::<f32>()?,
+                Latency::MinRate => {
+                    info.min_rate =
+                        u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Latency::MaxRate => {
+                    info.max_rate =
+                        u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                Latency::MinNs => info.min_ns = value.decode::<i64>()? as u64,
+                Latency::MaxNs => info.max_ns = value.decode::<i64>()? as u64,
+                _ => (),
+            }
+        }
+
+        info.direction = direction.ok_or(Error::Invalid)?;
+
+        Ok(info)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<LatencyInfo, Error> {
+        parser
+            .pop_object::<Latency, _>(|p, _| LatencyInfo::parse_properties(p))
+            .map(|(info, _)| info)
+    }
+}
+
+// Like spa_process_latency_info, the latency that a node adds between its inputs and outputs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProcessLatencyInfo {
+    pub quantum: f32,
+    pub rate: u32,
+    pub ns: u64,
+}
+
+impl ProcessLatencyInfo {
+    // Add the processing latency to the latency of the other side of the node, as
+    // spa_process_latency_info_add() does
+    pub fn add(&self, info: &mut LatencyInfo) {
+        info.min_quantum += self.quantum;
+        info.max_quantum += self.quantum;
+        info.min_rate = info.min_rate.wrapping_add(self.rate);
+        info.max_rate = info.max_rate.wrapping_add(self.rate);
+        info.min_ns = info.min_ns.wrapping_add(self.ns);
+        info.max_ns = info.max_ns.wrapping_add(self.ns);
+    }
+
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+
+        builder
+            .push_property(ProcessLatency::Quantum, empty, self.quantum)
+            .push_property(ProcessLatency::Rate, empty, self.rate as i32)
+            .push_property(ProcessLatency::Ns, empty, self.ns as i64)
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(
+            ObjectType::ParamProcessLatency,
+            ParamType::ProcessLatency,
+            |b| self.push_properties(b),
+        )
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ProcessLatencyInfo, Error> {
+        let mut info = ProcessLatencyInfo::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<ProcessLatency>()? {
+            match key {
+                ProcessLatency::Quantum => info.quantum = value.decode::<f32>()?,
+                ProcessLatency::Rate => {
+                    info.rate = u32::try_from(value.decode::<i32>()?).map_err(|_| Error::Invalid)?
+                }
+                ProcessLatency::Ns => info.ns = value.decode::<i64>()? as u64,
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ProcessLatencyInfo, Error> {
+        parser
+            .pop_object::<ProcessLatency, _>(|p, _| ProcessLatencyInfo::parse_properties(p))
+            .map(|(info, _)| info)
+    }
+}
@@ -4,11 +4,21 @@
 
 use pipewire_native_macros::EnumU32;
 
-use crate::pod::types::ObjectType;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::types::{Choice, Id, ObjectType, PropertyFlags};
+use crate::pod::value::Value;
+use crate::pod::{Error, RawPod};
 
+pub mod audio;
 pub mod buffers;
 pub mod format;
+pub mod latency;
+pub mod port_config;
+pub mod profile;
 pub mod props;
+pub mod route;
+pub mod tag;
+pub mod video;
 
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
@@ -33,6 +43,221 @@ pub enum ParamType {
     Tag,
 }
 
+impl ParamType {
+    // The type of object used for params with this id
+    pub fn object_type(&self) -> Option<ObjectType> {
+        match self {
+            ParamType::PropInfo => Some(ObjectType::PropInfo),
+            ParamType::Props => Some(ObjectType::Props),
+            ParamType::EnumFormat | ParamType::Format => Some(ObjectType::Format),
+            ParamType::Buffers => Some(ObjectType::ParamBuffers),
+            ParamType::Meta => Some(ObjectType::ParamMeta),
+            ParamType::IO => Some(ObjectType::ParamIo),
+            ParamType::EnumProfile | ParamType::Profile => Some(ObjectType::ParamProfile),
+            ParamType::EnumPortConfig | ParamType::PortConfig => Some(ObjectType::ParamPortConfig),
+            ParamType::EnumRoute | ParamType::Route => Some(ObjectType::ParamRoute),
+            ParamType::Latency => Some(ObjectType::ParamLatency),
+            ParamType::ProcessLatency => Some(ObjectType::ParamProcessLatency),
+            ParamType::Tag => Some(ObjectType::ParamTag),
+            ParamType::Invalid | ParamType::Control => None,
+        }
+    }
+}
+
+// spa/utils/defs.h: SPA_DIRECTION_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum Direction {
+    #[default]
+    Input,
+    Output,
+}
+
+// spa/param/param.h: SPA_PARAM_AVAILABILITY_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum Availability {
+    #[default]
+    Unknown,
+    No,
+    Yes,
+}
+
+// Strings that are empty are left out
+pub(crate) fn push_string<'a, K>(
+    builder: ObjectBuilder<'a>,
+    key: K,
+    value: &str,
+) -> ObjectBuilder<'a>
+where
+    K: Copy + Into<u32> + TryFrom<u32>,
+{
+    if value.is_empty() {
+        return builder;
+    }
+
+    builder.push_property(key, PropertyFlags::empty(), value)
+}
+
+// Integers that are zero are not set, and are left out
+pub(crate) fn push_int<K>(builder: ObjectBuilder<'_>, key: K, value: u32) -> ObjectBuilder<'_>
+where
+    K: Copy + Into<u32> + TryFrom<u32>,
+{
+    push_int_choice(builder, key, value, None)
+}
+
+// The same, but a choice to offer instead of the value is always set
+pub(crate) fn push_int_choice<'a, K>(
+    builder: ObjectBuilder<'a>,
+    key: K,
+    value: u32,
+    choice: Option<&Choice<i32>>,
+) -> ObjectBuilder<'a>
+where
+    K: Copy + Into<u32> + TryFrom<u32>,
+{
+    match choice {
+        Some(choice) => builder.push_property(key, PropertyFlags::empty(), choice.clone()),
+        None if value != 0 => builder.push_property(key, PropertyFlags::empty(), value as i32),
+        None => builder,
+    }
+}
+
+// Ids that are zero (unknown) are not set, and are left out
+pub(crate) fn push_id<K, T>(builder: ObjectBuilder<'_>, key: K, value: T) -> ObjectBuilder<'_>
+where
+    K: Copy + Into<u32> + TryFrom<u32>,
+    T: Into<u32> + TryFrom<u32> + Copy,
+{
+    if value.into() == 0 {
+        return builder;
+    }
+
+    builder.push_property(key, PropertyFlags::empty(), Id(value))
+}
+
+// The build and parse functions of a format, given its push_properties() and parse_properties().
+// Formats that can offer choices take them as a second argument, and have push_enum_properties()
+// instead of push_properties().
+macro_rules! format_param {
+    ($name:ident) => {
+        impl $name {
+            pub fn build_enum_format<'a>(
+                &self,
+                builder: $crate::pod::builder::Builder<'a>,
+            ) -> $crate::pod::builder::Builder<'a> {
+                builder.push_object(
+                    $crate::pod::types::ObjectType::Format,
+                    $crate::param::ParamType::EnumFormat,
+                    |b| self.push_properties(b),
+                )
+            }
+
+            pub fn build_format<'a>(
+                &self,
+                builder: $crate::pod::builder::Builder<'a>,
+            ) -> $crate::pod::builder::Builder<'a> {
+                builder.push_object(
+                    $crate::pod::types::ObjectType::Format,
+                    $crate::param::ParamType::Format,
+                    |b| self.push_properties(b),
+                )
+            }
+
+            pub fn parse(
+                parser: &mut $crate::pod::parser::Parser,
+            ) -> Result<$name, $crate::pod::Error> {
+                parser
+                    .pop_object::<$crate::param::format::Format, _>(|p, _| {
+                        $name::parse_properties(p)
+                    })
+                    .map(|(info, _)| info)
+            }
+        }
+    };
+    ($name:ident, $choices:ty) => {
+        impl $name {
+            pub fn push_properties<'a>(
+                &self,
+                builder: $crate::pod::builder::ObjectBuilder<'a>,
+            ) -> $crate::pod::builder::ObjectBuilder<'a> {
+                self.push_enum_properties(builder, &<$choices>::default())
+            }
+
+            pub fn build_enum_format<'a>(
+                &self,
+                builder: $crate::pod::builder::Builder<'a>,
+                choices: &$choices,
+            ) -> $crate::pod::builder::Builder<'a> {
+                builder.push_object(
+                    $crate::pod::types::ObjectType::Format,
+                    $crate::param::ParamType::EnumFormat,
+                    |b| self.push_enum_properties(b, choices),
+                )
+            }
+
+            pub fn build_format<'a>(
+                &self,
+                builder: $crate::pod::builder::Builder<'a>,
+            ) -> $crate::pod::builder::Builder<'a> {
+                builder.push_object(
+                    $crate::pod::types::ObjectType::Format,
+                    $crate::param::ParamType::Format,
+                    |b| self.push_properties(b),
+                )
+            }
+
+            pub fn parse(
+                parser: &mut $crate::pod::parser::Parser,
+            ) -> Result<$name, $crate::pod::Error> {
+                parser
+                    .pop_object::<$crate::param::format::Format, _>(|p, _| {
+                        $name::parse_properties(p)
+                    })
+                    .map(|(info, _)| info)
+            }
+        }
+    };
+}
+
+pub(crate) use format_param;
+
+// Extra key/value information is stored as Struct(Int: n_items, (String: key, String: value)*)
+pub(crate) fn info_value(info: &[(String, String)]) -> Value {
+    let mut items = vec![Value::Int(info.len() as i32)];
+
+    for (key, value) in info {
+        items.push(Value::String(key.clone()));
+        items.push(Value::String(value.clone()));
+    }
+
+    Value::Struct(items)
+}
+
+pub(crate) fn parse_info(value: RawPod) -> Result<Vec<(String, String)>, Error> {
+    let Value::Struct(items) = value.decode::<Value>()? else {
+        return Err(Error::Invalid);
+    };
+    let Some((Value::Int(n_items), items)) = items.split_first() else {
+        return Err(Error::Invalid);
+    };
+
+    // The count must match the pairs that follow it
+    let n_items = usize::try_from(*n_items).map_err(|_| Error::Invalid)?;
+    if items.len() % 2 != 0 || items.len() / 2 != n_items {
+        return Err(Error::Invalid);
+    }
+
+    items
+        .chunks_exact(2)
+        .map(|item| match item {
+            [Value::String(key), Value::String(value)] => Ok((key.clone(), value.clone())),
+            _ => Err(Error::Invalid),
+        })
+        .collect()
+}
+
 pub trait ParamObject {
     const TYPE: ObjectType;
 }
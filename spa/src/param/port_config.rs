@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Port configuration of adapter nodes, following spa/param/port-config.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, ObjectType, PropertyFlags};
+use crate::pod::Error;
+
+use super::audio::format::AudioInfo;
+use super::{Direction, ParamObject, ParamType};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum PortConfig {
+    Start,
+    Direction,
+    Mode,
+    Monitor,
+    Control,
+    Format,
+}
+
+impl ParamObject for PortConfig {
+    const TYPE: ObjectType = ObjectType::ParamPortConfig;
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum PortConfigMode {
+    #[default]
+    None,
+    // The ports of the follower node are exposed as they are
+    Passthrough,
+    // A single port that converts to and from the format of the follower
+    Convert,
+    // One port for each channel of the format, with the DSP sample format
+    Dsp,
+}
+
+// Like the PortConfig param that pw-cli or the session manager set on adapter nodes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamPortConfig {
+    pub direction: Direction,
+    pub mode: PortConfigMode,
+    // Add monitor output ports for the input ports
+    pub monitor: bool,
+    // Add a control port
+    pub control: bool,
+    // The format that the ports are configured for: raw audio in Convert or Dsp mode, and any
+    // audio format, such as IEC958 or DSD, in Passthrough mode
+    pub format: Option<AudioInfo>,
+}
+
+impl ParamPortConfig {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+
+        let builder = builder
+            .push_property(PortConfig::Direction, empty, Id(self.direction))
+            .push_property(PortConfig::Mode, empty, Id(self.mode))
+            .push_property(PortConfig::Monitor, empty, self.monitor)
+            .push_property(PortConfig::Control, empty, self.control);
+
+        match &self.format {
+            Some(format) => builder.push_object_property(
+                PortConfig::Format,
+                empty,
+                ObjectType::Format,
+                ParamType::Format,
+                |b| format.push_properties(b),
+            ),
+            None => builder,
+        }
+    }
+
+    pub fn build_enum_port_config<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(
+            ObjectType::ParamPortConfig,
+            ParamType::EnumPortConfig,
+            |b| self.push_properties(b),
+        )
+    }
+
+    pub fn build_port_config<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamPortConfig, ParamType::PortConfig, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamPortConfig, Error> {
+        let mut config = ParamPortConfig::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<PortConfig>()? {
+            match key {
+                PortConfig::Direction => config.direction = value.decode::<Id<Direction>>()?.0,
+                PortConfig::Mode => config.mode = value.decode::<Id<PortConfigMode>>()?.0,
+                PortConfig::Monitor => config.monitor = value.decode::<bool>()?,
+                PortConfig::Control => config.control = value.decode::<bool>()?,
+                PortConfig::Format => {
+                    config.format = Some(AudioInfo::parse(&mut Parser::new(value.as_bytes()))?)
+                }
+                PortConfig::Start => (),
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamPortConfig, Error> {
+        parser
+            .pop_object::<PortConfig, _>(|p, _| ParamPortConfig::parse_properties(p))
+            .map(|(config, _)| config)
+    }
+}
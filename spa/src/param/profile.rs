@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Profiles of a device, following spa/param/profile.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, ObjectType, PropertyFlags, Type};
+use crate::pod::value::{ArrayValue, Value};
+use crate::pod::{Error, RawPod};
+
+use super::{info_value, parse_info, push_string, Availability, ParamObject, ParamType};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum Profile {
+    Start,
+    Index,
+    Name,
+    Description,
+    Priority,
+    Available,
+    Info,
+    Classes,
+    Save,
+}
+
+impl ParamObject for Profile {
+    const TYPE: ObjectType = ObjectType::ParamProfile;
+}
+
+// The nodes of one media class that a profile provides, such as "Audio/Sink". The devices are
+// listed under the given property, usually "card.profile.devices", when it is set.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProfileClass {
+    pub name: String,
+    pub n_nodes: u32,
+    pub property: String,
+    pub devices: Vec<u32>,
+}
+
+// A profile as listed in EnumProfile, or the active one in Profile. To switch profiles, set a
+// Profile with just the index. Strings and lists that are empty are left out.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParamProfile {
+    pub index: u32,
+    pub name: String,
+    pub description: String,
+    pub priority: u32,
+    pub available: Availability,
+    pub info: Vec<(String, String)>,
+    pub classes: Vec<ProfileClass>,
+    pub save: bool,
+}
+
+// Classes are stored as Struct(Int: n_items, Struct(String: name, Int: n_nodes,
+// [String: property, Array of Int: devices])*)
+fn classes_value(classes: &[ProfileClass]) -> Value {
+    let mut items = vec![Value::Int(classes.len() as i32)];
+
+    for class in classes {
+        let mut fields = vec![
+            Value::String(class.name.clone()),
+            Value::Int(class.n_nodes as i32),
+        ];
+        if !class.property.is_empty() {
+            fields.push(Value::String(class.property.clone()));
+            fields.push(Value::Array(ArrayValue {
+                child_type: Type::Int,
+                values: class
+                    .devices
+                    .iter()
+                    .map(|d| Value::Int(*d as i32))
+                    .collect(),
+            }));
+        }
+        items.push(Value::Struct(fields));
+    }
+
+    Value::Struct(items)
+}
+
+fn parse_class(value: &Value) -> Result<ProfileClass, Error> {
+    let Value::Struct(fields) = value else {
+        return Err(Error::Invalid);
+    };
+
+    match fields.as_slice() {
+        [Value::String(name), Value::Int(n_nodes)] => Ok(ProfileClass {
+            name: name.clone(),
+            n_nodes: *n_nodes as u32,
+            ..ProfileClass::default()
+        }),
+        [Value::String(name), Value::Int(n_nodes), Value::String(property), Value::Array(devices), ..] => {
+            Ok(ProfileClass {
+                name: name.clone(),
+                n_nodes: *n_nodes as u32,
+                property: property.clone(),
+                devices: devices
+                    .values
+                    .iter()
+                    .map(|d| match d {
+                        Value::Int(d) => Ok(*d as u32),
+                        _ => Err(Error::Invalid),
+                    })
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn parse_classes(value: RawPod) -> Result<Vec<ProfileClass>, Error> {
+    let Value::Struct(items) = value.decode::<Value>()? else {
+        return Err(Error::Invalid);
+    };
+    let Some((Value::Int(n_items), items)) = items.split_first() else {
+        return Err(Error::Invalid);
+    };
+
+    items
+        .iter()
+        .take(*n_items as usize)
+        .map(parse_class)
+        .collect()
+}
+
+impl ParamProfile {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+
+        let builder = builder.push_property(Profile::Index, empty, self.index as i32);
+        let builder = push_string(builder, Profile::Name, &self.name);
+        let mut builder = push_string(builder, Profile::Description, &self.description)
+            .push_property(Profile::Priority, empty, self.priority as i32)
+            .push_property(Profile::Available, empty, Id(self.available));
+        if !self.info.is_empty() {
+            builder = builder.push_property(Profile::Info, empty, info_value(&self.info));
+        }
+        if !self.classes.is_empty() {
+            builder = builder.push_property(Profile::Classes, empty, classes_value(&self.classes));
+        }
+
+        builder.push_property(Profile::Save, empty, self.save)
+    }
+
+    pub fn build_enum_profile<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamProfile, ParamType::EnumProfile, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn build_profile<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamProfile, ParamType::Profile, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamProfile, Error> {
+        let mut profile = ParamProfile::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Profile>()? {
+            match key {
+                Profile::Index => profile.index = value.decode::<i32>()? as u32,
+                Profile::Name => profile.name = value.decode::<String>()?,
+                Profile::Description => profile.description = value.decode::<String>()?,
+                Profile::Priority => profile.priority = value.decode::<i32>()? as u32,
+                Profile::Available => profile.available = value.decode::<Id<Availability>>()?.0,
+                Profile::Info => profile.info = parse_info(value)?,
+                Profile::Classes => profile.classes = parse_classes(value)?,
+                Profile::Save => profile.save = value.decode::<bool>()?,
+                Profile::Start => (),
+            }
+        }
+
+        Ok(profile)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamProfile, Error> {
+        parser
+            .pop_object::<Profile, _>(|p, _| ParamProfile::parse_properties(p))
+            .map(|(profile, _)| profile)
+    }
+}
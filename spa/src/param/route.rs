@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Routes of a device, such as speakers or headphones, following spa/param/route.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, ObjectType, PropertyFlags};
+use crate::pod::value::{ObjectValue, Value};
+use crate::pod::{Error, RawPod};
+
+use super::{info_value, parse_info, push_string, Availability, Direction, ParamObject, ParamType};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum Route {
+    Start,
+    Index,
+    Direction,
+    Device,
+    Name,
+    Description,
+    Priority,
+    Available,
+    Info,
+    Profiles,
+    Props,
+    Devices,
+    Profile,
+    Save,
+}
+
+impl ParamObject for Route {
+    const TYPE: ObjectType = ObjectType::ParamRoute;
+}
+
+// A route as listed in EnumRoute, or the active one for a device in Route. To switch routes, set a
+// Route with the index, the device and optionally the props to apply. Strings and lists that are
+// empty are left out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamRoute {
+    pub index: u32,
+    pub direction: Direction,
+    // The device the route is active on, only set in Route
+    pub device: Option<u32>,
+    pub name: String,
+    pub description: String,
+    pub priority: u32,
+    pub available: Availability,
+    pub info: Vec<(String, String)>,
+    // The profiles and devices that the route can be used with
+    pub profiles: Vec<u32>,
+    pub devices: Vec<u32>,
+    // A Props object, with the volumes and mute state of the route
+    pub props: Option<ObjectValue>,
+    pub profile: Option<u32>,
+    pub save: bool,
+}
+
+fn push_indexes<'a, K>(builder: ObjectBuilder<'a>, key: K, indexes: &[u32]) -> ObjectBuilder<'a>
+where
+    K: Copy + Into<u32> + TryFrom<u32>,
+{
+    if indexes.is_empty() {
+        return builder;
+    }
+
+    let indexes = indexes.iter().map(|i| *i as i32).collect::<Vec<_>>();
+    builder.push_property(key, PropertyFlags::empty(), indexes.as_slice())
+}
+
+fn decode_indexes(value: RawPod) -> Result<Vec<u32>, Error> {
+    let indexes = value.decode::<&[i32]>()?;
+
+    Ok(indexes.into_iter().map(|i| i as u32).collect())
+}
+
+impl ParamRoute {
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+
+        let mut builder = builder
+            .push_property(Route::Index, empty, self.index as i32)
+            .push_property(Route::Direction, empty, Id(self.direction));
+        if let Some(device) = self.device {
+            builder = builder.push_property(Route::Device, empty, device as i32);
+        }
+
+        let builder = push_string(builder, Route::Name, &self.name);
+        let mut builder = push_string(builder, Route::Description, &self.description)
+            .push_property(Route::Priority, empty, self.priority as i32)
+            .push_property(Route::Available, empty, Id(self.available));
+        if !self.info.is_empty() {
+            builder = builder.push_property(Route::Info, empty, info_value(&self.info));
+        }
+
+        let mut builder = push_indexes(builder, Route::Profiles, &self.profiles);
+        if let Some(props) = &self.props {
+            builder = builder.push_property(Route::Props, empty, Value::Object(props.clone()));
+        }
+
+        let mut builder = push_indexes(builder, Route::Devices, &self.devices);
+        if let Some(profile) = self.profile {
+            builder = builder.push_property(Route::Profile, empty, profile as i32);
+        }
+
+        builder.push_property(Route::Save, empty, self.save)
+    }
+
+    pub fn build_enum_route<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamRoute, ParamType::EnumRoute, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn build_route<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamRoute, ParamType::Route, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamRoute, Error> {
+        let mut route = ParamRoute::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Route>()? {
+            match key {
+                Route::Index => route.index = value.decode::<i32>()? as u32,
+                Route::Direction => route.direction = value.decode::<Id<Direction>>()?.0,
+                Route::Device => route.device = Some(value.decode::<i32>()? as u32),
+                Route::Name => route.name = value.decode::<String>()?,
+                Route::Description => route.description = value.decode::<String>()?,
+                Route::Priority => route.priority = value.decode::<i32>()? as u32,
+                Route::Available => route.available = value.decode::<Id<Availability>>()?.0,
+                Route::Info => route.info = parse_info(value)?,
+                Route::Profiles => route.profiles = decode_indexes(value)?,
+                Route::Props => match value.decode::<Value>()? {
+                    Value::Object(props) if props.type_ == ObjectType::Props as u32 => {
+                        route.props = Some(props)
+                    }
+                    _ => return Err(Error::Invalid),
+                },
+                Route::Devices => route.devices = decode_indexes(value)?,
+                Route::Profile => route.profile = Some(value.decode::<i32>()? as u32),
+                Route::Save => route.save = value.decode::<bool>()?,
+                Route::Start => (),
+            }
+        }
+
+        Ok(route)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamRoute, Error> {
+        parser
+            .pop_object::<Route, _>(|p, _| ParamRoute::parse_properties(p))
+            .map(|(route, _)| route)
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Stream metadata such as the title or artist, following spa/param/tag.h and tag-utils.h
+
+use pipewire_native_macros::EnumU32;
+
+use crate::dict::Dict;
+use crate::pod::builder::{Builder, ObjectBuilder};
+use crate::pod::parser::{ObjectParser, Parser};
+use crate::pod::types::{Id, ObjectType, PropertyFlags};
+use crate::pod::Error;
+
+use super::{info_value, parse_info, Direction, ParamObject, ParamType};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum Tag {
+    Start,
+    Direction,
+    Info,
+}
+
+impl ParamObject for Tag {
+    const TYPE: ObjectType = ObjectType::ParamTag;
+}
+
+// The tags flowing in one direction through a node, as key/value pairs
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParamTag {
+    pub direction: Direction,
+    pub info: Vec<(String, String)>,
+}
+
+impl ParamTag {
+    pub fn new_dict(direction: Direction, dict: &Dict) -> ParamTag {
+        ParamTag {
+            direction,
+            info: dict
+                .items()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    pub fn dict(&self) -> Dict {
+        Dict::new(self.info.clone())
+    }
+
+    // Write the direction and the info, as spa_tag_build_start() and spa_tag_build_add_dict() do
+    pub fn push_properties<'a>(&self, builder: ObjectBuilder<'a>) -> ObjectBuilder<'a> {
+        builder
+            .push_property(Tag::Direction, PropertyFlags::empty(), Id(self.direction))
+            .push_property(Tag::Info, PropertyFlags::HINT_DICT, info_value(&self.info))
+    }
+
+    pub fn build<'a>(&self, builder: Builder<'a>) -> Builder<'a> {
+        builder.push_object(ObjectType::ParamTag, ParamType::Tag, |b| {
+            self.push_properties(b)
+        })
+    }
+
+    // There can be more than one Info property, their items are all collected
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<ParamTag, Error> {
+        let mut tag = ParamTag::default();
+
+        while let Some((key, _, value)) = parser.pop_property::<Tag>()? {
+            match key {
+                Tag::Direction => tag.direction = value.decode::<Id<Direction>>()?.0,
+                Tag::Info => tag.info.extend(parse_info(value)?),
+                Tag::Start => (),
+            }
+        }
+
+        Ok(tag)
+    }
+
+    pub fn parse(parser: &mut Parser) -> Result<ParamTag, Error> {
+        parser
+            .pop_object::<Tag, _>(|p, _| ParamTag::parse_properties(p))
+            .map(|(tag, _)| tag)
+    }
+}
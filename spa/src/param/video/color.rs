@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Colorimetry and chroma siting, following spa/param/video/color.h and chroma.h
+
+use bitflags::bitflags;
+use pipewire_native_macros::EnumU32;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoColorRange {
+    #[default]
+    Unknown,
+    // Full range, 0-255 for 8 bit components
+    Full,
+    // Limited range, 16-235 for 8 bit luma
+    Limited,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoColorMatrix {
+    #[default]
+    Unknown,
+    RGB,
+    FCC,
+    BT709,
+    BT601,
+    SMPTE240M,
+    BT2020,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoTransferFunction {
+    #[default]
+    Unknown,
+    Gamma10,
+    Gamma18,
+    Gamma20,
+    Gamma22,
+    BT709,
+    SMPTE240M,
+    SRGB,
+    Gamma28,
+    Log100,
+    Log316,
+    BT2020_12,
+    AdobeRGB,
+    BT2020_10,
+    SMPTE2084,
+    AribStdB67,
+    SMPTE170M,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoColorPrimaries {
+    #[default]
+    Unknown,
+    BT709,
+    BT470M,
+    BT470BG,
+    SMPTE170M,
+    SMPTE240M,
+    Film,
+    BT2020,
+    AdobeRGB,
+    SMPTEST428,
+    SMPTERP431,
+    SMPTEEG432,
+    EBU3213,
+}
+
+bitflags! {
+    // Where chroma samples are, relative to luma samples. An empty set means unknown.
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct VideoChromaSite: u32 {
+        const NONE = 0x0000_0001;
+        const H_COSITED = 0x0000_0002;
+        const V_COSITED = 0x0000_0004;
+        const ALT_LINE = 0x0000_0008;
+
+        const COSITED = Self::H_COSITED.bits() | Self::V_COSITED.bits();
+        const JPEG = Self::NONE.bits();
+        const MPEG2 = Self::H_COSITED.bits();
+        const DV = Self::COSITED.bits() | Self::ALT_LINE.bits();
+    }
+}
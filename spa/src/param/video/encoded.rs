@@ -0,0 +1,362 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Compressed video formats, following spa/param/video/h264.h, h265.h, mjpg.h and the matching
+// *-utils.h. Subtypes without a header of their own in spa use VideoInfoEncoded.
+
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::{format_param, push_id, push_int};
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::{Fraction, Id, PropertyFlags, Rectangle};
+use crate::pod::{Error, RawPod};
+
+use super::raw::VideoChoices;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum H264StreamFormat {
+    #[default]
+    Unknown,
+    // Parameter sets in the codec data
+    Avc,
+    // Parameter sets in the codec data and in the stream
+    Avc3,
+    // Annex B, with start codes
+    ByteStream,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum H264Alignment {
+    #[default]
+    Unknown,
+    // Buffers hold a complete access unit (frame)
+    Au,
+    // Buffers hold a single NAL unit
+    Nal,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum H265StreamFormat {
+    #[default]
+    Unknown,
+    // Parameter sets in the codec data
+    Hvc1,
+    // Parameter sets in the codec data and in the stream
+    Hev1,
+    // Annex B, with start codes
+    ByteStream,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum H265Alignment {
+    #[default]
+    Unknown,
+    Au,
+    Nal,
+}
+
+fn push_video(builder: ObjectBuilder<'_>, subtype: MediaSubtype) -> ObjectBuilder<'_> {
+    builder
+        .push_property(
+            Format::MediaType,
+            PropertyFlags::empty(),
+            Id(MediaType::Video),
+        )
+        .push_property(Format::MediaSubtype, PropertyFlags::empty(), Id(subtype))
+}
+
+// The size and frame rates, or the choices for them if there are any. Values that are not set
+// are left out.
+fn push_size_framerate<'a>(
+    mut builder: ObjectBuilder<'a>,
+    size: Rectangle,
+    framerate: Fraction,
+    max_framerate: Fraction,
+    choices: &VideoChoices,
+) -> ObjectBuilder<'a> {
+    let empty = PropertyFlags::empty();
+
+    if let Some(size) = &choices.size {
+        builder = builder.push_property(Format::VideoSize, empty, size.clone());
+    } else if size.width != 0 && size.height != 0 {
+        builder = builder.push_property(Format::VideoSize, empty, size);
+    }
+
+    if let Some(framerate) = &choices.framerate {
+        builder = builder.push_property(Format::VideoFramerate, empty, framerate.clone());
+    } else if framerate.denom != 0 {
+        builder = builder.push_property(Format::VideoFramerate, empty, framerate);
+    }
+
+    if let Some(max_framerate) = &choices.max_framerate {
+        builder = builder.push_property(Format::VideoMaxFramerate, empty, max_framerate.clone());
+    } else if max_framerate.denom != 0 {
+        builder = builder.push_property(Format::VideoMaxFramerate, empty, max_framerate);
+    }
+
+    builder
+}
+
+// Go through the properties of a fixated video format, handing everything but the media type to
+// parse_property
+fn parse_video(
+    parser: &mut ObjectParser,
+    mut parse_property: impl FnMut(Format, RawPod) -> Result<(), Error>,
+) -> Result<(), Error> {
+    while let Some((key, _, value)) = parser.pop_property::<Format>()? {
+        match key {
+            Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Video => {
+                return Err(Error::Invalid)
+            }
+            Format::MediaType => (),
+            key => parse_property(key, value)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn check_subtype(value: RawPod, subtype: MediaSubtype) -> Result<(), Error> {
+    if value.decode::<Id<MediaSubtype>>()?.0 != subtype {
+        return Err(Error::Invalid);
+    }
+
+    Ok(())
+}
+
+// Like spa_video_info_h264, with the profile and level that spa only has keys for
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VideoInfoH264 {
+    pub size: Rectangle,
+    pub framerate: Fraction,
+    pub max_framerate: Fraction,
+    pub stream_format: H264StreamFormat,
+    pub alignment: H264Alignment,
+    pub profile: u32,
+    pub level: u32,
+}
+
+impl VideoInfoH264 {
+    pub fn push_enum_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &VideoChoices,
+    ) -> ObjectBuilder<'a> {
+        let builder = push_video(builder, MediaSubtype::H264);
+        let builder = push_size_framerate(
+            builder,
+            self.size,
+            self.framerate,
+            self.max_framerate,
+            choices,
+        );
+        let builder = push_id(builder, Format::VideoH264StreamFormat, self.stream_format);
+        let builder = push_id(builder, Format::VideoH264Alignment, self.alignment);
+        let builder = push_int(builder, Format::VideoProfile, self.profile);
+        push_int(builder, Format::VideoLevel, self.level)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<VideoInfoH264, Error> {
+        let mut info = VideoInfoH264::default();
+
+        parse_video(parser, |key, value| {
+            match key {
+                Format::MediaSubtype => check_subtype(value, MediaSubtype::H264)?,
+                Format::VideoSize => info.size = value.decode::<Rectangle>()?,
+                Format::VideoFramerate => info.framerate = value.decode::<Fraction>()?,
+                Format::VideoMaxFramerate => info.max_framerate = value.decode::<Fraction>()?,
+                Format::VideoH264StreamFormat => {
+                    info.stream_format = value.decode::<Id<H264StreamFormat>>()?.0
+                }
+                Format::VideoH264Alignment => {
+                    info.alignment = value.decode::<Id<H264Alignment>>()?.0
+                }
+                Format::VideoProfile => info.profile = value.decode::<i32>()? as u32,
+                Format::VideoLevel => info.level = value.decode::<i32>()? as u32,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(VideoInfoH264, VideoChoices);
+
+// Like spa_video_info_h265, with the profile and level that spa only has keys for
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VideoInfoH265 {
+    pub size: Rectangle,
+    pub framerate: Fraction,
+    pub max_framerate: Fraction,
+    pub stream_format: H265StreamFormat,
+    pub alignment: H265Alignment,
+    pub profile: u32,
+    pub level: u32,
+}
+
+impl VideoInfoH265 {
+    pub fn push_enum_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &VideoChoices,
+    ) -> ObjectBuilder<'a> {
+        let builder = push_video(builder, MediaSubtype::H265);
+        let builder = push_size_framerate(
+            builder,
+            self.size,
+            self.framerate,
+            self.max_framerate,
+            choices,
+        );
+        let builder = push_id(builder, Format::VideoH265StreamFormat, self.stream_format);
+        let builder = push_id(builder, Format::VideoH265Alignment, self.alignment);
+        let builder = push_int(builder, Format::VideoProfile, self.profile);
+        push_int(builder, Format::VideoLevel, self.level)
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<VideoInfoH265, Error> {
+        let mut info = VideoInfoH265::default();
+
+        parse_video(parser, |key, value| {
+            match key {
+                Format::MediaSubtype => check_subtype(value, MediaSubtype::H265)?,
+                Format::VideoSize => info.size = value.decode::<Rectangle>()?,
+                Format::VideoFramerate => info.framerate = value.decode::<Fraction>()?,
+                Format::VideoMaxFramerate => info.max_framerate = value.decode::<Fraction>()?,
+                Format::VideoH265StreamFormat => {
+                    info.stream_format = value.decode::<Id<H265StreamFormat>>()?.0
+                }
+                Format::VideoH265Alignment => {
+                    info.alignment = value.decode::<Id<H265Alignment>>()?.0
+                }
+                Format::VideoProfile => info.profile = value.decode::<i32>()? as u32,
+                Format::VideoLevel => info.level = value.decode::<i32>()? as u32,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(VideoInfoH265, VideoChoices);
+
+// Like spa_video_info_mjpg
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VideoInfoMjpg {
+    pub size: Rectangle,
+    pub framerate: Fraction,
+    pub max_framerate: Fraction,
+}
+
+impl VideoInfoMjpg {
+    pub fn push_enum_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &VideoChoices,
+    ) -> ObjectBuilder<'a> {
+        let builder = push_video(builder, MediaSubtype::Mjpg);
+        push_size_framerate(
+            builder,
+            self.size,
+            self.framerate,
+            self.max_framerate,
+            choices,
+        )
+    }
+
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<VideoInfoMjpg, Error> {
+        let mut info = VideoInfoMjpg::default();
+
+        parse_video(parser, |key, value| {
+            match key {
+                Format::MediaSubtype => check_subtype(value, MediaSubtype::Mjpg)?,
+                Format::VideoSize => info.size = value.decode::<Rectangle>()?,
+                Format::VideoFramerate => info.framerate = value.decode::<Fraction>()?,
+                Format::VideoMaxFramerate => info.max_framerate = value.decode::<Fraction>()?,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(VideoInfoMjpg, VideoChoices);
+
+// Any other compressed video, such as VP8, VP9 or MPEG-1/2/4, which only differ in the subtype
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VideoInfoEncoded {
+    pub subtype: MediaSubtype,
+    pub size: Rectangle,
+    pub framerate: Fraction,
+    pub max_framerate: Fraction,
+    pub profile: u32,
+    pub level: u32,
+}
+
+impl VideoInfoEncoded {
+    pub fn new(subtype: MediaSubtype) -> VideoInfoEncoded {
+        VideoInfoEncoded {
+            subtype,
+            size: Rectangle::default(),
+            framerate: Fraction::default(),
+            max_framerate: Fraction::default(),
+            profile: 0,
+            level: 0,
+        }
+    }
+
+    pub fn push_enum_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &VideoChoices,
+    ) -> ObjectBuilder<'a> {
+        let builder = push_video(builder, self.subtype);
+        let builder = push_size_framerate(
+            builder,
+            self.size,
+            self.framerate,
+            self.max_framerate,
+            choices,
+        );
+        let builder = push_int(builder, Format::VideoProfile, self.profile);
+        push_int(builder, Format::VideoLevel, self.level)
+    }
+
+    // Any video subtype is accepted, except raw video
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<VideoInfoEncoded, Error> {
+        let mut info = VideoInfoEncoded::new(MediaSubtype::Unknown);
+
+        parse_video(parser, |key, value| {
+            match key {
+                Format::MediaSubtype => match value.decode::<Id<MediaSubtype>>()?.0 {
+                    MediaSubtype::Raw | MediaSubtype::Dsp => return Err(Error::Invalid),
+                    subtype => info.subtype = subtype,
+                },
+                Format::VideoSize => info.size = value.decode::<Rectangle>()?,
+                Format::VideoFramerate => info.framerate = value.decode::<Fraction>()?,
+                Format::VideoMaxFramerate => info.max_framerate = value.decode::<Fraction>()?,
+                Format::VideoProfile => info.profile = value.decode::<i32>()? as u32,
+                Format::VideoLevel => info.level = value.decode::<i32>()? as u32,
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(info)
+    }
+}
+
+format_param!(VideoInfoEncoded, VideoChoices);
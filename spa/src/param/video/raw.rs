@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Raw video formats, following spa/param/video/raw.h, multiview.h and raw-utils.h
+
+use bitflags::bitflags;
+use pipewire_native_macros::EnumU32;
+
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::format_param;
+use crate::pod::builder::ObjectBuilder;
+use crate::pod::parser::ObjectParser;
+use crate::pod::types::{Choice, Fraction, Id, PropertyFlags, Rectangle, Type};
+use crate::pod::Error;
+
+use super::color::{
+    VideoChromaSite, VideoColorMatrix, VideoColorPrimaries, VideoColorRange, VideoTransferFunction,
+};
+
+#[repr(u32)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoFormat {
+    #[default]
+    Unknown,
+    Encoded,
+    I420,
+    YV12,
+    YUY2,
+    UYVY,
+    AYUV,
+    RGBx,
+    BGRx,
+    xRGB,
+    xBGR,
+    RGBA,
+    BGRA,
+    ARGB,
+    ABGR,
+    RGB,
+    BGR,
+    Y41B,
+    Y42B,
+    YVYU,
+    Y444,
+    v210,
+    v216,
+    NV12,
+    NV21,
+    GRAY8,
+    GRAY16_BE,
+    GRAY16_LE,
+    v308,
+    RGB16,
+    BGR16,
+    RGB15,
+    BGR15,
+    UYVP,
+    A420,
+    RGB8P,
+    YUV9,
+    YVU9,
+    IYU1,
+    ARGB64,
+    AYUV64,
+    r210,
+    I420_10BE,
+    I420_10LE,
+    I422_10BE,
+    I422_10LE,
+    Y444_10BE,
+    Y444_10LE,
+    GBR,
+    GBR_10BE,
+    GBR_10LE,
+    NV16,
+    NV24,
+    NV12_64Z32,
+    A420_10BE,
+    A420_10LE,
+    A422_10BE,
+    A422_10LE,
+    A444_10BE,
+    A444_10LE,
+    NV61,
+    P010_10BE,
+    P010_10LE,
+    IYU2,
+    VYUY,
+    GBRA,
+    GBRA_10BE,
+    GBRA_10LE,
+    GBR_12BE,
+    GBR_12LE,
+    GBRA_12BE,
+    GBRA_12LE,
+    I420_12BE,
+    I420_12LE,
+    I422_12BE,
+    I422_12LE,
+    Y444_12BE,
+    Y444_12LE,
+    RGBA_F16,
+    RGBA_F32,
+    xRGB_210LE,
+    xBGR_210LE,
+    RGBx_102LE,
+    BGRx_102LE,
+    ARGB_210LE,
+    ABGR_210LE,
+    RGBA_102LE,
+    BGRA_102LE,
+}
+
+impl VideoFormat {
+    // The format used for DSP processing
+    pub const DSP_F32: VideoFormat = VideoFormat::RGBA_F32;
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct VideoFlags: u32 {
+        const VARIABLE_FPS = 0x0000_0001;
+        const PREMULTIPLIED_ALPHA = 0x0000_0002;
+        // The format has a DMA-BUF modifier
+        const MODIFIER = 0x0000_0004;
+        // There is a list of modifiers that still needs to be fixated to one
+        const MODIFIER_FIXATION_REQUIRED = 0x0000_0008;
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoInterlaceMode {
+    #[default]
+    Progressive,
+    Interleaved,
+    Mixed,
+    Fields,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, EnumU32)]
+pub enum VideoMultiviewMode {
+    // -1 in spa
+    None = 0xffff_ffff,
+    #[default]
+    Mono = 0,
+    Left,
+    Right,
+    SideBySide,
+    SideBySideQuincunx,
+    ColumnInterleaved,
+    RowInterleaved,
+    TopBottom,
+    Checkerboard,
+
+    FrameByFrame = 32,
+    MultiviewFrameByFrame,
+    Separated,
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct VideoMultiviewFlags: u32 {
+        const RIGHT_VIEW_FIRST = 0x0000_0001;
+        const LEFT_FLIPPED = 0x0000_0002;
+        const LEFT_FLOPPED = 0x0000_0004;
+        const RIGHT_FLIPPED = 0x0000_0008;
+        const RIGHT_FLOPPED = 0x0000_0010;
+        const HALF_ASPECT = 0x0000_4000;
+        const MIXED_MONO = 0x0000_8000;
+    }
+}
+
+// Like spa_video_info_raw. Fields that are zero (or unknown) are not set.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VideoInfoRaw {
+    pub format: VideoFormat,
+    pub flags: VideoFlags,
+    pub modifier: u64,
+    // DMA-BUF modifiers that are offered as a choice, with modifier as the default
+    pub modifiers: Vec<u64>,
+    pub size: Rectangle,
+    pub framerate: Fraction,
+    pub max_framerate: Fraction,
+    pub views: u32,
+    pub interlace_mode: VideoInterlaceMode,
+    pub pixel_aspect_ratio: Fraction,
+    pub multiview_mode: VideoMultiviewMode,
+    pub multiview_flags: VideoMultiviewFlags,
+    pub chroma_site: VideoChromaSite,
+    pub color_range: VideoColorRange,
+    pub color_matrix: VideoColorMatrix,
+    pub transfer_function: VideoTransferFunction,
+    pub color_primaries: VideoColorPrimaries,
+}
+
+// Values that an EnumFormat offers a choice of. Each one that is set is written instead of the
+// corresponding fixed value of the info, formats are only used for raw video.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VideoChoices {
+    // The first format is the preferred one
+    pub formats: Vec<VideoFormat>,
+    pub size: Option<Choice<Rectangle>>,
+    pub framerate: Option<Choice<Fraction>>,
+    pub max_framerate: Option<Choice<Fraction>>,
+}
+
+impl VideoInfoRaw {
+    // Write the properties of the format, as spa_format_video_raw_build() does, with the choices
+    // that are set in place of the matching values
+    pub fn push_enum_properties<'a>(
+        &self,
+        builder: ObjectBuilder<'a>,
+        choices: &VideoChoices,
+    ) -> ObjectBuilder<'a> {
+        let empty = PropertyFlags::empty();
+        let mut builder = builder
+            .push_property(Format::MediaType, empty, Id(MediaType::Video))
+            .push_property(Format::MediaSubtype, empty, Id(MediaSubtype::Raw));
+
+        if let Some((first, _)) = choices.formats.split_first() {
+            builder = builder.push_property(
+                Format::VideoFormat,
+                empty,
+                Choice::Enum {
+                    default: Id(*first),
+                    alternatives: choices.formats.iter().map(|f| Id(*f)).collect(),
+                },
+            );
+        } else if self.format != VideoFormat::Unknown {
+            builder = builder.push_property(Format::VideoFormat, empty, Id(self.format));
+        }
+
+        if let Some(size) = &choices.size {
+            builder = builder.push_property(Format::VideoSize, empty, size.clone());
+        } else if self.size.width != 0 && self.size.height != 0 {
+            builder = builder.push_property(Format::VideoSize, empty, self.size);
+        }
+
+        if let Some(framerate) = &choices.framerate {
+            builder = builder.push_property(Format::VideoFramerate, empty, framerate.clone());
+        } else if self.framerate.denom != 0 {
+            builder = builder.push_property(Format::VideoFramerate, empty, self.framerate);
+        }
+
+        // The modifier must be understood by the other side, and a list of modifiers must not be
+        // fixated by the usual rules, the producer picks one once it knows which ones work
+        if self.modifiers.len() > 1 {
+            builder = builder.push_property(
+                Format::VideoModifier,
+                PropertyFlags::MANDATORY | PropertyFlags::DONT_FIXATE,
+                Choice::Enum {
+                    default: self.modifier as i64,
+                    alternatives: self.modifiers.iter().map(|m| *m as i64).collect(),
+                },
+            );
+        } else if self.modifier != 0 || self.flags.contains(VideoFlags::MODIFIER) {
+            builder = builder.push_property(
+                Format::VideoModifier,
+                PropertyFlags::MANDATORY,
+                self.modifier as i64,
+            );
+        }
+
+        if let Some(max_framerate) = &choices.max_framerate {
+            builder =
+                builder.push_property(Format::VideoMaxFramerate, empty, max_framerate.clone());
+        } else if self.max_framerate.denom != 0 {
+            builder = builder.push_property(Format::VideoMaxFramerate, empty, self.max_framerate);
+        }
+
+        if self.views != 0 {
+            builder = builder.push_property(Format::VideoViews, empty, self.views as i32);
+        }
+        if self.interlace_mode != VideoInterlaceMode::Progressive {
+            builder =
+                builder.push_property(Format::VideoInterlaceMode, empty, Id(self.interlace_mode));
+        }
+        if self.pixel_aspect_ratio.denom != 0 {
+            builder = builder.push_property(
+                Format::VideoPixelAspectRatio,
+                empty,
+                self.pixel_aspect_ratio,
+            );
+        }
+        if self.multiview_mode != VideoMultiviewMode::Mono {
+            builder =
+                builder.push_property(Format::VideoMultiviewMode, empty, Id(self.multiview_mode));
+        }
+        if !self.multiview_flags.is_empty() {
+            builder = builder.push_property(
+                Format::VideoMultiviewFlags,
+                empty,
+                Id(self.multiview_flags.bits()),
+            );
+        }
+        if !self.chroma_site.is_empty() {
+            builder =
+                builder.push_property(Format::VideoChromaSite, empty, Id(self.chroma_site.bits()));
+        }
+        if self.color_range != VideoColorRange::Unknown {
+            builder = builder.push_property(Format::VideoColorRange, empty, Id(self.color_range));
+        }
+        if self.color_matrix != VideoColorMatrix::Unknown {
+            builder = builder.push_property(Format::VideoColorMatrix, empty, Id(self.color_matrix));
+        }
+        if self.transfer_function != VideoTransferFunction::Unknown {
+            builder = builder.push_property(
+                Format::VideoTransferFunction,
+                empty,
+                Id(self.transfer_function),
+            );
+        }
+        if self.color_primaries != VideoColorPrimaries::Unknown {
+            builder =
+                builder.push_property(Format::VideoColorPrimaries, empty, Id(self.color_primaries));
+        }
+
+        builder
+    }
+
+    // Read a fixated format, as spa_format_video_raw_parse() does. The modifier may still be a
+    // list of choices, which is marked as needing fixation if it is not meant to be fixated the
+    // usual way.
+    pub fn parse_properties(parser: &mut ObjectParser) -> Result<VideoInfoRaw, Error> {
+        let mut info = VideoInfoRaw::default();
+
+        while let Some((key, flags, value)) = parser.pop_property::<Format>()? {
+            match key {
+                Format::MediaType if value.decode::<Id<MediaType>>()?.0 != MediaType::Video => {
+                    return Err(Error::Invalid)
+                }
+                Format::MediaSubtype
+                    if value.decode::<Id<MediaSubtype>>()?.0 != MediaSubtype::Raw =>
+                {
+                    return Err(Error::Invalid)
+                }
+                Format::VideoFormat => info.format = value.decode::<Id<VideoFormat>>()?.0,
+                Format::VideoModifier => {
+                    info.flags |= VideoFlags::MODIFIER;
+
+                    if value.type_() != Type::Choice {
+                        info.modifier = value.decode::<i64>()? as u64;
+                        continue;
+                    }
+
+                    match value.decode::<Choice<i64>>()? {
+                        Choice::None(modifier) => info.modifier = modifier as u64,
+                        Choice::Enum {
+                            default,
+                            alternatives,
+                        } => {
+                            info.modifier = default as u64;
+                            info.modifiers = alternatives.into_iter().map(|m| m as u64).collect();
+
+                            if flags.contains(PropertyFlags::DONT_FIXATE)
+                                && !info.modifiers.is_empty()
+                            {
+                                info.flags |= VideoFlags::MODIFIER_FIXATION_REQUIRED;
+                            }
+                        }
+                        _ => return Err(Error::Invalid),
+                    }
+                }
+                Format::VideoSize => info.size = value.decode::<Rectangle>()?,
+                Format::VideoFramerate => info.framerate = value.decode::<Fraction>()?,
+                Format::VideoMaxFramerate => info.max_framerate = value.decode::<Fraction>()?,
+                Format::VideoViews => info.views = value.decode::<i32>()? as u32,
+                Format::VideoInterlaceMode => {
+                    info.interlace_mode = value.decode::<Id<VideoInterlaceMode>>()?.0
+                }
+                Format::VideoPixelAspectRatio => {
+                    info.pixel_aspect_ratio = value.decode::<Fraction>()?
+                }
+                Format::VideoMultiviewMode => {
+                    info.multiview_mode = value.decode::<Id<VideoMultiviewMode>>()?.0
+                }
+                Format::VideoMultiviewFlags => {
+                    info.multiview_flags =
+                        VideoMultiviewFlags::from_bits_retain(value.decode::<Id<u32>>()?.0)
+                }
+                Format::VideoChromaSite => {
+                    info.chroma_site =
+                        VideoChromaSite::from_bits_retain(value.decode::<Id<u32>>()?.0)
+                }
+                Format::VideoColorRange => {
+                    info.color_range = value.decode::<Id<VideoColorRange>>()?.0
+                }
+                Format::VideoColorMatrix => {
+                    info.color_matrix = value.decode::<Id<VideoColorMatrix>>()?.0
+                }
+                Format::VideoTransferFunction => {
+                    info.transfer_function = value.decode::<Id<VideoTransferFunction>>()?.0
+                }
+                Format::VideoColorPrimaries => {
+                    info.color_primaries = value.decode::<Id<VideoColorPrimaries>>()?.0
+                }
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+format_param!(VideoInfoRaw, VideoChoices);
@@ -8,9 +8,11 @@ use std::os::fd::RawFd;
 use crate::param::ParamType;
 
 use super::types::{
-    Choice, Fd, Fraction, Id, ObjectType, Pointer, Property, PropertyFlags, Rectangle, Type,
+    Bitmap, Choice, ControlType, Fd, Flags, Fraction, Id, ObjectType, Pointer, Property,
+    PropertyFlags, Rectangle, Type,
 };
-use super::{Error, Pod, Primitive};
+use super::value::Value;
+use super::{Error, PathSegment, Pod, Primitive};
 
 pub struct Builder<'a> {
     data: &'a mut [u8],
@@ -39,13 +41,31 @@ impl<'a> Builder<'a> {
         if self.error.is_none() {
             match value.encode(&mut self.data[self.pos..]) {
                 Ok(size) => self.pos += size,
-                Err(e) => self.error = Some(e),
+                Err(e) => self.error = Some(e.at(self.pos, None)),
             }
         }
 
         self
     }
 
+    // The key and flags of a property, for values that are built in place rather than encoded
+    fn push_property_header(mut self, key: u32, flags: PropertyFlags) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+
+        if self.data.len() < self.pos + 8 {
+            self.error = Some(Error::NoSpace.at(self.pos, None));
+            return self;
+        }
+
+        self.data[self.pos..self.pos + 4].copy_from_slice(&key.to_ne_bytes());
+        self.data[self.pos + 4..self.pos + 8].copy_from_slice(&flags.bits().to_ne_bytes());
+        self.pos += 8;
+
+        self
+    }
+
     pub fn push_none(self) -> Self {
         self.push_pod(&())
     }
@@ -61,6 +81,13 @@ impl<'a> Builder<'a> {
         self.push_pod(&value)
     }
 
+    pub fn push_flags<T>(self, value: T) -> Self
+    where
+        T: bitflags::Flags<Bits = u32>,
+    {
+        self.push_pod(&Flags(value))
+    }
+
     pub fn push_int(self, value: i32) -> Self {
         self.push_pod(&value)
     }
@@ -97,6 +124,10 @@ impl<'a> Builder<'a> {
         self.push_pod(&value)
     }
 
+    pub fn push_bitmap(self, value: Bitmap) -> Self {
+        self.push_pod(&value)
+    }
+
     pub fn push_pointer(self, typ: Type, value: *const c_void) -> Self {
         self.push_pod(&Pointer {
             type_: typ,
@@ -118,6 +149,10 @@ impl<'a> Builder<'a> {
         self.push_pod(&value)
     }
 
+    pub fn push_value(self, value: &Value) -> Self {
+        self.push_pod(value)
+    }
+
     // Struct is encoded as
     //
     // +--------------+
@@ -139,7 +174,7 @@ impl<'a> Builder<'a> {
         }
 
         if self.data.len() < 8 {
-            self.error = Some(Error::NoSpace);
+            self.error = Some(Error::NoSpace.at(self.pos, None));
             return self;
         }
 
@@ -200,7 +235,7 @@ impl<'a> Builder<'a> {
         }
 
         if self.data.len() < 16 {
-            self.error = Some(Error::NoSpace);
+            self.error = Some(Error::NoSpace.at(self.pos, None));
             return self;
         }
 
@@ -209,10 +244,11 @@ impl<'a> Builder<'a> {
         self.pos += 16;
 
         // Write out all the props
-        let object_builder = build_object(ObjectBuilder::new(self));
-        let ret = object_builder.builder();
+        let object_builder = build_object(ObjectBuilder::new(self, type_ as u32));
+        let mut ret = object_builder.builder();
 
-        if ret.error.is_some() {
+        if let Some(e) = ret.error.take() {
+            ret.error = Some(e.at(0, Some(PathSegment::Object(type_ as u32))));
             return ret;
         }
 
@@ -225,6 +261,70 @@ impl<'a> Builder<'a> {
 
         ret
     }
+
+    // Sequence is encoded as
+    //
+    // +--------------+
+    // |  total size  | 4 bytes
+    // +--------------+
+    // |   pod type   | 4 bytes
+    // +--------------+
+    // |     unit     | 4 bytes
+    // +--------------+
+    // |   padding    | 4 bytes
+    // +--------------+
+    // |              |
+    // |   controls   |
+    // |              |
+    // +--------------+
+    //
+    // where each control is
+    //
+    // +--------------+
+    // |    offset    | 4 bytes
+    // +--------------+
+    // | control type | 4 bytes
+    // +--------------+
+    // |              |
+    // |    value     |
+    // |     pod      |
+    // |              |
+    // +--------------+
+    //
+    pub fn push_sequence<F>(mut self, unit: u32, build_sequence: F) -> Self
+    where
+        F: FnOnce(SequenceBuilder) -> SequenceBuilder,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+
+        if self.data.len() - self.pos < 16 {
+            self.error = Some(Error::NoSpace.at(self.pos, None));
+            return self;
+        }
+
+        // Leave some space for the header
+        let old_pos = self.pos;
+        self.pos += 16;
+
+        // Write out all the controls
+        let sequence_builder = build_sequence(SequenceBuilder::new(self));
+        let ret = sequence_builder.builder();
+
+        if ret.error.is_some() {
+            return ret;
+        }
+
+        // Fill in the header
+        let size = ret.pos - old_pos - 8;
+        ret.data[old_pos..old_pos + 4].copy_from_slice(&(size as u32).to_ne_bytes());
+        ret.data[old_pos + 4..old_pos + 8].copy_from_slice(&(Type::Sequence as u32).to_ne_bytes());
+        ret.data[old_pos + 8..old_pos + 12].copy_from_slice(&unit.to_ne_bytes());
+        ret.data[old_pos + 12..old_pos + 16].copy_from_slice(&[0, 0, 0, 0]);
+
+        ret
+    }
 }
 
 pub struct StructBuilder<'a> {
@@ -259,6 +359,13 @@ impl<'a> StructBuilder<'a> {
         StructBuilder::new(self.builder.push_id(value))
     }
 
+    pub fn push_flags<T>(self, value: T) -> Self
+    where
+        T: bitflags::Flags<Bits = u32>,
+    {
+        StructBuilder::new(self.builder.push_flags(value))
+    }
+
     pub fn push_int(self, value: i32) -> Self {
         StructBuilder::new(self.builder.push_int(value))
     }
@@ -295,6 +402,10 @@ impl<'a> StructBuilder<'a> {
         StructBuilder::new(self.builder.push_bytes(value))
     }
 
+    pub fn push_bitmap(self, value: Bitmap) -> Self {
+        StructBuilder::new(self.builder.push_bitmap(value))
+    }
+
     pub fn push_pointer(self, typ: Type, value: *const c_void) -> Self {
         StructBuilder::new(self.builder.push_pointer(typ, value))
     }
@@ -313,6 +424,10 @@ impl<'a> StructBuilder<'a> {
         StructBuilder::new(self.builder.push_choice(value))
     }
 
+    pub fn push_value(self, value: &Value) -> Self {
+        StructBuilder::new(self.builder.push_value(value))
+    }
+
     pub fn push_struct<F>(self, build_struct: F) -> Self
     where
         F: FnOnce(StructBuilder) -> StructBuilder,
@@ -326,27 +441,393 @@ impl<'a> StructBuilder<'a> {
     {
         StructBuilder::new(self.builder.push_object(type_, id, build_object))
     }
+
+    pub fn push_sequence<F>(self, unit: u32, build_sequence: F) -> Self
+    where
+        F: FnOnce(SequenceBuilder) -> SequenceBuilder,
+    {
+        StructBuilder::new(self.builder.push_sequence(unit, build_sequence))
+    }
 }
 
 pub struct ObjectBuilder<'a> {
     builder: Builder<'a>,
+    object_type: u32,
 }
 
 impl<'a> ObjectBuilder<'a> {
-    fn new(builder: Builder<'a>) -> Self {
-        Self { builder }
+    fn new(builder: Builder<'a>, object_type: u32) -> Self {
+        Self {
+            builder,
+            object_type,
+        }
     }
 
     fn builder(self) -> Builder<'a> {
         self.builder
     }
 
+    // Add the property to the path of an error that happened while it was being written
+    fn property_error(&mut self, had_error: bool, key: u32) {
+        if had_error {
+            return;
+        }
+
+        if let Some(e) = self.builder.error.take() {
+            let segment = PathSegment::Property {
+                object_type: self.object_type,
+                key,
+            };
+            self.builder.error = Some(e.at(0, Some(segment)));
+        }
+    }
+
     pub fn push_property<K, V>(mut self, key: K, flags: PropertyFlags, value: V) -> Self
     where
         K: Copy + Into<u32> + TryFrom<u32>,
         V: Pod,
     {
+        let had_error = self.builder.error.is_some();
+
         self.builder = self.builder.push_pod(&Property { key, flags, value });
+        self.property_error(had_error, key.into());
+        self
+    }
+
+    // A property with an object as its value, built in place like with push_object()
+    pub fn push_object_property<K, T, F>(
+        mut self,
+        key: K,
+        flags: PropertyFlags,
+        type_: ObjectType,
+        id: T,
+        build_object: F,
+    ) -> Self
+    where
+        K: Copy + Into<u32> + TryFrom<u32>,
+        T: Into<u32> + TryFrom<u32>,
+        F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+    {
+        let had_error = self.builder.error.is_some();
+
+        self.builder = self
+            .builder
+            .push_property_header(key.into(), flags)
+            .push_object(type_, id, build_object);
+        self.property_error(had_error, key.into());
+        self
+    }
+}
+
+pub struct SequenceBuilder<'a> {
+    builder: Builder<'a>,
+}
+
+impl<'a> SequenceBuilder<'a> {
+    fn new(builder: Builder<'a>) -> Self {
+        Self { builder }
+    }
+
+    fn builder(self) -> Builder<'a> {
+        self.builder
+    }
+
+    pub fn push_control<V>(mut self, offset: u32, type_: ControlType, value: V) -> Self
+    where
+        V: Pod,
+    {
+        if self.builder.error.is_some() {
+            return self;
+        }
+
+        let pos = self.builder.pos;
+        if self.builder.data.len() - pos < 8 {
+            self.builder.error = Some(Error::NoSpace.at(pos, None));
+            return self;
+        }
+
+        self.builder.data[pos..pos + 4].copy_from_slice(&offset.to_ne_bytes());
+        self.builder.data[pos + 4..pos + 8].copy_from_slice(&(type_ as u32).to_ne_bytes());
+        self.builder.pos += 8;
+
+        self.builder = self.builder.push_pod(&value);
+        self
+    }
+
+    pub fn push_midi(self, offset: u32, data: &[u8]) -> Self {
+        self.push_control(offset, ControlType::Midi, data)
+    }
+}
+
+// An open struct, object or sequence in a DynamicBuilder, which must be closed with pop_frame()
+#[derive(Debug)]
+#[must_use = "frames must be closed with pop_frame()"]
+pub struct Frame {
+    pos: usize,
+}
+
+// Like Builder, but writes into a buffer that it owns and grows as needed, similar to
+// spa_pod_dynamic_builder. Nested pods are opened with one of the push_frame_*() methods, which
+// reserve space for the header, and closed with pop_frame(), which fills in the final size.
+// Errors are deferred until build(), so that calls can be chained.
+#[derive(Debug, Default)]
+pub struct DynamicBuilder {
+    // Zero-filled scratch space that pods are encoded into, of which the first `pos` bytes are used
+    data: Vec<u8>,
+    pos: usize,
+    frames: Vec<usize>,
+    error: Option<Error>,
+}
+
+impl DynamicBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    // Make sure at least `additional` more bytes can be written without reallocating
+    pub fn reserve(&mut self, additional: usize) -> &mut Self {
+        self.grow(self.pos + additional);
         self
     }
+
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    pub fn build(mut self) -> Result<Vec<u8>, Error> {
+        if let Some(e) = self.error {
+            Err(e)
+        } else if !self.frames.is_empty() {
+            // A frame was not closed
+            Err(Error::Invalid)
+        } else {
+            self.data.truncate(self.pos);
+            Ok(self.data)
+        }
+    }
+
+    // Grow the scratch space to at least `size` bytes, doubling it so that growing is amortized
+    fn grow(&mut self, size: usize) {
+        if self.data.len() < size {
+            self.data.resize(size.max(self.data.len() * 2).max(64), 0);
+        }
+    }
+
+    fn push_raw(&mut self, bytes: &[u8]) {
+        if self.error.is_none() {
+            self.grow(self.pos + bytes.len());
+            self.data[self.pos..self.pos + bytes.len()].copy_from_slice(bytes);
+            self.pos += bytes.len();
+        }
+    }
+
+    pub fn push_pod<U: Pod>(&mut self, value: &U) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+
+        let start = self.pos;
+
+        loop {
+            match value.encode(&mut self.data[start..]) {
+                Ok(size) => {
+                    self.pos = start + size;
+                    break;
+                }
+                Err(e)
+                    if matches!(e.cause(), Error::NoSpace)
+                        && self.data.len() - start < u32::MAX as usize =>
+                {
+                    self.grow(self.data.len() + 1)
+                }
+                Err(e) => {
+                    self.error = Some(e.at(start, None));
+                    break;
+                }
+            }
+        }
+
+        self
+    }
+
+    pub fn push_none(&mut self) -> &mut Self {
+        self.push_pod(&())
+    }
+
+    pub fn push_bool(&mut self, value: bool) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_id<T>(&mut self, value: Id<T>) -> &mut Self
+    where
+        T: Into<u32> + TryFrom<u32> + Copy,
+    {
+        self.push_pod(&value)
+    }
+
+    pub fn push_flags<T>(&mut self, value: T) -> &mut Self
+    where
+        T: bitflags::Flags<Bits = u32>,
+    {
+        self.push_pod(&Flags(value))
+    }
+
+    pub fn push_int(&mut self, value: i32) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_long(&mut self, value: i64) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_float(&mut self, value: f32) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_double(&mut self, value: f64) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_fd(&mut self, value: RawFd) -> &mut Self {
+        self.push_pod(&Fd(value))
+    }
+
+    pub fn push_rectangle(&mut self, width: u32, height: u32) -> &mut Self {
+        self.push_pod(&Rectangle { width, height })
+    }
+
+    pub fn push_fraction(&mut self, num: u32, denom: u32) -> &mut Self {
+        self.push_pod(&Fraction { num, denom })
+    }
+
+    pub fn push_string(&mut self, value: &str) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_bitmap(&mut self, value: Bitmap) -> &mut Self {
+        self.push_pod(&value)
+    }
+
+    pub fn push_pointer(&mut self, typ: Type, value: *const c_void) -> &mut Self {
+        self.push_pod(&Pointer {
+            type_: typ,
+            ptr: value,
+        })
+    }
+
+    pub fn push_array<T>(&mut self, values: &[T]) -> &mut Self
+    where
+        T: Pod + Primitive,
+    {
+        self.push_pod(&values)
+    }
+
+    pub fn push_choice<T>(&mut self, value: Choice<T>) -> &mut Self
+    where
+        T: Pod + Primitive,
+    {
+        self.push_pod(&value)
+    }
+
+    pub fn push_value(&mut self, value: &Value) -> &mut Self {
+        self.push_pod(value)
+    }
+
+    // The header is written with a zero size, which is filled in by pop_frame()
+    fn push_frame(&mut self, type_: Type, header: &[u32]) -> Frame {
+        let pos = self.pos;
+
+        self.push_raw(&0u32.to_ne_bytes());
+        self.push_raw(&(type_ as u32).to_ne_bytes());
+        for word in header {
+            self.push_raw(&word.to_ne_bytes());
+        }
+
+        self.frames.push(pos);
+        Frame { pos }
+    }
+
+    pub fn push_frame_struct(&mut self) -> Frame {
+        self.push_frame(Type::Struct, &[])
+    }
+
+    pub fn push_frame_object<T>(&mut self, type_: ObjectType, id: T) -> Frame
+    where
+        T: Into<u32>,
+    {
+        self.push_frame(Type::Object, &[type_ as u32, id.into()])
+    }
+
+    pub fn push_frame_sequence(&mut self, unit: u32) -> Frame {
+        self.push_frame(Type::Sequence, &[unit, 0])
+    }
+
+    // Frames must be closed in the reverse order of being opened
+    pub fn pop_frame(&mut self, frame: Frame) -> &mut Self {
+        if self.frames.last() != Some(&frame.pos) {
+            self.error
+                .get_or_insert_with(|| Error::Invalid.at(self.pos, None));
+            return self;
+        }
+        self.frames.pop();
+
+        if self.error.is_some() {
+            return self;
+        }
+
+        let size = self.pos - frame.pos - 8;
+        match u32::try_from(size) {
+            Ok(size) => self.data[frame.pos..frame.pos + 4].copy_from_slice(&size.to_ne_bytes()),
+            Err(_) => self.error = Some(Error::NoSpace.at(frame.pos, None)),
+        }
+
+        self
+    }
+
+    // Write the key and flags of a property in an object frame, the value should be pushed next
+    pub fn push_property_key<K>(&mut self, key: K, flags: PropertyFlags) -> &mut Self
+    where
+        K: Into<u32>,
+    {
+        self.push_raw(&key.into().to_ne_bytes());
+        self.push_raw(&flags.bits().to_ne_bytes());
+        self
+    }
+
+    pub fn push_property<K, V>(&mut self, key: K, flags: PropertyFlags, value: &V) -> &mut Self
+    where
+        K: Into<u32>,
+        V: Pod,
+    {
+        self.push_property_key(key, flags).push_pod(value)
+    }
+
+    // Write the offset and type of a control in a sequence frame, the value should be pushed next
+    pub fn push_control_header(&mut self, offset: u32, type_: ControlType) -> &mut Self {
+        self.push_raw(&offset.to_ne_bytes());
+        self.push_raw(&(type_ as u32).to_ne_bytes());
+        self
+    }
+
+    pub fn push_control<V>(&mut self, offset: u32, type_: ControlType, value: &V) -> &mut Self
+    where
+        V: Pod,
+    {
+        self.push_control_header(offset, type_).push_pod(value)
+    }
 }
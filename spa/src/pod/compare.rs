@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Ordering of pods, following spa/pod/compare.h
+
+use std::cmp::Ordering;
+
+use super::types::ChoiceType;
+use super::value::Value;
+use super::{Error, Pod};
+
+// Compare two values of the same type, as spa_pod_compare_value() does. Values of different or
+// non-comparable types are considered equal.
+pub(crate) fn compare_value(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Id(a), Value::Id(b)) => a.cmp(b),
+        (Value::Int(a), Value::Int(b)) => a.cmp(b),
+        (Value::Long(a), Value::Long(b)) => a.cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::Double(a), Value::Double(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.as_bytes().cmp(b.as_bytes()),
+        (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+        (Value::Rectangle(a), Value::Rectangle(b)) => {
+            if a.width == b.width && a.height == b.height {
+                return Ordering::Equal;
+            }
+
+            // Ordered by area, then by whichever dimension differs
+            let n1 = a.width as u64 * a.height as u64;
+            let n2 = b.width as u64 * b.height as u64;
+
+            n1.cmp(&n2).then_with(|| {
+                if a.width == b.width {
+                    a.height.cmp(&b.height)
+                } else {
+                    a.width.cmp(&b.width)
+                }
+            })
+        }
+        (Value::Fraction(a), Value::Fraction(b)) => {
+            let n1 = a.num as u64 * b.denom as u64;
+            let n2 = b.num as u64 * a.denom as u64;
+
+            n1.cmp(&n2)
+        }
+        _ => Ordering::Equal,
+    }
+}
+
+// The value to compare and the number of values, choices are compared by their default value
+fn get_values(value: &Value) -> Result<(&Value, usize), Error> {
+    match value {
+        Value::Choice(c) => {
+            let n_values = match c.type_ {
+                ChoiceType::None => c.values.len().min(1),
+                _ => c.values.len(),
+            };
+            c.values
+                .first()
+                .map(|v| (v, n_values))
+                .ok_or(Error::Invalid)
+        }
+        v => Ok((v, 1)),
+    }
+}
+
+// Size of the body, without padding
+fn body_size(value: &Value) -> Result<u32, Error> {
+    let bytes = value.to_bytes()?;
+    Ok(u32::from_ne_bytes(bytes[0..4].try_into().unwrap()))
+}
+
+// Compare two pods, like spa_pod_compare(). Objects are compared property by property, with an
+// object that has a property the other does not have ordering after it. Pods of different types
+// or sizes can't be compared, which is an error.
+pub fn compare(a: &[u8], b: &[u8]) -> Result<Ordering, Error> {
+    let (a, _) = Value::decode(a)?;
+    let (b, _) = Value::decode(b)?;
+
+    compare_values(&a, &b)
+}
+
+pub fn compare_values(a: &Value, b: &Value) -> Result<Ordering, Error> {
+    let (a, n_values_a) = get_values(a)?;
+    let (b, n_values_b) = get_values(b)?;
+
+    if n_values_a != n_values_b || a.type_() != b.type_() {
+        return Err(Error::Invalid);
+    }
+
+    match (a, b) {
+        (Value::Struct(a), Value::Struct(b)) => {
+            if a.len() != b.len() {
+                return Err(Error::Invalid);
+            }
+
+            for (a, b) in a.iter().zip(b) {
+                match compare_values(a, b)? {
+                    Ordering::Equal => (),
+                    res => return Ok(res),
+                }
+            }
+
+            Ok(Ordering::Equal)
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            for p1 in &a.properties {
+                let Some(p2) = b.property(p1.key) else {
+                    return Ok(Ordering::Greater);
+                };
+                match compare_values(&p1.value, &p2.value)? {
+                    Ordering::Equal => (),
+                    res => return Ok(res),
+                }
+            }
+
+            if b.properties.iter().any(|p| a.property(p.key).is_none()) {
+                return Ok(Ordering::Less);
+            }
+
+            Ok(Ordering::Equal)
+        }
+        (a, b) => {
+            if body_size(a)? != body_size(b)? {
+                return Err(Error::Invalid);
+            }
+
+            match (a, b) {
+                // Arrays are compared bytewise, including the child size and type
+                (Value::Array(_), Value::Array(_)) => {
+                    Ok(a.to_bytes()?[8..].cmp(&b.to_bytes()?[8..]))
+                }
+                _ => Ok(compare_value(a, b)),
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// serde support, the inverse of the mapping described in ser.rs
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+use super::value::Value;
+use super::Pod;
+
+pub use super::ser::Error;
+
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(Deserializer::new(value))
+}
+
+pub fn from_bytes<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
+    let (value, _) = Value::decode(data)?;
+    from_value(value)
+}
+
+pub struct Deserializer {
+    value: Value,
+}
+
+impl Deserializer {
+    pub fn new(value: Value) -> Self {
+        Self { value }
+    }
+
+    fn invalid_type<V>(self, expected: &str) -> Result<V, Error> {
+        Err(Error::Message(format!(
+            "invalid type: expected {}, found {:?} pod",
+            expected,
+            self.value.type_()
+        )))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::None => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Id(v) => visitor.visit_u32(v),
+            Value::Int(v) => visitor.visit_i32(v),
+            Value::Long(v) => visitor.visit_i64(v),
+            Value::Float(v) => visitor.visit_f32(v),
+            Value::Double(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Bytes(v) | Value::Bitmap(v) => visitor.visit_byte_buf(v),
+            Value::Rectangle(v) => visitor.visit_seq(SeqDeserializer::new(vec![
+                Value::Int(v.width as i32),
+                Value::Int(v.height as i32),
+            ])),
+            Value::Fraction(v) => visitor.visit_seq(SeqDeserializer::new(vec![
+                Value::Int(v.num as i32),
+                Value::Int(v.denom as i32),
+            ])),
+            Value::Array(array) => visitor.visit_seq(SeqDeserializer::new(array.values)),
+            Value::Struct(values) => visitor.visit_seq(SeqDeserializer::new(values)),
+            Value::Fd(v) => visitor.visit_i64(v.0 as i64),
+            v => Deserializer::new(v).invalid_type("a serde data type"),
+        }
+    }
+
+    // Unsigned values are stored with the same bits in signed pods
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Int(v) => visitor.visit_u32(v as u32),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Long(v) => visitor.visit_u64(v as u64),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(ref s) if s.chars().count() == 1 => {
+                visitor.visit_char(s.chars().next().unwrap())
+            }
+            _ => self.invalid_type("a single character string"),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::None => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Struct(values) => {
+                let mut values = values.into_iter();
+
+                let n_items = match values.next() {
+                    Some(Value::Int(n)) if n >= 0 => n as usize,
+                    _ => return Err(Error::Message("invalid map header".to_string())),
+                };
+
+                if values.len() != n_items * 2 {
+                    return Err(Error::Message("invalid map length".to_string()));
+                }
+
+                visitor.visit_map(MapDeserializer {
+                    values,
+                    value: None,
+                })
+            }
+            _ => self.invalid_type("a map"),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Id(index) => visitor.visit_enum(EnumDeserializer {
+                index,
+                values: Vec::new(),
+            }),
+            Value::Struct(values) => {
+                let mut values = values.into_iter();
+
+                let index = match values.next() {
+                    Some(Value::Id(index)) => index,
+                    _ => return Err(Error::Message("invalid enum variant".to_string())),
+                };
+
+                visitor.visit_enum(EnumDeserializer {
+                    index,
+                    values: values.collect(),
+                })
+            }
+            _ => self.invalid_type("an enum"),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 f32 f64 str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct struct identifier ignored_any
+    }
+}
+
+struct SeqDeserializer {
+    values: std::vec::IntoIter<Value>,
+}
+
+impl SeqDeserializer {
+    fn new(values: Vec<Value>) -> Self {
+        Self {
+            values: values.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(Deserializer::new(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct MapDeserializer {
+    values: std::vec::IntoIter<Value>,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.values.next() {
+            Some(key) => {
+                self.value = self.values.next();
+                seed.deserialize(Deserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(Deserializer::new(value)),
+            None => Err(Error::Message("map value missing".to_string())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len() / 2)
+    }
+}
+
+struct EnumDeserializer {
+    index: u32,
+    values: Vec<Value>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let deserializer: de::value::U32Deserializer<Error> = self.index.into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        if self.values.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Message(
+                "unexpected data for unit variant".to_string(),
+            ))
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        let mut values = self.values.into_iter();
+
+        match (values.next(), values.next()) {
+            (Some(value), None) => seed.deserialize(Deserializer::new(value)),
+            _ => Err(Error::Message("invalid newtype variant".to_string())),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.values))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.values))
+    }
+}
@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Human-readable output for pods, along the lines of spa_debug_pod(). Each nested level is indented
+// by two spaces, and known object types, param ids, property keys and ids are printed by name.
+
+use std::fmt;
+
+use crate::param::audio::dsd::BitOrder;
+use crate::param::audio::encoded::{AacStreamFormat, AmrBandMode, WmaProfile};
+use crate::param::audio::iec958::Iec958Codec;
+use crate::param::audio::raw::{AudioChannel, AudioFormat};
+use crate::param::buffers::{Buffers, Io, IoType, Meta, MetaType};
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::latency::{Latency, ProcessLatency};
+use crate::param::port_config::{PortConfig, PortConfigMode};
+use crate::param::profile::Profile;
+use crate::param::props::{Prop, PropInfo};
+use crate::param::route::Route;
+use crate::param::tag::Tag;
+use crate::param::video::color::{
+    VideoColorMatrix, VideoColorPrimaries, VideoColorRange, VideoTransferFunction,
+};
+use crate::param::video::encoded::{
+    H264Alignment, H264StreamFormat, H265Alignment, H265StreamFormat,
+};
+use crate::param::video::raw::{VideoFormat, VideoInterlaceMode, VideoMultiviewMode};
+use crate::param::{Availability, Direction, ParamType};
+
+use super::types::{ChoiceType, ObjectType, PropertyFlags};
+use super::value::{ChoiceValue, Value};
+use super::RawPod;
+
+fn name_of<T: TryFrom<u32> + fmt::Debug>(value: u32) -> Option<String> {
+    T::try_from(value).ok().map(|v| format!("{:?}", v))
+}
+
+pub(super) fn object_type_name(type_: u32) -> String {
+    name_of::<ObjectType>(type_).unwrap_or_else(|| format!("{:#x}", type_))
+}
+
+fn param_name(id: u32) -> String {
+    name_of::<ParamType>(id).unwrap_or_else(|| format!("{}", id))
+}
+
+pub(super) fn key_name(object_type: u32, key: u32) -> String {
+    let name = match ObjectType::try_from(object_type) {
+        Ok(ObjectType::PropInfo) => name_of::<PropInfo>(key),
+        Ok(ObjectType::Props) => name_of::<Prop>(key),
+        Ok(ObjectType::Format) => name_of::<Format>(key),
+        Ok(ObjectType::ParamBuffers) => name_of::<Buffers>(key),
+        Ok(ObjectType::ParamMeta) => name_of::<Meta>(key),
+        Ok(ObjectType::ParamIo) => name_of::<Io>(key),
+        Ok(ObjectType::ParamProfile) => name_of::<Profile>(key),
+        Ok(ObjectType::ParamPortConfig) => name_of::<PortConfig>(key),
+        Ok(ObjectType::ParamRoute) => name_of::<Route>(key),
+        Ok(ObjectType::ParamTag) => name_of::<Tag>(key),
+        Ok(ObjectType::ParamLatency) => name_of::<Latency>(key),
+        Ok(ObjectType::ParamProcessLatency) => name_of::<ProcessLatency>(key),
+        _ => None,
+    };
+
+    name.unwrap_or_else(|| format!("{:#x}", key))
+}
+
+// Name of an Id value, for properties whose values are known enumerations
+fn id_name(object_type: u32, key: u32, id: u32) -> Option<String> {
+    match ObjectType::try_from(object_type) {
+        Ok(ObjectType::Format) => match Format::try_from(key) {
+            Ok(Format::MediaType) => name_of::<MediaType>(id),
+            Ok(Format::MediaSubtype) => name_of::<MediaSubtype>(id),
+            Ok(Format::AudioFormat) => name_of::<AudioFormat>(id),
+            Ok(Format::AudioPosition) => name_of::<AudioChannel>(id),
+            Ok(Format::AudioIec958Codec) => name_of::<Iec958Codec>(id),
+            Ok(Format::AudioBitorder) => name_of::<BitOrder>(id),
+            Ok(Format::AudioAacStreamFormat) => name_of::<AacStreamFormat>(id),
+            Ok(Format::AudioWmaProfile) => name_of::<WmaProfile>(id),
+            Ok(Format::AudioAmrBandMode) => name_of::<AmrBandMode>(id),
+            Ok(Format::VideoFormat) => name_of::<VideoFormat>(id),
+            Ok(Format::VideoInterlaceMode) => name_of::<VideoInterlaceMode>(id),
+            Ok(Format::VideoMultiviewMode) => name_of::<VideoMultiviewMode>(id),
+            Ok(Format::VideoColorRange) => name_of::<VideoColorRange>(id),
+            Ok(Format::VideoColorMatrix) => name_of::<VideoColorMatrix>(id),
+            Ok(Format::VideoTransferFunction) => name_of::<VideoTransferFunction>(id),
+            Ok(Format::VideoColorPrimaries) => name_of::<VideoColorPrimaries>(id),
+            Ok(Format::VideoH264StreamFormat) => name_of::<H264StreamFormat>(id),
+            Ok(Format::VideoH264Alignment) => name_of::<H264Alignment>(id),
+            Ok(Format::VideoH265StreamFormat) => name_of::<H265StreamFormat>(id),
+            Ok(Format::VideoH265Alignment) => name_of::<H265Alignment>(id),
+            _ => None,
+        },
+        Ok(ObjectType::Props) => match Prop::try_from(key) {
+            Ok(Prop::ChannelMap) => name_of::<AudioChannel>(id),
+            Ok(Prop::Iec958Codecs) => name_of::<Iec958Codec>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamMeta) => match Meta::try_from(key) {
+            Ok(Meta::Type) => name_of::<MetaType>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamIo) => match Io::try_from(key) {
+            Ok(Io::Id) => name_of::<IoType>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamProfile) => match Profile::try_from(key) {
+            Ok(Profile::Available) => name_of::<Availability>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamPortConfig) => match PortConfig::try_from(key) {
+            Ok(PortConfig::Direction) => name_of::<Direction>(id),
+            Ok(PortConfig::Mode) => name_of::<PortConfigMode>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamRoute) => match Route::try_from(key) {
+            Ok(Route::Direction) => name_of::<Direction>(id),
+            Ok(Route::Available) => name_of::<Availability>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamTag) => match Tag::try_from(key) {
+            Ok(Tag::Direction) => name_of::<Direction>(id),
+            _ => None,
+        },
+        Ok(ObjectType::ParamLatency) => match Latency::try_from(key) {
+            Ok(Latency::Direction) => name_of::<Direction>(id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn flags_string(flags: PropertyFlags) -> String {
+    let mut s = String::new();
+    // Writing to a String can't fail
+    let _ = bitflags::parser::to_writer(&flags, &mut s);
+    s
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(
+        value,
+        Value::Array(_)
+            | Value::Struct(_)
+            | Value::Object(_)
+            | Value::Sequence(_)
+            | Value::Choice(_)
+    )
+}
+
+// Key of the property being printed, used to resolve ids to names
+type Context = Option<(u32, u32)>;
+
+// The value of a scalar, without its type
+fn scalar_string(value: &Value, context: Context) -> String {
+    match value {
+        Value::None => "null".to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::Id(v) => match context.and_then(|(t, k)| id_name(t, k, *v)) {
+            Some(name) => format!("{} ({})", name, v),
+            None => v.to_string(),
+        },
+        Value::Int(v) => v.to_string(),
+        Value::Long(v) => v.to_string(),
+        Value::Float(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::String(v) => format!("{:?}", v),
+        Value::Bytes(v) | Value::Bitmap(v) => format!("[{}]", hex_string(v)),
+        Value::Rectangle(v) => format!("{}x{}", v.width, v.height),
+        Value::Fraction(v) => format!("{}/{}", v.num, v.denom),
+        Value::Pointer(v) => format!("{:?} {:p}", v.type_, v.ptr),
+        Value::Fd(v) => v.0.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn choice_string(choice: &ChoiceValue, context: Context) -> String {
+    let values = choice
+        .values
+        .iter()
+        .map(|v| match (choice.type_, v) {
+            // Flags are easier to read in hex
+            (ChoiceType::Flags, Value::Int(i)) => format!("{:#x}", i),
+            (ChoiceType::Flags, Value::Long(l)) => format!("{:#x}", l),
+            _ => scalar_string(v, context),
+        })
+        .collect::<Vec<_>>();
+
+    let labels: &[&str] = match choice.type_ {
+        ChoiceType::None => &["value"],
+        ChoiceType::Range => &["default", "min", "max"],
+        ChoiceType::Step => &["default", "min", "max", "step"],
+        ChoiceType::Flags => &["default", "flags"],
+        ChoiceType::Enum => {
+            return match values.split_first() {
+                Some((default, alternatives)) => format!(
+                    "default {}, alternatives [{}]",
+                    default,
+                    alternatives.join(", ")
+                ),
+                None => "empty".to_string(),
+            }
+        }
+    };
+
+    labels
+        .iter()
+        .zip(values.iter())
+        .map(|(label, value)| format!("{} {}", label, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn write_value(
+    lines: &mut Vec<String>,
+    value: &Value,
+    depth: usize,
+    prefix: &str,
+    context: Context,
+) {
+    let indent = "  ".repeat(depth);
+
+    match value {
+        Value::Array(array) if array.values.iter().all(is_scalar) => {
+            let values = array
+                .values
+                .iter()
+                .map(|v| scalar_string(v, context))
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "{}{}Array of {:?}: [{}]",
+                indent,
+                prefix,
+                array.child_type,
+                values.join(", ")
+            ));
+        }
+        Value::Array(array) => {
+            lines.push(format!(
+                "{}{}Array of {:?}:",
+                indent, prefix, array.child_type
+            ));
+            for v in &array.values {
+                write_value(lines, v, depth + 1, "", context);
+            }
+        }
+        Value::Choice(choice) => {
+            lines.push(format!(
+                "{}{}Choice {:?} of {:?}: {}",
+                indent,
+                prefix,
+                choice.type_,
+                choice.child_type,
+                choice_string(choice, context)
+            ));
+        }
+        Value::Struct(values) => {
+            lines.push(format!("{}{}Struct:", indent, prefix));
+            for v in values {
+                write_value(lines, v, depth + 1, "", None);
+            }
+        }
+        Value::Object(object) => {
+            lines.push(format!(
+                "{}{}Object {}, id {}:",
+                indent,
+                prefix,
+                object_type_name(object.type_),
+                param_name(object.id)
+            ));
+            for prop in &object.properties {
+                let key = key_name(object.type_, prop.key);
+                let prefix = if prop.flags.is_empty() {
+                    format!("{}: ", key)
+                } else {
+                    format!("{} ({}): ", key, flags_string(prop.flags))
+                };
+
+                write_value(
+                    lines,
+                    &prop.value,
+                    depth + 1,
+                    &prefix,
+                    Some((object.type_, prop.key)),
+                );
+            }
+        }
+        Value::Sequence(sequence) => {
+            lines.push(format!(
+                "{}{}Sequence, unit {}:",
+                indent, prefix, sequence.unit
+            ));
+            for control in &sequence.controls {
+                let prefix = format!("Control {:?} at {}: ", control.type_, control.offset);
+                write_value(lines, &control.value, depth + 1, &prefix, None);
+            }
+        }
+        Value::None => lines.push(format!("{}{}None", indent, prefix)),
+        v => lines.push(format!(
+            "{}{}{:?} {}",
+            indent,
+            prefix,
+            v.type_(),
+            scalar_string(v, context)
+        )),
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        write_value(&mut lines, self, 0, "", None);
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl fmt::Display for RawPod<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.decode::<Value>() {
+            Ok(value) => value.fmt(f),
+            Err(_) => write!(
+                f,
+                "Invalid {:?} pod ({} bytes)",
+                self.type_(),
+                self.total_size()
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for RawPod<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawPod")
+            .field("type_", &self.type_())
+            .field("size", &self.total_size())
+            .finish()
+    }
+}
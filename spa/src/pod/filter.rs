@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Intersection and fixation of pods, following spa/pod/filter.h. This is what format negotiation
+// uses to find the formats that both sides of a link support, and then pick one of them.
+
+use std::cmp::Ordering;
+
+use super::compare::compare_value;
+use super::types::{ChoiceType, PropertyFlags, Type};
+use super::value::{ChoiceValue, ObjectValue, PropertyValue, Value};
+use super::{Error, Pod};
+
+// Filter `pod` with `filter`, returning the resulting pod. Object properties are intersected,
+// properties only present on one side are kept, unless the other side marks them as mandatory.
+pub fn filter(pod: &[u8], filter: &[u8]) -> Result<Vec<u8>, Error> {
+    let (pod, _) = Value::decode(pod)?;
+    let (filter, _) = Value::decode(filter)?;
+
+    let mut res = filter_part(&[pod], &[filter])?;
+
+    res.pop().ok_or(Error::Invalid)?.to_bytes()
+}
+
+// Fixate all choices in an object to their default value, like spa_pod_fixate(). The default is
+// first clamped to the possible values. Properties with the DONT_FIXATE flag are left as is.
+pub fn fixate(pod: &[u8]) -> Result<Vec<u8>, Error> {
+    let (Value::Object(mut object), _) = Value::decode(pod)? else {
+        return Err(Error::Invalid);
+    };
+
+    for prop in &mut object.properties {
+        if prop.flags.contains(PropertyFlags::DONT_FIXATE) {
+            continue;
+        }
+
+        if let Value::Choice(choice) = &mut prop.value {
+            if choice.values.is_empty() {
+                return Err(Error::Invalid);
+            }
+
+            fix_default(choice);
+            choice.type_ = ChoiceType::None;
+            choice.values.truncate(1);
+        }
+    }
+
+    Value::Object(object).to_bytes()
+}
+
+// Filters a list of pods element-wise, extra pods that have no filter counterpart are copied
+fn filter_part(pods: &[Value], filters: &[Value]) -> Result<Vec<Value>, Error> {
+    let mut res = Vec::with_capacity(pods.len());
+    let mut filters = filters.iter();
+
+    for pod in pods {
+        let Some(filter) = filters.next() else {
+            res.push(pod.clone());
+            continue;
+        };
+
+        match (pod, filter) {
+            (Value::Object(p), Value::Object(f)) => res.push(Value::Object(filter_object(p, f)?)),
+            (Value::Struct(p), Value::Struct(f)) => res.push(Value::Struct(filter_part(p, f)?)),
+            (Value::Object(_), _) | (Value::Struct(_), _) => return Err(Error::Invalid),
+            // Anything else must match exactly
+            (p, f) => {
+                if p.to_bytes()? != f.to_bytes()? {
+                    return Err(Error::Invalid);
+                }
+                res.push(p.clone());
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+fn filter_object(pod: &ObjectValue, filter: &ObjectValue) -> Result<ObjectValue, Error> {
+    let mut properties = Vec::new();
+
+    for p1 in &pod.properties {
+        match filter.properties.iter().find(|p| p.key == p1.key) {
+            Some(p2) => properties.push(filter_prop(p1, p2)?),
+            None if p1.flags.contains(PropertyFlags::MANDATORY) => return Err(Error::Invalid),
+            None => properties.push(p1.clone()),
+        }
+    }
+
+    for p2 in &filter.properties {
+        if pod.properties.iter().any(|p| p.key == p2.key) {
+            continue;
+        }
+        if p2.flags.contains(PropertyFlags::MANDATORY) {
+            return Err(Error::Invalid);
+        }
+        properties.push(p2.clone());
+    }
+
+    Ok(ObjectValue {
+        type_: pod.type_,
+        id: pod.id,
+        properties,
+    })
+}
+
+// The choice type and values of a property, a plain value is treated as a choice of one
+fn get_values(value: &Value) -> Result<(ChoiceType, Type, &[Value]), Error> {
+    match value {
+        Value::Choice(c) if c.values.is_empty() => Err(Error::Invalid),
+        Value::Choice(c) if c.type_ == ChoiceType::None => {
+            Ok((c.type_, c.child_type, &c.values[..1]))
+        }
+        Value::Choice(c) => Ok((c.type_, c.child_type, &c.values)),
+        v => Ok((ChoiceType::None, v.type_(), std::slice::from_ref(v))),
+    }
+}
+
+// The values to intersect, which for ranges, steps and enums are the ones after the default
+fn alternatives(choice: ChoiceType, values: &[Value]) -> &[Value] {
+    match choice {
+        ChoiceType::None | ChoiceType::Flags => &values[..1],
+        _ => &values[1..],
+    }
+}
+
+// The min and max of a range or step choice
+fn range(values: &[Value]) -> Result<(&Value, &Value), Error> {
+    match values {
+        [min, max, ..] => Ok((min, max)),
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn step(values: &[Value]) -> Result<(&Value, &Value, &Value), Error> {
+    match values {
+        [min, max, step, ..] => Ok((min, max, step)),
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn in_range(value: &Value, min: &Value, max: &Value) -> bool {
+    compare_value(value, min) != Ordering::Less && compare_value(value, max) != Ordering::Greater
+}
+
+fn is_step_of(value: &Value, step: &Value) -> Result<bool, Error> {
+    match (value, step) {
+        (Value::Int(v), Value::Int(s)) => v.checked_rem(*s).map(|r| r == 0),
+        (Value::Long(v), Value::Long(s)) => v.checked_rem(*s).map(|r| r == 0),
+        (Value::Rectangle(v), Value::Rectangle(s)) => v
+            .width
+            .checked_rem(s.width)
+            .zip(v.height.checked_rem(s.height))
+            .map(|(w, h)| w == 0 && h == 0),
+        _ => None,
+    }
+    .ok_or(Error::Invalid)
+}
+
+fn flags_value(a: &Value, b: &Value) -> Result<Value, Error> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) if a & b != 0 => Ok(Value::Int(a & b)),
+        (Value::Long(a), Value::Long(b)) if a & b != 0 => Ok(Value::Long(a & b)),
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn filter_prop(p1: &PropertyValue, p2: &PropertyValue) -> Result<PropertyValue, Error> {
+    let (c1, type_, v1) = get_values(&p1.value)?;
+    let (c2, type2, v2) = get_values(&p2.value)?;
+
+    // Incompatible property types
+    if type_ != type2 || v1[0].to_bytes()?.len() != v2[0].to_bytes()?.len() {
+        return Err(Error::Invalid);
+    }
+
+    let alt1 = alternatives(c1, v1);
+    let alt2 = alternatives(c2, v2);
+
+    // The default of the pod is kept, and fixed up afterwards
+    let mut values = vec![v1[0].clone()];
+
+    let choice = match (c1, c2) {
+        (ChoiceType::None | ChoiceType::Enum, ChoiceType::None | ChoiceType::Enum) => {
+            let mut n_copied = 0;
+
+            // Copy all equal values, but don't copy a plain value again as it is the default
+            for (j, a1) in alt1.iter().enumerate() {
+                for a2 in alt2 {
+                    if compare_value(a1, a2) == Ordering::Equal {
+                        if c1 == ChoiceType::Enum || j > 0 {
+                            values.push(a1.clone());
+                        }
+                        n_copied += 1;
+                    }
+                }
+            }
+
+            if n_copied == 0 {
+                return Err(Error::Invalid);
+            }
+            ChoiceType::Enum
+        }
+        (ChoiceType::None | ChoiceType::Enum, ChoiceType::Range) => {
+            let (min, max) = range(alt2)?;
+
+            values.extend(alt1.iter().filter(|a| in_range(a, min, max)).cloned());
+
+            if values.len() == 1 {
+                return Err(Error::Invalid);
+            }
+            ChoiceType::Enum
+        }
+        (ChoiceType::None | ChoiceType::Enum, ChoiceType::Step) => {
+            let (min, max, step) = step(alt2)?;
+
+            for a1 in alt1 {
+                if in_range(a1, min, max) && is_step_of(a1, step)? {
+                    values.push(a1.clone());
+                }
+            }
+
+            if values.len() == 1 {
+                return Err(Error::Invalid);
+            }
+            ChoiceType::Enum
+        }
+        (ChoiceType::Range, ChoiceType::None | ChoiceType::Enum) => {
+            let (min, max) = range(alt1)?;
+
+            values.extend(alt2.iter().filter(|a| in_range(a, min, max)).cloned());
+
+            if values.len() == 1 {
+                return Err(Error::Invalid);
+            }
+            ChoiceType::Enum
+        }
+        (ChoiceType::Step, ChoiceType::None | ChoiceType::Enum) => {
+            let (min, max, step) = step(alt1)?;
+
+            for a2 in alt2 {
+                if in_range(a2, min, max) && is_step_of(a2, step)? {
+                    values.push(a2.clone());
+                }
+            }
+
+            if values.len() == 1 {
+                return Err(Error::Invalid);
+            }
+            ChoiceType::Enum
+        }
+        (ChoiceType::Range | ChoiceType::Step, ChoiceType::Range | ChoiceType::Step) => {
+            let (min1, max1) = range(alt1)?;
+            let (min2, max2) = range(alt2)?;
+
+            // The step is not carried over, so the result is always a range
+            values.push(if compare_value(min1, min2) == Ordering::Less {
+                min2.clone()
+            } else {
+                min1.clone()
+            });
+            values.push(if compare_value(max1, max2) == Ordering::Less {
+                max1.clone()
+            } else {
+                max2.clone()
+            });
+            ChoiceType::Range
+        }
+        (ChoiceType::None | ChoiceType::Flags, ChoiceType::None | ChoiceType::Flags) => {
+            values.push(flags_value(&alt1[0], &alt2[0])?);
+            ChoiceType::Flags
+        }
+        // Everything else, such as flags against ranges, can't be intersected
+        _ => return Err(Error::Invalid),
+    };
+
+    let mut choice = ChoiceValue {
+        type_: choice,
+        flags: 0,
+        child_type: type_,
+        values,
+    };
+    fix_default(&mut choice);
+
+    Ok(PropertyValue {
+        key: p1.key,
+        flags: p1.flags & p2.flags,
+        value: Value::Choice(choice),
+    })
+}
+
+// Make sure the default is one of the possible values, like spa_pod_choice_fix_default()
+fn fix_default(choice: &mut ChoiceValue) {
+    let values = &mut choice.values;
+
+    match choice.type_ {
+        ChoiceType::None => (),
+        ChoiceType::Range | ChoiceType::Step => {
+            if values.len() > 1 && compare_value(&values[0], &values[1]) == Ordering::Less {
+                values[0] = values[1].clone();
+            }
+            if values.len() > 2 && compare_value(&values[0], &values[2]) == Ordering::Greater {
+                values[0] = values[2].clone();
+            }
+        }
+        ChoiceType::Enum | ChoiceType::Flags => {
+            let best = values[1..]
+                .iter()
+                .find(|v| compare_value(&values[0], v) == Ordering::Equal)
+                .or_else(|| values.get(1))
+                .cloned();
+
+            if let Some(best) = best {
+                values[0] = best;
+            }
+            if values.len() <= 1 {
+                choice.type_ = ChoiceType::None;
+            }
+        }
+    }
+}
@@ -0,0 +1,1463 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Conversion between pods and JSON, using the same layout as pw-dump:
+//
+//   objects      { "key": value, ... } with the short SPA names of the keys, or "id-%08x"
+//   structs      [ value, ... ]
+//   arrays       [ value, ... ]
+//   ids          the short SPA name of the id if it is known, or "id-%08x"
+//   rectangles   { "width": w, "height": h }
+//   fractions    { "num": n, "denom": d }
+//   choices      { "default": v, "min": v, "max": v(, "step": v) } for ranges and steps,
+//                { "default": v, "alt1": v, ... } for enums,
+//                { "default": v, "flag1": v, ... } for flags
+//
+// Bytes, bitmaps, pointers and sequences are written as null, and property flags are not kept.
+//
+// JSON has less type information than pods, so when converting back, values of known keys are
+// converted to the type SPA uses for that key, and objects nested in them get the object type and id
+// SPA uses for that key. Other values are guessed as spa_json_to_pod() does: integers become Int,
+// other numbers Float, and arrays become Structs.
+
+use std::collections::HashMap;
+
+use tinyjson::JsonValue;
+
+use crate::param::audio::dsd::BitOrder;
+use crate::param::audio::encoded::{AacStreamFormat, AmrBandMode, WmaProfile};
+use crate::param::audio::iec958::Iec958Codec;
+use crate::param::audio::raw::{AudioChannel, AudioFormat};
+use crate::param::buffers::{Buffers, Io, IoType, Meta, MetaType};
+use crate::param::format::{Format, MediaSubtype, MediaType};
+use crate::param::latency::{Latency, ProcessLatency};
+use crate::param::port_config::{PortConfig, PortConfigMode};
+use crate::param::profile::Profile;
+use crate::param::props::{Prop, PropInfo};
+use crate::param::route::Route;
+use crate::param::tag::Tag;
+use crate::param::video::color::{
+    VideoColorMatrix, VideoColorPrimaries, VideoColorRange, VideoTransferFunction,
+};
+use crate::param::video::encoded::{
+    H264Alignment, H264StreamFormat, H265Alignment, H265StreamFormat,
+};
+use crate::param::video::raw::{VideoFormat, VideoInterlaceMode, VideoMultiviewMode};
+use crate::param::{Availability, Direction, ParamType};
+
+use super::parser::Parser;
+use super::types::{ChoiceType, Fd, Fraction, ObjectType, PropertyFlags, Rectangle, Type};
+use super::value::{ArrayValue, ChoiceValue, ObjectValue, PropertyValue, Value};
+use super::Error;
+
+// Short names of ids, as found in the SPA type info
+type Names = &'static [(u32, &'static str)];
+
+const NO_NAMES: Names = &[];
+
+const TYPES: Names = &[
+    (Type::None as u32, "None"),
+    (Type::Bool as u32, "Bool"),
+    (Type::Id as u32, "Id"),
+    (Type::Int as u32, "Int"),
+    (Type::Long as u32, "Long"),
+    (Type::Float as u32, "Float"),
+    (Type::Double as u32, "Double"),
+    (Type::String as u32, "String"),
+    (Type::Bytes as u32, "Bytes"),
+    (Type::Rectangle as u32, "Rectangle"),
+    (Type::Fraction as u32, "Fraction"),
+    (Type::Bitmap as u32, "Bitmap"),
+    (Type::Array as u32, "Array"),
+    (Type::Struct as u32, "Struct"),
+    (Type::Object as u32, "Object"),
+    (Type::Sequence as u32, "Sequence"),
+    (Type::Pointer as u32, "Pointer"),
+    (Type::Fd as u32, "Fd"),
+    (Type::Choice as u32, "Choice"),
+    (Type::Pod as u32, "Pod"),
+];
+
+const MEDIA_TYPES: Names = &[
+    (MediaType::Unknown as u32, "unknown"),
+    (MediaType::Audio as u32, "audio"),
+    (MediaType::Video as u32, "video"),
+    (MediaType::Image as u32, "image"),
+    (MediaType::Binary as u32, "binary"),
+    (MediaType::Stream as u32, "stream"),
+    (MediaType::Application as u32, "application"),
+];
+
+const MEDIA_SUBTYPES: Names = &[
+    (MediaSubtype::Unknown as u32, "unknown"),
+    (MediaSubtype::Raw as u32, "raw"),
+    (MediaSubtype::Dsp as u32, "dsp"),
+    (MediaSubtype::Iec958 as u32, "iec958"),
+    (MediaSubtype::Dsd as u32, "dsd"),
+    (MediaSubtype::Mp3 as u32, "mp3"),
+    (MediaSubtype::Aac as u32, "aac"),
+    (MediaSubtype::Vorbis as u32, "vorbis"),
+    (MediaSubtype::Wma as u32, "wma"),
+    (MediaSubtype::Ra as u32, "ra"),
+    (MediaSubtype::Sbc as u32, "sbc"),
+    (MediaSubtype::Adpcm as u32, "adpcm"),
+    (MediaSubtype::G723 as u32, "g723"),
+    (MediaSubtype::G726 as u32, "g726"),
+    (MediaSubtype::G729 as u32, "g729"),
+    (MediaSubtype::Amr as u32, "amr"),
+    (MediaSubtype::Gsm as u32, "gsm"),
+    (MediaSubtype::Alac as u32, "alac"),
+    (MediaSubtype::Flac as u32, "flac"),
+    (MediaSubtype::Ape as u32, "ape"),
+    (MediaSubtype::Opus as u32, "opus"),
+    (MediaSubtype::H264 as u32, "h264"),
+    (MediaSubtype::Mjpg as u32, "mjpg"),
+    (MediaSubtype::Dv as u32, "dv"),
+    (MediaSubtype::Mpegts as u32, "mpegts"),
+    (MediaSubtype::H263 as u32, "h263"),
+    (MediaSubtype::Mpeg1 as u32, "mpeg1"),
+    (MediaSubtype::Mpeg2 as u32, "mpeg2"),
+    (MediaSubtype::Mpeg4 as u32, "mpeg4"),
+    (MediaSubtype::Xvid as u32, "xvid"),
+    (MediaSubtype::Vc1 as u32, "vc1"),
+    (MediaSubtype::Vp8 as u32, "vp8"),
+    (MediaSubtype::Vp9 as u32, "vp9"),
+    (MediaSubtype::Bayer as u32, "bayer"),
+    (MediaSubtype::H265 as u32, "h265"),
+    (MediaSubtype::Jpeg as u32, "jpeg"),
+    (MediaSubtype::Midi as u32, "midi"),
+    (MediaSubtype::Control as u32, "control"),
+];
+
+const AUDIO_FORMATS: Names = &[
+    (AudioFormat::Unknown as u32, "UNKNOWN"),
+    (AudioFormat::Encoded as u32, "ENCODED"),
+    (AudioFormat::S8 as u32, "S8"),
+    (AudioFormat::U8 as u32, "U8"),
+    (AudioFormat::S16LE as u32, "S16LE"),
+    (AudioFormat::S16BE as u32, "S16BE"),
+    (AudioFormat::U16LE as u32, "U16LE"),
+    (AudioFormat::U16BE as u32, "U16BE"),
+    (AudioFormat::S24_32LE as u32, "S24_32LE"),
+    (AudioFormat::S24_32BE as u32, "S24_32BE"),
+    (AudioFormat::U24_32LE as u32, "U24_32LE"),
+    (AudioFormat::U24_32BE as u32, "U24_32BE"),
+    (AudioFormat::S32LE as u32, "S32LE"),
+    (AudioFormat::S32BE as u32, "S32BE"),
+    (AudioFormat::U32LE as u32, "U32LE"),
+    (AudioFormat::U32BE as u32, "U32BE"),
+    (AudioFormat::S24LE as u32, "S24LE"),
+    (AudioFormat::S24BE as u32, "S24BE"),
+    (AudioFormat::U24LE as u32, "U24LE"),
+    (AudioFormat::U24BE as u32, "U24BE"),
+    (AudioFormat::S20LE as u32, "S20LE"),
+    (AudioFormat::S20BE as u32, "S20BE"),
+    (AudioFormat::U20LE as u32, "U20LE"),
+    (AudioFormat::U20BE as u32, "U20BE"),
+    (AudioFormat::S18LE as u32, "S18LE"),
+    (AudioFormat::S18BE as u32, "S18BE"),
+    (AudioFormat::U18LE as u32, "U18LE"),
+    (AudioFormat::U18BE as u32, "U18BE"),
+    (AudioFormat::F32LE as u32, "F32LE"),
+    (AudioFormat::F32BE as u32, "F32BE"),
+    (AudioFormat::F64LE as u32, "F64LE"),
+    (AudioFormat::F64BE as u32, "F64BE"),
+    (AudioFormat::Ulaw as u32, "ULAW"),
+    (AudioFormat::Alaw as u32, "ALAW"),
+    (AudioFormat::U8P as u32, "U8P"),
+    (AudioFormat::S16P as u32, "S16P"),
+    (AudioFormat::S24_32P as u32, "S24_32P"),
+    (AudioFormat::S32P as u32, "S32P"),
+    (AudioFormat::S24P as u32, "S24P"),
+    (AudioFormat::F32P as u32, "F32P"),
+    (AudioFormat::F64P as u32, "F64P"),
+    (AudioFormat::S8P as u32, "S8P"),
+];
+
+const AUDIO_CHANNELS: Names = &[
+    (AudioChannel::Unknown as u32, "UNK"),
+    (AudioChannel::NA as u32, "NA"),
+    (AudioChannel::Mono as u32, "MONO"),
+    (AudioChannel::FL as u32, "FL"),
+    (AudioChannel::FR as u32, "FR"),
+    (AudioChannel::FC as u32, "FC"),
+    (AudioChannel::LFE as u32, "LFE"),
+    (AudioChannel::SL as u32, "SL"),
+    (AudioChannel::SR as u32, "SR"),
+    (AudioChannel::FLC as u32, "FLC"),
+    (AudioChannel::FRC as u32, "FRC"),
+    (AudioChannel::RC as u32, "RC"),
+    (AudioChannel::RL as u32, "RL"),
+    (AudioChannel::RR as u32, "RR"),
+    (AudioChannel::TC as u32, "TC"),
+    (AudioChannel::TFL as u32, "TFL"),
+    (AudioChannel::TFC as u32, "TFC"),
+    (AudioChannel::TFR as u32, "TFR"),
+    (AudioChannel::TRL as u32, "TRL"),
+    (AudioChannel::TRC as u32, "TRC"),
+    (AudioChannel::TRR as u32, "TRR"),
+    (AudioChannel::RLC as u32, "RLC"),
+    (AudioChannel::RRC as u32, "RRC"),
+    (AudioChannel::FLW as u32, "FLW"),
+    (AudioChannel::FRW as u32, "FRW"),
+    (AudioChannel::LFE2 as u32, "LFE2"),
+    (AudioChannel::FLH as u32, "FLH"),
+    (AudioChannel::FCH as u32, "FCH"),
+    (AudioChannel::FRH as u32, "FRH"),
+    (AudioChannel::TFLC as u32, "TFLC"),
+    (AudioChannel::TFRC as u32, "TFRC"),
+    (AudioChannel::TSL as u32, "TSL"),
+    (AudioChannel::TSR as u32, "TSR"),
+    (AudioChannel::LLFE as u32, "LLFE"),
+    (AudioChannel::RLFE as u32, "RLFE"),
+    (AudioChannel::BC as u32, "BC"),
+    (AudioChannel::BLC as u32, "BLC"),
+    (AudioChannel::BRC as u32, "BRC"),
+    (AudioChannel::Aux0 as u32, "AUX0"),
+    (AudioChannel::Aux1 as u32, "AUX1"),
+    (AudioChannel::Aux2 as u32, "AUX2"),
+    (AudioChannel::Aux3 as u32, "AUX3"),
+    (AudioChannel::Aux4 as u32, "AUX4"),
+    (AudioChannel::Aux5 as u32, "AUX5"),
+    (AudioChannel::Aux6 as u32, "AUX6"),
+    (AudioChannel::Aux7 as u32, "AUX7"),
+    (AudioChannel::Aux8 as u32, "AUX8"),
+    (AudioChannel::Aux9 as u32, "AUX9"),
+    (AudioChannel::Aux10 as u32, "AUX10"),
+    (AudioChannel::Aux11 as u32, "AUX11"),
+    (AudioChannel::Aux12 as u32, "AUX12"),
+    (AudioChannel::Aux13 as u32, "AUX13"),
+    (AudioChannel::Aux14 as u32, "AUX14"),
+    (AudioChannel::Aux15 as u32, "AUX15"),
+    (AudioChannel::Aux16 as u32, "AUX16"),
+    (AudioChannel::Aux17 as u32, "AUX17"),
+    (AudioChannel::Aux18 as u32, "AUX18"),
+    (AudioChannel::Aux19 as u32, "AUX19"),
+    (AudioChannel::Aux20 as u32, "AUX20"),
+    (AudioChannel::Aux21 as u32, "AUX21"),
+    (AudioChannel::Aux22 as u32, "AUX22"),
+    (AudioChannel::Aux23 as u32, "AUX23"),
+    (AudioChannel::Aux24 as u32, "AUX24"),
+    (AudioChannel::Aux25 as u32, "AUX25"),
+    (AudioChannel::Aux26 as u32, "AUX26"),
+    (AudioChannel::Aux27 as u32, "AUX27"),
+    (AudioChannel::Aux28 as u32, "AUX28"),
+    (AudioChannel::Aux29 as u32, "AUX29"),
+    (AudioChannel::Aux30 as u32, "AUX30"),
+    (AudioChannel::Aux31 as u32, "AUX31"),
+    (AudioChannel::Aux32 as u32, "AUX32"),
+    (AudioChannel::Aux33 as u32, "AUX33"),
+    (AudioChannel::Aux34 as u32, "AUX34"),
+    (AudioChannel::Aux35 as u32, "AUX35"),
+    (AudioChannel::Aux36 as u32, "AUX36"),
+    (AudioChannel::Aux37 as u32, "AUX37"),
+    (AudioChannel::Aux38 as u32, "AUX38"),
+    (AudioChannel::Aux39 as u32, "AUX39"),
+    (AudioChannel::Aux40 as u32, "AUX40"),
+    (AudioChannel::Aux41 as u32, "AUX41"),
+    (AudioChannel::Aux42 as u32, "AUX42"),
+    (AudioChannel::Aux43 as u32, "AUX43"),
+    (AudioChannel::Aux44 as u32, "AUX44"),
+    (AudioChannel::Aux45 as u32, "AUX45"),
+    (AudioChannel::Aux46 as u32, "AUX46"),
+    (AudioChannel::Aux47 as u32, "AUX47"),
+    (AudioChannel::Aux48 as u32, "AUX48"),
+    (AudioChannel::Aux49 as u32, "AUX49"),
+    (AudioChannel::Aux50 as u32, "AUX50"),
+    (AudioChannel::Aux51 as u32, "AUX51"),
+    (AudioChannel::Aux52 as u32, "AUX52"),
+    (AudioChannel::Aux53 as u32, "AUX53"),
+    (AudioChannel::Aux54 as u32, "AUX54"),
+    (AudioChannel::Aux55 as u32, "AUX55"),
+    (AudioChannel::Aux56 as u32, "AUX56"),
+    (AudioChannel::Aux57 as u32, "AUX57"),
+    (AudioChannel::Aux58 as u32, "AUX58"),
+    (AudioChannel::Aux59 as u32, "AUX59"),
+    (AudioChannel::Aux60 as u32, "AUX60"),
+    (AudioChannel::Aux61 as u32, "AUX61"),
+    (AudioChannel::Aux62 as u32, "AUX62"),
+    (AudioChannel::Aux63 as u32, "AUX63"),
+];
+
+const IEC958_CODECS: Names = &[
+    (Iec958Codec::Unknown as u32, "UNKNOWN"),
+    (Iec958Codec::Pcm as u32, "PCM"),
+    (Iec958Codec::Dts as u32, "DTS"),
+    (Iec958Codec::Ac3 as u32, "AC3"),
+    (Iec958Codec::Mpeg as u32, "MPEG"),
+    (Iec958Codec::Mpeg2Aac as u32, "MPEG2-AAC"),
+    (Iec958Codec::Eac3 as u32, "EAC3"),
+    (Iec958Codec::TrueHd as u32, "TrueHD"),
+    (Iec958Codec::DtsHd as u32, "DTS-HD"),
+];
+
+const BIT_ORDERS: Names = &[
+    (BitOrder::Unknown as u32, "unknown"),
+    (BitOrder::Msb as u32, "msb"),
+    (BitOrder::Lsb as u32, "lsb"),
+];
+
+const AAC_STREAM_FORMATS: Names = &[
+    (AacStreamFormat::Unknown as u32, "UNKNOWN"),
+    (AacStreamFormat::Raw as u32, "RAW"),
+    (AacStreamFormat::Mp2Adts as u32, "MP2ADTS"),
+    (AacStreamFormat::Mp4Adts as u32, "MP4ADTS"),
+    (AacStreamFormat::Mp4Loas as u32, "MP4LOAS"),
+    (AacStreamFormat::Mp4Latm as u32, "MP4LATM"),
+    (AacStreamFormat::Adif as u32, "ADIF"),
+    (AacStreamFormat::Mp4ff as u32, "MP4FF"),
+    (AacStreamFormat::Custom as u32, "CUSTOM"),
+];
+
+const WMA_PROFILES: Names = &[
+    (WmaProfile::Unknown as u32, "UNKNOWN"),
+    (WmaProfile::Wma7 as u32, "WMA7"),
+    (WmaProfile::Wma8 as u32, "WMA8"),
+    (WmaProfile::Wma9 as u32, "WMA9"),
+    (WmaProfile::Wma10 as u32, "WMA10"),
+    (WmaProfile::Wma9Pro as u32, "WMA9-Pro"),
+    (WmaProfile::Wma9Lossless as u32, "WMA9-Lossless"),
+    (WmaProfile::Wma10Lossless as u32, "WMA10-Lossless"),
+    (WmaProfile::Custom as u32, "CUSTOM"),
+];
+
+const AMR_BAND_MODES: Names = &[
+    (AmrBandMode::Unknown as u32, "UNKNOWN"),
+    (AmrBandMode::Nb as u32, "NB"),
+    (AmrBandMode::Wb as u32, "WB"),
+];
+
+const META_TYPES: Names = &[
+    (MetaType::Invalid as u32, "Invalid"),
+    (MetaType::Header as u32, "Header"),
+    (MetaType::VideoCrop as u32, "VideoCrop"),
+    (MetaType::VideoDamage as u32, "VideoDamage"),
+    (MetaType::Bitmap as u32, "Bitmap"),
+    (MetaType::Cursor as u32, "Cursor"),
+    (MetaType::Control as u32, "Control"),
+    (MetaType::Busy as u32, "Busy"),
+    (MetaType::VideoTransform as u32, "VideoTransform"),
+    (MetaType::SyncTimeline as u32, "SyncTimeline"),
+];
+
+const IO_TYPES: Names = &[
+    (IoType::Invalid as u32, "Invalid"),
+    (IoType::Buffers as u32, "Buffers"),
+    (IoType::Range as u32, "Range"),
+    (IoType::Clock as u32, "Clock"),
+    (IoType::Latency as u32, "Latency"),
+    (IoType::Control as u32, "Control"),
+    (IoType::Notify as u32, "Notify"),
+    (IoType::Position as u32, "Position"),
+    (IoType::RateMatch as u32, "RateMatch"),
+    (IoType::Memory as u32, "Memory"),
+    (IoType::AsyncBuffers as u32, "AsyncBuffers"),
+];
+
+const VIDEO_FORMATS: Names = &[
+    (VideoFormat::Unknown as u32, "UNKNOWN"),
+    (VideoFormat::Encoded as u32, "ENCODED"),
+    (VideoFormat::I420 as u32, "I420"),
+    (VideoFormat::YV12 as u32, "YV12"),
+    (VideoFormat::YUY2 as u32, "YUY2"),
+    (VideoFormat::UYVY as u32, "UYVY"),
+    (VideoFormat::AYUV as u32, "AYUV"),
+    (VideoFormat::RGBx as u32, "RGBx"),
+    (VideoFormat::BGRx as u32, "BGRx"),
+    (VideoFormat::xRGB as u32, "xRGB"),
+    (VideoFormat::xBGR as u32, "xBGR"),
+    (VideoFormat::RGBA as u32, "RGBA"),
+    (VideoFormat::BGRA as u32, "BGRA"),
+    (VideoFormat::ARGB as u32, "ARGB"),
+    (VideoFormat::ABGR as u32, "ABGR"),
+    (VideoFormat::RGB as u32, "RGB"),
+    (VideoFormat::BGR as u32, "BGR"),
+    (VideoFormat::Y41B as u32, "Y41B"),
+    (VideoFormat::Y42B as u32, "Y42B"),
+    (VideoFormat::YVYU as u32, "YVYU"),
+    (VideoFormat::Y444 as u32, "Y444"),
+    (VideoFormat::v210 as u32, "v210"),
+    (VideoFormat::v216 as u32, "v216"),
+    (VideoFormat::NV12 as u32, "NV12"),
+    (VideoFormat::NV21 as u32, "NV21"),
+    (VideoFormat::GRAY8 as u32, "GRAY8"),
+    (VideoFormat::GRAY16_BE as u32, "GRAY16_BE"),
+    (VideoFormat::GRAY16_LE as u32, "GRAY16_LE"),
+    (VideoFormat::v308 as u32, "v308"),
+    (VideoFormat::RGB16 as u32, "RGB16"),
+    (VideoFormat::BGR16 as u32, "BGR16"),
+    (VideoFormat::RGB15 as u32, "RGB15"),
+    (VideoFormat::BGR15 as u32, "BGR15"),
+    (VideoFormat::UYVP as u32, "UYVP"),
+    (VideoFormat::A420 as u32, "A420"),
+    (VideoFormat::RGB8P as u32, "RGB8P"),
+    (VideoFormat::YUV9 as u32, "YUV9"),
+    (VideoFormat::YVU9 as u32, "YVU9"),
+    (VideoFormat::IYU1 as u32, "IYU1"),
+    (VideoFormat::ARGB64 as u32, "ARGB64"),
+    (VideoFormat::AYUV64 as u32, "AYUV64"),
+    (VideoFormat::r210 as u32, "r210"),
+    (VideoFormat::I420_10BE as u32, "I420_10BE"),
+    (VideoFormat::I420_10LE as u32, "I420_10LE"),
+    (VideoFormat::I422_10BE as u32, "I422_10BE"),
+    (VideoFormat::I422_10LE as u32, "I422_10LE"),
+    (VideoFormat::Y444_10BE as u32, "Y444_10BE"),
+    (VideoFormat::Y444_10LE as u32, "Y444_10LE"),
+    (VideoFormat::GBR as u32, "GBR"),
+    (VideoFormat::GBR_10BE as u32, "GBR_10BE"),
+    (VideoFormat::GBR_10LE as u32, "GBR_10LE"),
+    (VideoFormat::NV16 as u32, "NV16"),
+    (VideoFormat::NV24 as u32, "NV24"),
+    (VideoFormat::NV12_64Z32 as u32, "NV12_64Z32"),
+    (VideoFormat::A420_10BE as u32, "A420_10BE"),
+    (VideoFormat::A420_10LE as u32, "A420_10LE"),
+    (VideoFormat::A422_10BE as u32, "A422_10BE"),
+    (VideoFormat::A422_10LE as u32, "A422_10LE"),
+    (VideoFormat::A444_10BE as u32, "A444_10BE"),
+    (VideoFormat::A444_10LE as u32, "A444_10LE"),
+    (VideoFormat::NV61 as u32, "NV61"),
+    (VideoFormat::P010_10BE as u32, "P010_10BE"),
+    (VideoFormat::P010_10LE as u32, "P010_10LE"),
+    (VideoFormat::IYU2 as u32, "IYU2"),
+    (VideoFormat::VYUY as u32, "VYUY"),
+    (VideoFormat::GBRA as u32, "GBRA"),
+    (VideoFormat::GBRA_10BE as u32, "GBRA_10BE"),
+    (VideoFormat::GBRA_10LE as u32, "GBRA_10LE"),
+    (VideoFormat::GBR_12BE as u32, "GBR_12BE"),
+    (VideoFormat::GBR_12LE as u32, "GBR_12LE"),
+    (VideoFormat::GBRA_12BE as u32, "GBRA_12BE"),
+    (VideoFormat::GBRA_12LE as u32, "GBRA_12LE"),
+    (VideoFormat::I420_12BE as u32, "I420_12BE"),
+    (VideoFormat::I420_12LE as u32, "I420_12LE"),
+    (VideoFormat::I422_12BE as u32, "I422_12BE"),
+    (VideoFormat::I422_12LE as u32, "I422_12LE"),
+    (VideoFormat::Y444_12BE as u32, "Y444_12BE"),
+    (VideoFormat::Y444_12LE as u32, "Y444_12LE"),
+    (VideoFormat::RGBA_F16 as u32, "RGBA_F16"),
+    (VideoFormat::RGBA_F32 as u32, "RGBA_F32"),
+    (VideoFormat::xRGB_210LE as u32, "xRGB_210LE"),
+    (VideoFormat::xBGR_210LE as u32, "xBGR_210LE"),
+    (VideoFormat::RGBx_102LE as u32, "RGBx_102LE"),
+    (VideoFormat::BGRx_102LE as u32, "BGRx_102LE"),
+    (VideoFormat::ARGB_210LE as u32, "ARGB_210LE"),
+    (VideoFormat::ABGR_210LE as u32, "ABGR_210LE"),
+    (VideoFormat::RGBA_102LE as u32, "RGBA_102LE"),
+    (VideoFormat::BGRA_102LE as u32, "BGRA_102LE"),
+];
+
+const VIDEO_INTERLACE_MODES: Names = &[
+    (VideoInterlaceMode::Progressive as u32, "progressive"),
+    (VideoInterlaceMode::Interleaved as u32, "interleaved"),
+    (VideoInterlaceMode::Mixed as u32, "mixed"),
+    (VideoInterlaceMode::Fields as u32, "fields"),
+];
+
+const VIDEO_MULTIVIEW_MODES: Names = &[
+    (VideoMultiviewMode::None as u32, "none"),
+    (VideoMultiviewMode::Mono as u32, "mono"),
+    (VideoMultiviewMode::Left as u32, "left"),
+    (VideoMultiviewMode::Right as u32, "right"),
+    (VideoMultiviewMode::SideBySide as u32, "side-by-side"),
+    (
+        VideoMultiviewMode::SideBySideQuincunx as u32,
+        "side-by-side-quincunx",
+    ),
+    (
+        VideoMultiviewMode::ColumnInterleaved as u32,
+        "column-interleaved",
+    ),
+    (VideoMultiviewMode::RowInterleaved as u32, "row-interleaved"),
+    (VideoMultiviewMode::TopBottom as u32, "top-bottom"),
+    (VideoMultiviewMode::Checkerboard as u32, "checkerboard"),
+    (VideoMultiviewMode::FrameByFrame as u32, "frame-by-frame"),
+    (
+        VideoMultiviewMode::MultiviewFrameByFrame as u32,
+        "multiview-frame-by-frame",
+    ),
+    (VideoMultiviewMode::Separated as u32, "separated"),
+];
+
+const VIDEO_COLOR_RANGES: Names = &[
+    (VideoColorRange::Unknown as u32, "unknown"),
+    (VideoColorRange::Full as u32, "0-255"),
+    (VideoColorRange::Limited as u32, "16-235"),
+];
+
+const VIDEO_COLOR_MATRICES: Names = &[
+    (VideoColorMatrix::Unknown as u32, "unknown"),
+    (VideoColorMatrix::RGB as u32, "rgb"),
+    (VideoColorMatrix::FCC as u32, "fcc"),
+    (VideoColorMatrix::BT709 as u32, "bt709"),
+    (VideoColorMatrix::BT601 as u32, "bt601"),
+    (VideoColorMatrix::SMPTE240M as u32, "smpte240m"),
+    (VideoColorMatrix::BT2020 as u32, "bt2020"),
+];
+
+const VIDEO_TRANSFER_FUNCTIONS: Names = &[
+    (VideoTransferFunction::Unknown as u32, "unknown"),
+    (VideoTransferFunction::Gamma10 as u32, "gamma10"),
+    (VideoTransferFunction::Gamma18 as u32, "gamma18"),
+    (VideoTransferFunction::Gamma20 as u32, "gamma20"),
+    (VideoTransferFunction::Gamma22 as u32, "gamma22"),
+    (VideoTransferFunction::BT709 as u32, "bt709"),
+    (VideoTransferFunction::SMPTE240M as u32, "smpte240m"),
+    (VideoTransferFunction::SRGB as u32, "srgb"),
+    (VideoTransferFunction::Gamma28 as u32, "gamma28"),
+    (VideoTransferFunction::Log100 as u32, "log100"),
+    (VideoTransferFunction::Log316 as u32, "log316"),
+    (VideoTransferFunction::BT2020_12 as u32, "bt2020-12"),
+    (VideoTransferFunction::AdobeRGB as u32, "adobergb"),
+    (VideoTransferFunction::BT2020_10 as u32, "bt2020-10"),
+    (VideoTransferFunction::SMPTE2084 as u32, "smpte2084"),
+    (VideoTransferFunction::AribStdB67 as u32, "arib-std-b67"),
+    (VideoTransferFunction::SMPTE170M as u32, "smpte170m"),
+];
+
+const VIDEO_COLOR_PRIMARIES: Names = &[
+    (VideoColorPrimaries::Unknown as u32, "unknown"),
+    (VideoColorPrimaries::BT709 as u32, "bt709"),
+    (VideoColorPrimaries::BT470M as u32, "bt470m"),
+    (VideoColorPrimaries::BT470BG as u32, "bt470bg"),
+    (VideoColorPrimaries::SMPTE170M as u32, "smpte170m"),
+    (VideoColorPrimaries::SMPTE240M as u32, "smpte240m"),
+    (VideoColorPrimaries::Film as u32, "film"),
+    (VideoColorPrimaries::BT2020 as u32, "bt2020"),
+    (VideoColorPrimaries::AdobeRGB as u32, "adobergb"),
+    (VideoColorPrimaries::SMPTEST428 as u32, "smptest428"),
+    (VideoColorPrimaries::SMPTERP431 as u32, "smpterp431"),
+    (VideoColorPrimaries::SMPTEEG432 as u32, "smpteeg432"),
+    (VideoColorPrimaries::EBU3213 as u32, "ebu3213"),
+];
+
+const H264_STREAM_FORMATS: Names = &[
+    (H264StreamFormat::Unknown as u32, "unknown"),
+    (H264StreamFormat::Avc as u32, "avc"),
+    (H264StreamFormat::Avc3 as u32, "avc3"),
+    (H264StreamFormat::ByteStream as u32, "bytestream"),
+];
+
+const H264_ALIGNMENTS: Names = &[
+    (H264Alignment::Unknown as u32, "unknown"),
+    (H264Alignment::Au as u32, "au"),
+    (H264Alignment::Nal as u32, "nal"),
+];
+
+const H265_STREAM_FORMATS: Names = &[
+    (H265StreamFormat::Unknown as u32, "unknown"),
+    (H265StreamFormat::Hvc1 as u32, "hvc1"),
+    (H265StreamFormat::Hev1 as u32, "hev1"),
+    (H265StreamFormat::ByteStream as u32, "bytestream"),
+];
+
+const H265_ALIGNMENTS: Names = &[
+    (H265Alignment::Unknown as u32, "unknown"),
+    (H265Alignment::Au as u32, "au"),
+    (H265Alignment::Nal as u32, "nal"),
+];
+
+const DIRECTIONS: Names = &[
+    (Direction::Input as u32, "Input"),
+    (Direction::Output as u32, "Output"),
+];
+
+const AVAILABILITIES: Names = &[
+    (Availability::Unknown as u32, "unknown"),
+    (Availability::No as u32, "no"),
+    (Availability::Yes as u32, "yes"),
+];
+
+const PORT_CONFIG_MODES: Names = &[
+    (PortConfigMode::None as u32, "none"),
+    (PortConfigMode::Passthrough as u32, "passthrough"),
+    (PortConfigMode::Convert as u32, "convert"),
+    (PortConfigMode::Dsp as u32, "dsp"),
+];
+
+// What a value is expected to be, used to pick names for ids and types for JSON values
+#[derive(Clone, Copy)]
+enum Kind {
+    Any,
+    Bool,
+    Id(Names),
+    // An id naming a key of the given object type
+    Key(ObjectType),
+    Int,
+    Long,
+    Float,
+    Double,
+    String,
+    Fd,
+    Rectangle,
+    Fraction,
+    Struct,
+    Array(&'static Kind),
+    // A nested object, which JSON does not give the type and id of
+    Object(ObjectType, ParamType),
+}
+
+struct Key {
+    id: u32,
+    name: &'static str,
+    kind: Kind,
+    // Format keys can share a name, in which case the media (sub)type tells them apart
+    media_type: MediaType,
+    media_subtype: MediaSubtype,
+}
+
+const fn key(id: u32, name: &'static str, kind: Kind) -> Key {
+    media_key(id, name, kind, MediaType::Unknown, MediaSubtype::Unknown)
+}
+
+const fn media_key(
+    id: u32,
+    name: &'static str,
+    kind: Kind,
+    media_type: MediaType,
+    media_subtype: MediaSubtype,
+) -> Key {
+    Key {
+        id,
+        name,
+        kind,
+        media_type,
+        media_subtype,
+    }
+}
+
+const fn audio_key(id: u32, name: &'static str, kind: Kind) -> Key {
+    media_key(id, name, kind, MediaType::Audio, MediaSubtype::Unknown)
+}
+
+const fn video_key(id: u32, name: &'static str, kind: Kind) -> Key {
+    media_key(id, name, kind, MediaType::Video, MediaSubtype::Unknown)
+}
+
+const PROP_INFO_KEYS: &[Key] = &[
+    key(PropInfo::Id as u32, "id", Kind::Key(ObjectType::Props)),
+    key(PropInfo::Name as u32, "name", Kind::String),
+    key(PropInfo::Type as u32, "type", Kind::Any),
+    key(PropInfo::Labels as u32, "labels", Kind::Struct),
+    key(PropInfo::Container as u32, "container", Kind::Id(TYPES)),
+    key(PropInfo::Params as u32, "params", Kind::Bool),
+    key(PropInfo::Description as u32, "description", Kind::String),
+];
+
+const PROPS_KEYS: &[Key] = &[
+    key(Prop::Unknown as u32, "unknown", Kind::Any),
+    key(Prop::Device as u32, "device", Kind::String),
+    key(Prop::DeviceName as u32, "deviceName", Kind::String),
+    key(Prop::DeviceFd as u32, "deviceFd", Kind::Fd),
+    key(Prop::Card as u32, "card", Kind::String),
+    key(Prop::CardName as u32, "cardName", Kind::String),
+    key(Prop::MinLatency as u32, "minLatency", Kind::Int),
+    key(Prop::MaxLatency as u32, "maxLatency", Kind::Int),
+    key(Prop::Periods as u32, "periods", Kind::Int),
+    key(Prop::PeriodSize as u32, "periodSize", Kind::Int),
+    key(Prop::PeriodEvent as u32, "periodEvent", Kind::Bool),
+    key(Prop::Live as u32, "live", Kind::Bool),
+    key(Prop::Rate as u32, "rate", Kind::Double),
+    key(Prop::Quality as u32, "quality", Kind::Int),
+    key(
+        Prop::BluetoothAudioCodec as u32,
+        "bluetoothAudioCodec",
+        Kind::Id(NO_NAMES),
+    ),
+    key(
+        Prop::BluetoothOffloadActive as u32,
+        "bluetoothOffloadActive",
+        Kind::Bool,
+    ),
+    key(Prop::WaveType as u32, "waveType", Kind::Id(NO_NAMES)),
+    key(Prop::Frequency as u32, "frequency", Kind::Float),
+    key(Prop::Volume as u32, "volume", Kind::Float),
+    key(Prop::Mute as u32, "mute", Kind::Bool),
+    key(Prop::PatternType as u32, "patternType", Kind::Id(NO_NAMES)),
+    key(Prop::DitherType as u32, "ditherType", Kind::Id(NO_NAMES)),
+    key(Prop::Truncate as u32, "truncate", Kind::Bool),
+    key(
+        Prop::ChannelVolumes as u32,
+        "channelVolumes",
+        Kind::Array(&Kind::Float),
+    ),
+    key(Prop::VolumeBase as u32, "volumeBase", Kind::Float),
+    key(Prop::VolumeStep as u32, "volumeStep", Kind::Float),
+    key(
+        Prop::ChannelMap as u32,
+        "channelMap",
+        Kind::Array(&Kind::Id(AUDIO_CHANNELS)),
+    ),
+    key(Prop::MonitorMute as u32, "monitorMute", Kind::Bool),
+    key(
+        Prop::MonitorVolumes as u32,
+        "monitorVolumes",
+        Kind::Array(&Kind::Float),
+    ),
+    key(
+        Prop::LatencyOffsetNsec as u32,
+        "latencyOffsetNsec",
+        Kind::Long,
+    ),
+    key(Prop::SoftMute as u32, "softMute", Kind::Bool),
+    key(
+        Prop::SoftVolumes as u32,
+        "softVolumes",
+        Kind::Array(&Kind::Float),
+    ),
+    key(
+        Prop::Iec958Codecs as u32,
+        "iec958Codecs",
+        Kind::Array(&Kind::Id(IEC958_CODECS)),
+    ),
+    key(
+        Prop::VolumeRampSamples as u32,
+        "volumeRampSamples",
+        Kind::Int,
+    ),
+    key(
+        Prop::VolumeRampStepSamples as u32,
+        "volumeRampStepSamples",
+        Kind::Int,
+    ),
+    key(Prop::VolumeRampTime as u32, "volumeRampTime", Kind::Int),
+    key(
+        Prop::VolumeRampStepTime as u32,
+        "volumeRampStepTime",
+        Kind::Int,
+    ),
+    key(
+        Prop::VolumeRampScale as u32,
+        "volumeRampScale",
+        Kind::Id(NO_NAMES),
+    ),
+    key(Prop::Brightness as u32, "brightness", Kind::Float),
+    key(Prop::Contrast as u32, "contrast", Kind::Float),
+    key(Prop::Saturation as u32, "saturation", Kind::Float),
+    key(Prop::Hue as u32, "hue", Kind::Int),
+    key(Prop::Gamma as u32, "gamma", Kind::Float),
+    key(Prop::Exposure as u32, "exposure", Kind::Int),
+    key(Prop::Gain as u32, "gain", Kind::Float),
+    key(Prop::Sharpness as u32, "sharpness", Kind::Float),
+    key(Prop::Params as u32, "params", Kind::Struct),
+];
+
+const FORMAT_KEYS: &[Key] = &[
+    key(Format::MediaType as u32, "mediaType", Kind::Id(MEDIA_TYPES)),
+    key(
+        Format::MediaSubtype as u32,
+        "mediaSubtype",
+        Kind::Id(MEDIA_SUBTYPES),
+    ),
+    audio_key(
+        Format::AudioFormat as u32,
+        "format",
+        Kind::Id(AUDIO_FORMATS),
+    ),
+    audio_key(Format::AudioFlags as u32, "flags", Kind::Int),
+    audio_key(Format::AudioRate as u32, "rate", Kind::Int),
+    audio_key(Format::AudioChannels as u32, "channels", Kind::Int),
+    audio_key(
+        Format::AudioPosition as u32,
+        "position",
+        Kind::Array(&Kind::Id(AUDIO_CHANNELS)),
+    ),
+    audio_key(
+        Format::AudioIec958Codec as u32,
+        "iec958Codec",
+        Kind::Id(IEC958_CODECS),
+    ),
+    audio_key(
+        Format::AudioBitorder as u32,
+        "bitorder",
+        Kind::Id(BIT_ORDERS),
+    ),
+    audio_key(Format::AudioInterleave as u32, "interleave", Kind::Int),
+    audio_key(Format::AudioBitrate as u32, "bitrate", Kind::Int),
+    audio_key(Format::AudioBlockAlign as u32, "blockAlign", Kind::Int),
+    media_key(
+        Format::AudioAacStreamFormat as u32,
+        "streamFormat",
+        Kind::Id(AAC_STREAM_FORMATS),
+        MediaType::Audio,
+        MediaSubtype::Aac,
+    ),
+    media_key(
+        Format::AudioWmaProfile as u32,
+        "profile",
+        Kind::Id(WMA_PROFILES),
+        MediaType::Audio,
+        MediaSubtype::Wma,
+    ),
+    media_key(
+        Format::AudioAmrBandMode as u32,
+        "bandMode",
+        Kind::Id(AMR_BAND_MODES),
+        MediaType::Audio,
+        MediaSubtype::Amr,
+    ),
+    video_key(
+        Format::VideoFormat as u32,
+        "format",
+        Kind::Id(VIDEO_FORMATS),
+    ),
+    video_key(Format::VideoModifier as u32, "modifier", Kind::Long),
+    video_key(Format::VideoSize as u32, "size", Kind::Rectangle),
+    video_key(Format::VideoFramerate as u32, "framerate", Kind::Fraction),
+    video_key(
+        Format::VideoMaxFramerate as u32,
+        "maxFramerate",
+        Kind::Fraction,
+    ),
+    video_key(Format::VideoViews as u32, "views", Kind::Int),
+    video_key(
+        Format::VideoInterlaceMode as u32,
+        "interlaceMode",
+        Kind::Id(VIDEO_INTERLACE_MODES),
+    ),
+    video_key(
+        Format::VideoPixelAspectRatio as u32,
+        "pixelAspectRatio",
+        Kind::Fraction,
+    ),
+    video_key(
+        Format::VideoMultiviewMode as u32,
+        "multiviewMode",
+        Kind::Id(VIDEO_MULTIVIEW_MODES),
+    ),
+    video_key(
+        Format::VideoMultiviewFlags as u32,
+        "multiviewFlags",
+        Kind::Id(NO_NAMES),
+    ),
+    video_key(
+        Format::VideoChromaSite as u32,
+        "chromaSite",
+        Kind::Id(NO_NAMES),
+    ),
+    video_key(
+        Format::VideoColorRange as u32,
+        "colorRange",
+        Kind::Id(VIDEO_COLOR_RANGES),
+    ),
+    video_key(
+        Format::VideoColorMatrix as u32,
+        "colorMatrix",
+        Kind::Id(VIDEO_COLOR_MATRICES),
+    ),
+    video_key(
+        Format::VideoTransferFunction as u32,
+        "transferFunction",
+        Kind::Id(VIDEO_TRANSFER_FUNCTIONS),
+    ),
+    video_key(
+        Format::VideoColorPrimaries as u32,
+        "colorPrimaries",
+        Kind::Id(VIDEO_COLOR_PRIMARIES),
+    ),
+    video_key(Format::VideoProfile as u32, "profile", Kind::Int),
+    video_key(Format::VideoLevel as u32, "level", Kind::Int),
+    media_key(
+        Format::VideoH264StreamFormat as u32,
+        "streamFormat",
+        Kind::Id(H264_STREAM_FORMATS),
+        MediaType::Video,
+        MediaSubtype::H264,
+    ),
+    media_key(
+        Format::VideoH264Alignment as u32,
+        "alignment",
+        Kind::Id(H264_ALIGNMENTS),
+        MediaType::Video,
+        MediaSubtype::H264,
+    ),
+    media_key(
+        Format::VideoH265StreamFormat as u32,
+        "streamFormat",
+        Kind::Id(H265_STREAM_FORMATS),
+        MediaType::Video,
+        MediaSubtype::H265,
+    ),
+    media_key(
+        Format::VideoH265Alignment as u32,
+        "alignment",
+        Kind::Id(H265_ALIGNMENTS),
+        MediaType::Video,
+        MediaSubtype::H265,
+    ),
+    key(Format::ControlTypes as u32, "controlTypes", Kind::Int),
+];
+
+const BUFFERS_KEYS: &[Key] = &[
+    key(Buffers::Buffers as u32, "buffers", Kind::Int),
+    key(Buffers::Blocks as u32, "blocks", Kind::Int),
+    key(Buffers::Size as u32, "size", Kind::Int),
+    key(Buffers::Stride as u32, "stride", Kind::Int),
+    key(Buffers::Align as u32, "align", Kind::Int),
+    key(Buffers::DataType as u32, "dataType", Kind::Int),
+    key(Buffers::MetaType as u32, "metaType", Kind::Int),
+];
+
+const META_KEYS: &[Key] = &[
+    key(Meta::Type as u32, "type", Kind::Id(META_TYPES)),
+    key(Meta::Size as u32, "size", Kind::Int),
+];
+
+const IO_KEYS: &[Key] = &[
+    key(Io::Id as u32, "id", Kind::Id(IO_TYPES)),
+    key(Io::Size as u32, "size", Kind::Int),
+];
+
+const LATENCY_KEYS: &[Key] = &[
+    key(Latency::Direction as u32, "direction", Kind::Id(DIRECTIONS)),
+    key(Latency::MinQuantum as u32, "minQuantum", Kind::Float),
+    key(Latency::MaxQuantum as u32, "maxQuantum", Kind::Float),
+    key(Latency::MinRate as u32, "minRate", Kind::Int),
+    key(Latency::MaxRate as u32, "maxRate", Kind::Int),
+    key(Latency::MinNs as u32, "minNs", Kind::Long),
+    key(Latency::MaxNs as u32, "maxNs", Kind::Long),
+];
+
+const PROCESS_LATENCY_KEYS: &[Key] = &[
+    key(ProcessLatency::Quantum as u32, "quantum", Kind::Float),
+    key(ProcessLatency::Rate as u32, "rate", Kind::Int),
+    key(ProcessLatency::Ns as u32, "ns", Kind::Long),
+];
+
+const PORT_CONFIG_KEYS: &[Key] = &[
+    key(
+        PortConfig::Direction as u32,
+        "direction",
+        Kind::Id(DIRECTIONS),
+    ),
+    key(PortConfig::Mode as u32, "mode", Kind::Id(PORT_CONFIG_MODES)),
+    key(PortConfig::Monitor as u32, "monitor", Kind::Bool),
+    key(PortConfig::Control as u32, "control", Kind::Bool),
+    key(
+        PortConfig::Format as u32,
+        "format",
+        Kind::Object(ObjectType::Format, ParamType::Format),
+    ),
+];
+
+const PROFILE_KEYS: &[Key] = &[
+    key(Profile::Index as u32, "index", Kind::Int),
+    key(Profile::Name as u32, "name", Kind::String),
+    key(Profile::Description as u32, "description", Kind::String),
+    key(Profile::Priority as u32, "priority", Kind::Int),
+    key(
+        Profile::Available as u32,
+        "available",
+        Kind::Id(AVAILABILITIES),
+    ),
+    key(Profile::Info as u32, "info", Kind::Struct),
+    key(Profile::Classes as u32, "classes", Kind::Struct),
+    key(Profile::Save as u32, "save", Kind::Bool),
+];
+
+const ROUTE_KEYS: &[Key] = &[
+    key(Route::Index as u32, "index", Kind::Int),
+    key(Route::Direction as u32, "direction", Kind::Id(DIRECTIONS)),
+    key(Route::Device as u32, "device", Kind::Int),
+    key(Route::Name as u32, "name", Kind::String),
+    key(Route::Description as u32, "description", Kind::String),
+    key(Route::Priority as u32, "priority", Kind::Int),
+    key(
+        Route::Available as u32,
+        "available",
+        Kind::Id(AVAILABILITIES),
+    ),
+    key(Route::Info as u32, "info", Kind::Struct),
+    key(Route::Profiles as u32, "profiles", Kind::Array(&Kind::Int)),
+    key(
+        Route::Props as u32,
+        "props",
+        Kind::Object(ObjectType::Props, ParamType::Route),
+    ),
+    key(Route::Devices as u32, "devices", Kind::Array(&Kind::Int)),
+    key(Route::Profile as u32, "profile", Kind::Int),
+    key(Route::Save as u32, "save", Kind::Bool),
+];
+
+const TAG_KEYS: &[Key] = &[
+    key(Tag::Direction as u32, "direction", Kind::Id(DIRECTIONS)),
+    key(Tag::Info as u32, "info", Kind::Struct),
+];
+
+fn object_keys(object_type: u32) -> &'static [Key] {
+    match ObjectType::try_from(object_type) {
+        Ok(ObjectType::PropInfo) => PROP_INFO_KEYS,
+        Ok(ObjectType::Props) => PROPS_KEYS,
+        Ok(ObjectType::Format) => FORMAT_KEYS,
+        Ok(ObjectType::ParamBuffers) => BUFFERS_KEYS,
+        Ok(ObjectType::ParamMeta) => META_KEYS,
+        Ok(ObjectType::ParamIo) => IO_KEYS,
+        Ok(ObjectType::ParamProfile) => PROFILE_KEYS,
+        Ok(ObjectType::ParamPortConfig) => PORT_CONFIG_KEYS,
+        Ok(ObjectType::ParamRoute) => ROUTE_KEYS,
+        Ok(ObjectType::ParamTag) => TAG_KEYS,
+        Ok(ObjectType::ParamLatency) => LATENCY_KEYS,
+        Ok(ObjectType::ParamProcessLatency) => PROCESS_LATENCY_KEYS,
+        _ => &[],
+    }
+}
+
+fn fallback_name(id: u32) -> String {
+    format!("id-{:08x}", id)
+}
+
+// Accepts the fallback names that we write, as well as plain numbers like spa_json_to_pod()
+fn parse_fallback_name(name: &str) -> Option<u32> {
+    match name.strip_prefix("id-") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => name.parse().ok(),
+    }
+}
+
+fn id_name(kind: Kind, id: u32) -> String {
+    let name = match kind {
+        Kind::Id(names) => names.iter().find(|(i, _)| *i == id).map(|(_, n)| *n),
+        Kind::Key(object_type) => object_keys(object_type as u32)
+            .iter()
+            .find(|k| k.id == id)
+            .map(|k| k.name),
+        _ => None,
+    };
+
+    name.map(str::to_string)
+        .unwrap_or_else(|| fallback_name(id))
+}
+
+fn id_from_name(kind: Kind, name: &str) -> Option<u32> {
+    let id = match kind {
+        Kind::Id(names) => names.iter().find(|(_, n)| *n == name).map(|(i, _)| *i),
+        Kind::Key(object_type) => object_keys(object_type as u32)
+            .iter()
+            .find(|k| k.name == name)
+            .map(|k| k.id),
+        _ => None,
+    };
+
+    id.or_else(|| parse_fallback_name(name))
+}
+
+//
+// Pod to JSON
+//
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_float(out: &mut String, v: f64) {
+    // JSON has no representation for these
+    if v.is_finite() {
+        out.push_str(&format!("{:.6}", v));
+    } else {
+        out.push_str("null");
+    }
+}
+
+// Containers are written over multiple lines (with the given indentation level) or on a single
+// line (if indent is None), like pw-dump does for objects and structs, and everything else
+fn write_container<'a, I>(
+    out: &mut String,
+    open: &str,
+    close: &str,
+    items: I,
+    indent: Option<usize>,
+) where
+    I: IntoIterator<Item = (Option<String>, &'a Value, Kind)>,
+{
+    let mut first = true;
+
+    out.push_str(open);
+
+    for (key, value, kind) in items {
+        if !first {
+            out.push(',');
+        }
+
+        match indent {
+            Some(level) => {
+                out.push('\n');
+                out.push_str(&"  ".repeat(level + 1));
+            }
+            None => out.push(' '),
+        }
+
+        if let Some(key) = key {
+            write_string(out, &key);
+            out.push_str(": ");
+        }
+
+        write_value(out, value, kind, indent.map(|level| level + 1));
+        first = false;
+    }
+
+    if !first {
+        match indent {
+            Some(level) => {
+                out.push('\n');
+                out.push_str(&"  ".repeat(level));
+            }
+            None => out.push(' '),
+        }
+    }
+
+    out.push_str(close);
+}
+
+fn write_choice(out: &mut String, choice: &ChoiceValue, kind: Kind, indent: Option<usize>) {
+    let label = |i: usize| -> Option<String> {
+        match (choice.type_, i) {
+            (_, 0) => Some("default".to_string()),
+            (ChoiceType::Range | ChoiceType::Step, 1) => Some("min".to_string()),
+            (ChoiceType::Range | ChoiceType::Step, 2) => Some("max".to_string()),
+            (ChoiceType::Step, 3) => Some("step".to_string()),
+            (ChoiceType::Enum, i) => Some(format!("alt{}", i)),
+            (ChoiceType::Flags, i) => Some(format!("flag{}", i)),
+            _ => None,
+        }
+    };
+
+    let items = choice
+        .values
+        .iter()
+        .enumerate()
+        .map_while(|(i, v)| label(i).map(|l| (Some(l), v, kind)));
+
+    match choice.type_ {
+        // A choice of one value is just that value
+        ChoiceType::None => match choice.values.first() {
+            Some(v) => write_value(out, v, kind, indent),
+            None => out.push_str("null"),
+        },
+        ChoiceType::Range | ChoiceType::Step => write_container(out, "{", "}", items, None),
+        ChoiceType::Enum | ChoiceType::Flags => write_container(out, "{", "}", items, indent),
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, kind: Kind, indent: Option<usize>) {
+    match value {
+        Value::None => out.push_str("null"),
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::Id(v) => write_string(out, &id_name(kind, *v)),
+        Value::Int(v) => out.push_str(&v.to_string()),
+        Value::Long(v) => out.push_str(&v.to_string()),
+        Value::Float(v) => write_float(out, *v as f64),
+        Value::Double(v) => write_float(out, *v),
+        Value::String(v) => write_string(out, v),
+        Value::Rectangle(v) => {
+            out.push_str(&format!(
+                "{{ \"width\": {}, \"height\": {} }}",
+                v.width, v.height
+            ));
+        }
+        Value::Fraction(v) => {
+            out.push_str(&format!("{{ \"num\": {}, \"denom\": {} }}", v.num, v.denom));
+        }
+        Value::Fd(v) => out.push_str(&v.0.to_string()),
+        Value::Array(array) => {
+            let kind = match kind {
+                Kind::Array(kind) => *kind,
+                _ => Kind::Any,
+            };
+            let items = array.values.iter().map(|v| (None, v, kind));
+            write_container(out, "[", "]", items, None);
+        }
+        Value::Choice(choice) => write_choice(out, choice, kind, indent),
+        Value::Struct(values) => {
+            let items = values.iter().map(|v| (None, v, Kind::Any));
+            write_container(out, "[", "]", items, indent);
+        }
+        Value::Object(object) => {
+            let keys = object_keys(object.type_);
+            let items = object.properties.iter().map(|p| {
+                let (name, kind) = match keys.iter().find(|k| k.id == p.key) {
+                    Some(k) => (k.name.to_string(), k.kind),
+                    None => (fallback_name(p.key), Kind::Any),
+                };
+                (Some(name), &p.value, kind)
+            });
+            write_container(out, "{", "}", items, indent);
+        }
+        Value::Bytes(_) | Value::Bitmap(_) | Value::Pointer(_) | Value::Sequence(_) => {
+            out.push_str("null")
+        }
+    }
+}
+
+// Convert a pod to JSON text, formatted like pw-dump does. We produce text rather than a
+// JsonValue, as the latter does not keep the order of object keys, which makes diffs hard to read.
+pub fn to_json(data: &[u8]) -> Result<String, Error> {
+    let mut parser = Parser::new(data);
+    let value = parser.pop_value()?;
+
+    Ok(value_to_json(&value))
+}
+
+pub fn value_to_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, Kind::Any, Some(0));
+    out
+}
+
+//
+// JSON to pod
+//
+
+fn kind_of(type_: Type) -> Kind {
+    match type_ {
+        Type::Bool => Kind::Bool,
+        Type::Id => Kind::Id(NO_NAMES),
+        Type::Int => Kind::Int,
+        Type::Long => Kind::Long,
+        Type::Float => Kind::Float,
+        Type::Double => Kind::Double,
+        Type::String => Kind::String,
+        Type::Fd => Kind::Fd,
+        Type::Rectangle => Kind::Rectangle,
+        Type::Fraction => Kind::Fraction,
+        Type::Struct => Kind::Struct,
+        _ => Kind::Any,
+    }
+}
+
+fn type_of(kind: Kind) -> Type {
+    match kind {
+        Kind::Any => Type::None,
+        Kind::Bool => Type::Bool,
+        Kind::Id(_) | Kind::Key(_) => Type::Id,
+        Kind::Int => Type::Int,
+        Kind::Long => Type::Long,
+        Kind::Float => Type::Float,
+        Kind::Double => Type::Double,
+        Kind::String => Type::String,
+        Kind::Fd => Type::Fd,
+        Kind::Rectangle => Type::Rectangle,
+        Kind::Fraction => Type::Fraction,
+        Kind::Struct => Type::Struct,
+        Kind::Array(_) => Type::Array,
+        Kind::Object(..) => Type::Object,
+    }
+}
+
+fn number_from_json(n: f64, kind: Kind) -> Result<Value, Error> {
+    let is_int = n.fract() == 0.0;
+
+    let value = match kind {
+        Kind::Bool => Value::Bool(n >= 0.5),
+        Kind::Id(_) | Kind::Key(_) if is_int => Value::Id(n as u32),
+        Kind::Int if is_int => Value::Int(n as i32),
+        Kind::Long if is_int => Value::Long(n as i64),
+        Kind::Fd if is_int => Value::Fd(Fd(n as i32)),
+        Kind::Float => Value::Float(n as f32),
+        Kind::Double => Value::Double(n),
+        Kind::Any if is_int && n >= i32::MIN as f64 && n <= i32::MAX as f64 => Value::Int(n as i32),
+        Kind::Any if is_int => Value::Long(n as i64),
+        Kind::Any => Value::Float(n as f32),
+        _ => return Err(Error::Invalid),
+    };
+
+    Ok(value)
+}
+
+fn u32_field(map: &HashMap<String, JsonValue>, name: &str) -> Result<u32, Error> {
+    match map.get(name) {
+        Some(JsonValue::Number(n)) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as u32),
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn has_fields(map: &HashMap<String, JsonValue>, names: &[&str]) -> bool {
+    map.len() == names.len() && names.iter().all(|n| map.contains_key(*n))
+}
+
+fn choice_from_json(map: &HashMap<String, JsonValue>, kind: Kind) -> Result<Value, Error> {
+    let default = json_to_value(map.get("default").ok_or(Error::Invalid)?, kind)?;
+    let child_type = default.type_();
+
+    // If we did not know what to expect, the rest must match the default
+    let kind = match kind {
+        Kind::Any => kind_of(child_type),
+        kind => kind,
+    };
+
+    let (type_, labels) = if map.contains_key("step") {
+        (ChoiceType::Step, vec!["min", "max", "step"])
+    } else if map.contains_key("min") || map.contains_key("max") {
+        (ChoiceType::Range, vec!["min", "max"])
+    } else if map.contains_key("alt1") {
+        (ChoiceType::Enum, vec![])
+    } else if map.contains_key("flag1") {
+        (ChoiceType::Flags, vec![])
+    } else {
+        (ChoiceType::None, vec![])
+    };
+
+    let labels = match type_ {
+        ChoiceType::Enum => (1..map.len()).map(|i| format!("alt{}", i)).collect(),
+        ChoiceType::Flags => (1..map.len()).map(|i| format!("flag{}", i)).collect(),
+        _ => labels.into_iter().map(str::to_string).collect::<Vec<_>>(),
+    };
+
+    if labels.len() + 1 != map.len() {
+        return Err(Error::Invalid);
+    }
+
+    let mut values = vec![default];
+    for label in labels {
+        let value = json_to_value(map.get(&label).ok_or(Error::Invalid)?, kind)?;
+        if value.type_() != child_type {
+            return Err(Error::Invalid);
+        }
+        values.push(value);
+    }
+
+    Ok(Value::Choice(ChoiceValue {
+        type_,
+        flags: 0,
+        child_type,
+        values,
+    }))
+}
+
+fn json_to_value(json: &JsonValue, kind: Kind) -> Result<Value, Error> {
+    let value = match (json, kind) {
+        (JsonValue::Null, _) => Value::None,
+        (JsonValue::Boolean(b), Kind::Bool | Kind::Any) => Value::Bool(*b),
+        (JsonValue::Number(n), _) => number_from_json(*n, kind)?,
+        (JsonValue::String(s), Kind::Id(_) | Kind::Key(_)) => {
+            Value::Id(id_from_name(kind, s).ok_or(Error::Invalid)?)
+        }
+        (JsonValue::String(s), Kind::String | Kind::Any) => Value::String(s.clone()),
+        (JsonValue::Array(values), Kind::Array(child)) => Value::Array(ArrayValue {
+            child_type: type_of(*child),
+            values: values
+                .iter()
+                .map(|v| json_to_value(v, *child))
+                .collect::<Result<_, _>>()?,
+        }),
+        (JsonValue::Array(values), Kind::Struct | Kind::Any) => Value::Struct(
+            values
+                .iter()
+                .map(|v| json_to_value(v, Kind::Any))
+                .collect::<Result<_, _>>()?,
+        ),
+        (JsonValue::Object(map), Kind::Object(object_type, id)) => {
+            Value::Object(object_from_json(map, object_type, id as u32)?)
+        }
+        (JsonValue::Object(map), _) if map.contains_key("default") => choice_from_json(map, kind)?,
+        (JsonValue::Object(map), Kind::Rectangle | Kind::Any)
+            if has_fields(map, &["width", "height"]) =>
+        {
+            Value::Rectangle(Rectangle {
+                width: u32_field(map, "width")?,
+                height: u32_field(map, "height")?,
+            })
+        }
+        (JsonValue::Object(map), Kind::Fraction | Kind::Any)
+            if has_fields(map, &["num", "denom"]) =>
+        {
+            Value::Fraction(Fraction {
+                num: u32_field(map, "num")?,
+                denom: u32_field(map, "denom")?,
+            })
+        }
+        _ => return Err(Error::Invalid),
+    };
+
+    Ok(value)
+}
+
+fn media_id(map: &HashMap<String, JsonValue>, name: &str, names: Names) -> u32 {
+    match map.get(name) {
+        Some(JsonValue::String(s)) => id_from_name(Kind::Id(names), s).unwrap_or(0),
+        Some(JsonValue::Number(n)) => *n as u32,
+        _ => 0,
+    }
+}
+
+fn find_key(
+    keys: &'static [Key],
+    name: &str,
+    media_type: u32,
+    media_subtype: u32,
+) -> Option<&'static Key> {
+    let mut candidates = keys.iter().filter(|k| k.name == name);
+    let first = candidates.clone().next();
+
+    candidates
+        .find(|k| {
+            (k.media_type == MediaType::Unknown || k.media_type as u32 == media_type)
+                && (k.media_subtype == MediaSubtype::Unknown
+                    || k.media_subtype as u32 == media_subtype)
+        })
+        .or(first)
+}
+
+fn object_from_json(
+    map: &HashMap<String, JsonValue>,
+    object_type: ObjectType,
+    id: u32,
+) -> Result<ObjectValue, Error> {
+    let keys = object_keys(object_type as u32);
+    let media_type = media_id(map, "mediaType", MEDIA_TYPES);
+    let media_subtype = media_id(map, "mediaSubtype", MEDIA_SUBTYPES);
+
+    let mut properties = map
+        .iter()
+        .map(|(name, value)| {
+            let (key, kind) = match find_key(keys, name, media_type, media_subtype) {
+                Some(k) => (k.id, k.kind),
+                None => (parse_fallback_name(name).ok_or(Error::Invalid)?, Kind::Any),
+            };
+
+            Ok(PropertyValue {
+                key,
+                flags: PropertyFlags::empty(),
+                value: json_to_value(value, kind)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // JSON objects are unordered, so we use the order of the keys
+    properties.sort_by_key(|p| p.key);
+
+    Ok(ObjectValue {
+        type_: object_type as u32,
+        id,
+        properties,
+    })
+}
+
+// Convert JSON in the pw-dump layout to a pod. Objects are built as the object type for the given
+// param, and other values are guessed from the JSON types.
+pub fn value_from_json(json: &JsonValue, param: ParamType) -> Result<Value, Error> {
+    match (json, param.object_type()) {
+        (JsonValue::Object(map), Some(object_type)) => Ok(Value::Object(object_from_json(
+            map,
+            object_type,
+            param as u32,
+        )?)),
+        (json, _) => json_to_value(json, Kind::Any),
+    }
+}
+
+pub fn from_json(json: &JsonValue, param: ParamType) -> Result<Vec<u8>, Error> {
+    value_from_json(json, param)?.to_bytes()
+}
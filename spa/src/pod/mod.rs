@@ -3,20 +3,242 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
 
 pub mod builder;
+pub mod compare;
+#[cfg(feature = "serde")]
+pub mod de;
+mod debug;
+pub mod filter;
+pub mod json;
+pub mod object;
 pub mod parser;
+#[cfg(feature = "serde")]
+pub mod ser;
 pub mod types;
+pub mod value;
 
 use std::ffi::c_void;
+use std::fmt;
 use std::os::fd::RawFd;
 
-use types::{Choice, Fd, Fraction, Id, Pointer, Property, PropertyFlags, Rectangle, Type};
+use types::{
+    ArrayRef, Bitmap, BitmapBuf, Choice, ChoiceType, Control, ControlType, Fd, Flags, Fraction, Id,
+    Pointer, Property, PropertyFlags, Rectangle, Sequence, Type,
+};
 
 #[derive(Debug)]
 pub enum Error {
     Invalid,
     NoSpace,
+    // A pod was of a different type than the one being decoded
+    WrongType {
+        expected: Type,
+        found: Type,
+    },
+    // An object was of a different object type than the one being decoded
+    WrongObjectType {
+        expected: u32,
+        found: u32,
+    },
+    // Where an error happened: the byte offset from the start of the outermost pod, and the path
+    // of nested pods leading to it. This is never nested.
+    At {
+        offset: usize,
+        path: Vec<PathSegment>,
+        error: Box<Error>,
+    },
 }
 
+// One level of nesting in the path to an error
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathSegment {
+    // An object of the given type
+    Object(u32),
+    // A property of an object of the given type
+    Property { object_type: u32, key: u32 },
+    // The n-th element of a struct, array, choice or sequence
+    Element(Type, usize),
+}
+
+impl Error {
+    // Add the location of a nested pod to an error, as it is passed up to the parent. The offset
+    // is that of the nested pod within the parent.
+    pub(crate) fn at(self, offset: usize, segment: Option<PathSegment>) -> Error {
+        match self {
+            Error::At {
+                offset: o,
+                mut path,
+                error,
+            } => {
+                path.splice(0..0, segment);
+                Error::At {
+                    offset: offset + o,
+                    path,
+                    error,
+                }
+            }
+            error => Error::At {
+                offset,
+                path: segment.into_iter().collect(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    // The error itself, without the location
+    pub fn cause(&self) -> &Error {
+        match self {
+            Error::At { error, .. } => error,
+            error => error,
+        }
+    }
+
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::At { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Error::At { path, .. } => path,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Object(type_) => write!(f, "Object({})", debug::object_type_name(*type_)),
+            PathSegment::Property { object_type, key } => {
+                f.write_str(&debug::key_name(*object_type, *key))
+            }
+            PathSegment::Element(type_, index) => write!(f, "{:?}[{}]", type_, index),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Invalid => f.write_str("invalid pod"),
+            Error::NoSpace => f.write_str("not enough space for pod"),
+            Error::WrongType { expected, found } => {
+                write!(f, "expected {:?} pod, found {:?}", expected, found)
+            }
+            Error::WrongObjectType { expected, found } => write!(
+                f,
+                "expected {} object, found {}",
+                debug::object_type_name(*expected),
+                debug::object_type_name(*found)
+            ),
+            Error::At {
+                offset,
+                path,
+                error,
+            } => {
+                write!(f, "{} at offset {}", error, offset)?;
+                if !path.is_empty() {
+                    let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+                    write!(f, " ({})", path.join("/"))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Limits for decoding pods, which may come from other processes and can't be trusted. The largest
+// pod body that will be decoded:
+pub const MAX_POD_SIZE: usize = 16 * 1024 * 1024;
+// The most memory that decoding the elements of a single array or choice may allocate:
+pub const MAX_ALLOC_SIZE: usize = 64 * 1024 * 1024;
+// How deeply pods may be nested inside each other:
+pub const MAX_DEPTH: usize = 64;
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    offset
+        .checked_add(4)
+        .and_then(|end| data.get(offset..end))
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_ne_bytes)
+        .ok_or(Error::Invalid)
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64, Error> {
+    offset
+        .checked_add(8)
+        .and_then(|end| data.get(offset..end))
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_ne_bytes)
+        .ok_or(Error::Invalid)
+}
+
+// Read the header of the pod at the start of data, and check that the whole pod, including
+// padding, is there. Returns the size of the body and the total size.
+pub(crate) fn read_header(data: &[u8]) -> Result<(usize, usize), Error> {
+    let size = read_u32(data, 0)? as usize;
+    if size > MAX_POD_SIZE {
+        return Err(Error::Invalid);
+    }
+
+    let total = 8 + size + pad_8(size);
+    if data.len() < total {
+        return Err(Error::Invalid);
+    }
+
+    Ok((size, total))
+}
+
+// Check that allocating space for count elements of T stays within MAX_ALLOC_SIZE
+pub(crate) fn check_alloc<T>(count: usize) -> Result<(), Error> {
+    match count.checked_mul(std::mem::size_of::<T>()) {
+        Some(size) if size <= MAX_ALLOC_SIZE => Ok(()),
+        _ => Err(Error::Invalid),
+    }
+}
+
+// Read the header of a choice whose children must be of the given type and size. Returns the
+// choice type, the bodies of the values and the total size. The number of values is not checked,
+// as that depends on the choice type.
+pub(crate) fn read_choice_header(
+    data: &[u8],
+    child_type: Type,
+    child_size: usize,
+) -> Result<(u32, &[u8], usize), Error> {
+    let (size, total) = read_header(data)?;
+    check_type(data, Type::Choice)?;
+
+    if size < 16 {
+        return Err(Error::Invalid);
+    }
+
+    let choice_type = read_u32(data, 8)?;
+    // flags is unused, so we don't decode it at [12..16]
+    if child_size == 0 || read_u32(data, 16)? as usize != child_size {
+        return Err(Error::Invalid);
+    }
+    check_type(&data[16..], child_type)?;
+
+    Ok((choice_type, &data[24..8 + size], total))
+}
+
+// Check the type in the header of a pod
+fn check_type(data: &[u8], expected: Type) -> Result<(), Error> {
+    let found = read_u32(data, 4)?;
+    if found == expected as u32 {
+        return Ok(());
+    }
+
+    match Type::try_from(found) {
+        Ok(found) => Err(Error::WrongType { expected, found }),
+        Err(_) => Err(Error::Invalid),
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub trait Pod {
     // Default to Self once that is stable, or try to generate references to owned data
     type DecodesTo;
@@ -25,6 +247,11 @@ pub trait Pod {
     fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error>;
 }
 
+// Decoding that borrows from the source buffer instead of allocating, for types that support it
+pub trait PodBorrow<'a>: Sized {
+    fn decode_borrowed(data: &'a [u8]) -> Result<(Self, usize), Error>;
+}
+
 pub trait Primitive {
     fn pod_type() -> Type;
     fn pod_size() -> usize;
@@ -35,6 +262,19 @@ pub trait Primitive {
         Self: Sized;
 }
 
+/// Primitives whose pod body is exactly their in-memory representation, so that a run of array
+/// elements can be viewed as a slice
+///
+/// # Safety
+///
+/// Every bit pattern of pod_size() bytes must be a valid value of the type.
+pub unsafe trait PlainPrimitive: Primitive {}
+
+unsafe impl PlainPrimitive for i32 {}
+unsafe impl PlainPrimitive for i64 {}
+unsafe impl PlainPrimitive for f32 {}
+unsafe impl PlainPrimitive for f64 {}
+
 fn pad_8(size: usize) -> usize {
     if size % 8 > 0 {
         8 - size % 8
@@ -43,6 +283,25 @@ fn pad_8(size: usize) -> usize {
     }
 }
 
+// Encode a pod into a freshly allocated buffer, growing it until the pod fits
+pub(crate) fn encode_to_vec<P: Pod>(value: &P) -> Result<Vec<u8>, Error> {
+    let mut buf = vec![0u8; 64];
+
+    loop {
+        match value.encode(&mut buf) {
+            Ok(size) => {
+                buf.truncate(size);
+                return Ok(buf);
+            }
+            Err(e) if matches!(e.cause(), Error::NoSpace) && buf.len() < u32::MAX as usize => {
+                buf.resize(buf.len() * 2, 0);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct RawPod<'a> {
     size: usize,
     type_: Type,
@@ -51,19 +310,8 @@ pub struct RawPod<'a> {
 
 impl<'a> RawPod<'a> {
     pub fn wrap(data: &'a [u8]) -> Result<RawPod<'a>, Error> {
-        if data.len() < 8 {
-            return Err(Error::Invalid);
-        }
-
-        let internal_size = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
-        let size = 8 + internal_size + pad_8(internal_size);
-
-        if size > data.len() {
-            return Err(Error::Invalid);
-        }
-
-        let type_ = Type::try_from(u32::from_ne_bytes(data[4..8].try_into().unwrap()))
-            .map_err(|_| Error::Invalid)?;
+        let (_, size) = read_header(data)?;
+        let type_ = Type::try_from(read_u32(data, 4)?).map_err(|_| Error::Invalid)?;
 
         Ok(RawPod {
             size,
@@ -80,11 +328,27 @@ impl<'a> RawPod<'a> {
         self.type_
     }
 
+    // Decoding errors are always located, so that parsers can tell them apart from errors that
+    // callers raise themselves
     pub fn decode<T>(&self) -> Result<<T as Pod>::DecodesTo, Error>
     where
         T: Pod,
     {
-        T::decode(self.data).map(|v| v.0)
+        T::decode(self.data).map(|v| v.0).map_err(|e| e.at(0, None))
+    }
+
+    pub fn decode_borrowed<T>(&self) -> Result<T, Error>
+    where
+        T: PodBorrow<'a>,
+    {
+        T::decode_borrowed(self.data)
+            .map(|v| v.0)
+            .map_err(|e| e.at(0, None))
+    }
+
+    // The complete pod, including the header and padding
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
     }
 }
 
@@ -115,23 +379,15 @@ where
     }
 
     fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error> {
-        if data.len() < 16 {
-            return Err(Error::Invalid);
-        }
+        let (size, total) = read_header(data)?;
+        check_type(data, Self::pod_type())?;
 
-        let size = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
         if size != Self::pod_size() {
             return Err(Error::Invalid);
         }
 
-        let t = u32::from_ne_bytes(data[4..8].try_into().unwrap());
-        if t != Self::pod_type() as u32 {
-            return Err(Error::Invalid);
-        }
-
-        let val = Self::decode_body(&data[8..])?;
-        let padding = pad_8(size);
-        Ok((val, 8 + size + padding))
+        let val = Self::decode_body(&data[8..8 + size])?;
+        Ok((val, total))
     }
 }
 
@@ -168,7 +424,7 @@ impl Primitive for bool {
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let val = u32::from_ne_bytes(data[0..4].try_into().unwrap()) != 0;
+        let val = read_u32(data, 0)? != 0;
         Ok(val)
     }
 }
@@ -191,7 +447,7 @@ where
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let raw_val = u32::from_ne_bytes(data[0..4].try_into().unwrap());
+        let raw_val = read_u32(data, 0)?;
         if let Ok(val) = raw_val.try_into() {
             Ok(Id(val))
         } else {
@@ -200,6 +456,30 @@ where
     }
 }
 
+impl<T> Primitive for Flags<T>
+where
+    T: bitflags::Flags<Bits = u32>,
+{
+    fn pod_type() -> Type {
+        Type::Int
+    }
+
+    fn pod_size() -> usize {
+        4
+    }
+
+    fn encode_body(&self, data: &mut [u8]) -> Result<(), Error> {
+        data[0..4].copy_from_slice(&self.0.bits().to_ne_bytes());
+        Ok(())
+    }
+
+    fn decode_body(data: &[u8]) -> Result<Self, Error> {
+        // Unknown bits are retained, as they may be defined by newer versions of SPA
+        let bits = read_u32(data, 0)?;
+        Ok(Flags(T::from_bits_retain(bits)))
+    }
+}
+
 impl Primitive for i32 {
     fn pod_type() -> Type {
         Type::Int
@@ -215,7 +495,7 @@ impl Primitive for i32 {
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let val = i32::from_ne_bytes(data[0..4].try_into().unwrap());
+        let val = read_u32(data, 0)? as i32;
         Ok(val)
     }
 }
@@ -235,7 +515,7 @@ impl Primitive for i64 {
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let val = i64::from_ne_bytes(data[0..8].try_into().unwrap());
+        let val = read_u64(data, 0)? as i64;
         Ok(val)
     }
 }
@@ -255,7 +535,7 @@ impl Primitive for f32 {
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let val = f32::from_ne_bytes(data[0..4].try_into().unwrap());
+        let val = f32::from_bits(read_u32(data, 0)?);
         Ok(val)
     }
 }
@@ -275,7 +555,7 @@ impl Primitive for f64 {
     }
 
     fn decode_body(data: &[u8]) -> Result<Self, Error> {
-        let val = f64::from_ne_bytes(data[0..8].try_into().unwrap());
+        let val = f64::from_bits(read_u64(data, 0)?);
         Ok(val)
     }
 }
@@ -295,7 +575,7 @@ impl Primitive for Fd {
     }
 
     fn decode_body(data: &[u8]) -> Result<Fd, Error> {
-        let val = i64::from_ne_bytes(data[0..8].try_into().unwrap());
+        let val = read_u64(data, 0)? as i64;
         Ok(Fd(val as RawFd))
     }
 }
@@ -316,8 +596,8 @@ impl Primitive for Rectangle {
     }
 
     fn decode_body(data: &[u8]) -> Result<Rectangle, Error> {
-        let width = u32::from_ne_bytes(data[0..4].try_into().unwrap());
-        let height = u32::from_ne_bytes(data[4..8].try_into().unwrap());
+        let width = read_u32(data, 0)?;
+        let height = read_u32(data, 4)?;
 
         Ok(Rectangle { width, height })
     }
@@ -339,8 +619,8 @@ impl Primitive for Fraction {
     }
 
     fn decode_body(data: &[u8]) -> Result<Fraction, Error> {
-        let num = u32::from_ne_bytes(data[0..4].try_into().unwrap());
-        let denom = u32::from_ne_bytes(data[4..8].try_into().unwrap());
+        let num = read_u32(data, 0)?;
+        let denom = read_u32(data, 4)?;
 
         Ok(Fraction { num, denom })
     }
@@ -369,24 +649,14 @@ impl Pod for &str {
     }
 
     fn decode(data: &[u8]) -> Result<(String, usize), Error> {
-        let len = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
-        let padding = pad_8(len);
-
-        if data.len() < 8 + len {
-            return Err(Error::Invalid);
-        }
-
-        if data[4..8] != (Type::String as u32).to_ne_bytes() {
-            return Err(Error::Invalid);
-        }
+        let (len, total) = read_header(data)?;
+        check_type(data, Type::String)?;
 
-        let s = String::from_utf8_lossy(&data[8..8 + len - 1]).to_string();
         // Null terminator
-        if data[8 + len - 1] != 0 {
-            return Err(Error::Invalid);
+        match data[8..8 + len].split_last() {
+            Some((0, s)) => Ok((String::from_utf8_lossy(s).to_string(), total)),
+            _ => Err(Error::Invalid),
         }
-
-        Ok((s, 8 + len + padding))
     }
 }
 
@@ -403,39 +673,36 @@ impl Pod for String {
     }
 }
 
-impl Pod for &[u8] {
-    type DecodesTo = Vec<u8>;
+// Writes a pod of the given type whose body is the given bytes, padded to 8 bytes
+fn encode_bytes(data: &mut [u8], type_: Type, bytes: &[u8]) -> Result<usize, Error> {
+    let len = bytes.len();
+    let padding = pad_8(len);
 
-    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
-        let len = self.len();
-        let padding = pad_8(len);
+    if len as u32 > u32::MAX || data.len() < 8 + len + padding {
+        return Err(Error::NoSpace);
+    }
 
-        if len as u32 > u32::MAX || data.len() < 8 + len + padding {
-            return Err(Error::NoSpace);
-        }
+    data[0..4].copy_from_slice(&(len as u32).to_ne_bytes());
+    data[4..8].copy_from_slice(&(type_ as u32).to_ne_bytes());
+    data[8..8 + len].copy_from_slice(bytes);
+    // Padding
+    data[8 + len..8 + len + padding].copy_from_slice(&[0; 8][0..padding]);
 
-        data[0..4].copy_from_slice(&(len as u32).to_ne_bytes());
-        data[4..8].copy_from_slice(&(Type::Bytes as u32).to_ne_bytes());
-        data[8..8 + self.len()].copy_from_slice(self);
-        // Padding
-        data[8 + len..8 + len + padding].copy_from_slice(&[0; 8][0..padding]);
+    Ok(8 + len + padding)
+}
 
-        Ok(8 + len + padding)
+impl Pod for &[u8] {
+    type DecodesTo = Vec<u8>;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        encode_bytes(data, Type::Bytes, self)
     }
 
     fn decode(data: &[u8]) -> Result<(Vec<u8>, usize), Error> {
-        let len = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
-        let padding = pad_8(len);
-
-        if data.len() < 8 + len {
-            return Err(Error::Invalid);
-        }
-
-        if data[4..8] != (Type::Bytes as u32).to_ne_bytes() {
-            return Err(Error::Invalid);
-        }
+        let (len, total) = read_header(data)?;
+        check_type(data, Type::Bytes)?;
 
-        Ok((data[8..8 + len].to_vec(), 8 + len + padding))
+        Ok((data[8..8 + len].to_vec(), total))
     }
 }
 
@@ -452,6 +719,54 @@ impl Pod for Vec<u8> {
     }
 }
 
+impl Pod for Bitmap<'_> {
+    type DecodesTo = BitmapBuf;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        encode_bytes(data, Type::Bitmap, self.0)
+    }
+
+    fn decode(data: &[u8]) -> Result<(BitmapBuf, usize), Error> {
+        let (bitmap, size) = Bitmap::decode_borrowed(data)?;
+
+        Ok((bitmap.to_owned(), size))
+    }
+}
+
+impl Pod for BitmapBuf {
+    type DecodesTo = BitmapBuf;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        self.as_bitmap().encode(data)
+    }
+
+    fn decode(data: &[u8]) -> Result<(BitmapBuf, usize), Error> {
+        <Bitmap as Pod>::decode(data)
+    }
+}
+
+// An absent value is encoded as a None pod
+impl<T: Pod> Pod for Option<T> {
+    type DecodesTo = Option<T::DecodesTo>;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            Some(value) => value.encode(data),
+            None => ().encode(data),
+        }
+    }
+
+    fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error> {
+        let pod = RawPod::wrap(data)?;
+
+        if pod.type_() == Type::None {
+            Ok((None, pod.total_size()))
+        } else {
+            T::decode(data).map(|(value, size)| (Some(value), size))
+        }
+    }
+}
+
 // Pointer is encoded as:
 //
 // +--------------+
@@ -495,29 +810,26 @@ impl Pod for Pointer {
     }
 
     fn decode(data: &[u8]) -> Result<(Pointer, usize), Error> {
-        let size = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
+        let (size, total) = read_header(data)?;
         let ptr_size = std::mem::size_of::<*const c_void>();
-        let padding = 8 - ptr_size;
 
-        if data.len() < 24 {
-            return Err(Error::Invalid);
-        }
+        check_type(data, Type::Pointer)?;
 
-        if data[4..8] != (Type::Pointer as u32).to_ne_bytes() {
+        if size < 8 + ptr_size {
             return Err(Error::Invalid);
         }
 
-        let type_ = match u32::from_ne_bytes(data[8..12].try_into().unwrap()).try_into() {
+        let type_ = match read_u32(data, 8)?.try_into() {
             Ok(t) => t,
             Err(_) => return Err(Error::Invalid),
         };
         let ptr = if ptr_size == 8 {
-            u64::from_ne_bytes(data[16..24].try_into().unwrap()) as *const c_void
+            read_u64(data, 16)? as *const c_void
         } else {
-            u32::from_ne_bytes(data[16..20].try_into().unwrap()) as *const c_void
+            read_u32(data, 16)? as *const c_void
         };
 
-        Ok((Pointer { type_, ptr }, 8 + size + padding))
+        Ok((Pointer { type_, ptr }, total))
     }
 }
 
@@ -545,57 +857,119 @@ where
     type DecodesTo = Vec<T>;
 
     fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
-        let child_size = T::pod_size();
-        let child_type = T::pod_type();
-        let elems_size = child_size * self.len();
-        let padding = pad_8(elems_size);
+        encode_array(self, data, T::pod_type(), T::pod_size(), T::encode_body)
+    }
 
-        if data.len() < 8 + 8 + elems_size + padding {
-            return Err(Error::NoSpace);
-        }
+    fn decode(data: &[u8]) -> Result<(Vec<T>, usize), Error> {
+        decode_array(data, T::pod_type(), Some(T::pod_size()), T::decode_body)
+    }
+}
 
-        data[0..4].copy_from_slice(&(8 + elems_size as u32).to_ne_bytes());
-        data[4..8].copy_from_slice(&(Type::Array as u32).to_ne_bytes());
-        data[8..12].copy_from_slice(&(child_size as u32).to_ne_bytes());
-        data[12..16].copy_from_slice(&(child_type as u32).to_ne_bytes());
+// Bitmaps have no fixed size, so arrays and choices of them take the size of their first
+// element, which all the others must have too
+impl Pod for &[BitmapBuf] {
+    type DecodesTo = Vec<BitmapBuf>;
 
-        for i in 0..self.len() {
-            self[i].encode_body(&mut data[16 + i * child_size..])?;
-        }
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        let child_size = bitmap_child_size(self.iter())?;
+        encode_array(self, data, Type::Bitmap, child_size, encode_bitmap_body)
+    }
 
-        Ok(8 + 8 + elems_size + padding)
+    fn decode(data: &[u8]) -> Result<(Vec<BitmapBuf>, usize), Error> {
+        decode_array(data, Type::Bitmap, None, decode_bitmap_body)
     }
+}
 
-    fn decode(data: &[u8]) -> Result<(Vec<T>, usize), Error> {
-        let mut res = Vec::new();
+fn bitmap_child_size<'a>(mut bitmaps: impl Iterator<Item = &'a BitmapBuf>) -> Result<usize, Error> {
+    let Some(first) = bitmaps.next() else {
+        return Ok(0);
+    };
 
-        if data.len() < 16 {
-            return Err(Error::Invalid);
-        }
+    let size = first.as_bytes().len();
+    if bitmaps.any(|b| b.as_bytes().len() != size) {
+        return Err(Error::Invalid);
+    }
 
-        let size = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
-        let padding = pad_8(size);
+    Ok(size)
+}
 
-        if data.len() < 8 + size + padding {
-            return Err(Error::Invalid);
-        }
+fn encode_bitmap_body(bitmap: &BitmapBuf, data: &mut [u8]) -> Result<(), Error> {
+    let bytes = bitmap.as_bytes();
+    data.get_mut(..bytes.len())
+        .ok_or(Error::NoSpace)?
+        .copy_from_slice(bytes);
 
-        if Ok(Type::Array) != u32::from_ne_bytes(data[4..8].try_into().unwrap()).try_into() {
-            return Err(Error::Invalid);
-        }
+    Ok(())
+}
 
-        let child_size = u32::from_ne_bytes(data[8..12].try_into().unwrap()) as usize;
-        if Ok(T::pod_type()) != u32::from_ne_bytes(data[12..16].try_into().unwrap()).try_into() {
-            return Err(Error::Invalid);
-        }
+fn decode_bitmap_body(data: &[u8]) -> Result<BitmapBuf, Error> {
+    Ok(BitmapBuf::from_bytes(data.to_vec()))
+}
 
-        for i in 0..(size - 8) / child_size {
-            let val = T::decode_body(&data[16 + i * child_size..])?;
-            res.push(val);
-        }
+fn encode_array<T>(
+    values: &[T],
+    data: &mut [u8],
+    child_type: Type,
+    child_size: usize,
+    encode_body: impl Fn(&T, &mut [u8]) -> Result<(), Error>,
+) -> Result<usize, Error> {
+    let elems_size = child_size * values.len();
+    let padding = pad_8(elems_size);
+
+    if data.len() < 8 + 8 + elems_size + padding {
+        return Err(Error::NoSpace);
+    }
 
-        Ok((res, 8 + size + padding))
+    data[0..4].copy_from_slice(&(8 + elems_size as u32).to_ne_bytes());
+    data[4..8].copy_from_slice(&(Type::Array as u32).to_ne_bytes());
+    data[8..12].copy_from_slice(&(child_size as u32).to_ne_bytes());
+    data[12..16].copy_from_slice(&(child_type as u32).to_ne_bytes());
+
+    for (i, value) in values.iter().enumerate() {
+        encode_body(value, &mut data[16 + i * child_size..])?;
+    }
+
+    Ok(8 + 8 + elems_size + padding)
+}
+
+// Decode the elements of an array, which must be of the given size unless any size will do
+fn decode_array<T>(
+    data: &[u8],
+    child_type: Type,
+    expected_size: Option<usize>,
+    decode_body: impl Fn(&[u8]) -> Result<T, Error>,
+) -> Result<(Vec<T>, usize), Error> {
+    let (size, total) = read_header(data)?;
+    check_type(data, Type::Array)?;
+
+    if size < 8 {
+        return Err(Error::Invalid);
+    }
+
+    // The child header has the same layout as a pod header
+    let child_size = read_u32(data, 8)? as usize;
+    check_type(&data[8..], child_type)?;
+
+    if expected_size.is_some_and(|expected| child_size != expected) {
+        return Err(Error::Invalid);
+    }
+
+    let n_values = (size - 8).checked_div(child_size).unwrap_or(0);
+    check_alloc::<T>(n_values)?;
+
+    let mut res = Vec::with_capacity(n_values);
+    for i in 0..n_values {
+        let val =
+            decode_body(&data[16 + i * child_size..16 + (i + 1) * child_size]).map_err(|e| {
+                e.at(
+                    16 + i * child_size,
+                    Some(PathSegment::Element(Type::Array, i)),
+                )
+            })?;
+        res.push(val);
     }
+
+    Ok((res, total))
 }
 
 // Choice is encoded as:
@@ -626,166 +1000,168 @@ where
     type DecodesTo = Choice<T>;
 
     fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        encode_choice(self, data, T::pod_type(), T::pod_size(), T::encode_body)
+    }
+
+    fn decode(data: &[u8]) -> Result<(Choice<T>, usize), Error> {
         let child_size = T::pod_size();
-        let size = 16
-            + match self {
-                Choice::None(_) => child_size,
-                Choice::Range { .. } => child_size * 3,
-                Choice::Step { .. } => child_size * 4,
-                Choice::Enum {
-                    default: _,
-                    alternatives,
-                } => child_size * (1 + alternatives.len()),
-                Choice::Flags { .. } => child_size * 2,
-            };
-        let padding = pad_8(size);
+        let (choice_type, values, total) = read_choice_header(data, T::pod_type(), child_size)?;
+        let choice = decode_choice_values(choice_type, values, child_size, T::decode_body)?;
 
-        if data.len() < 24 + size + padding {
-            return Err(Error::NoSpace);
-        }
+        Ok((choice, total))
+    }
+}
 
-        let choice_type = match self {
-            Choice::None(_) => 0u32,
-            Choice::Range { .. } => 1,
-            Choice::Step { .. } => 2,
-            Choice::Enum { .. } => 3,
-            Choice::Flags { .. } => 4,
-        };
+impl Pod for Choice<BitmapBuf> {
+    type DecodesTo = Choice<BitmapBuf>;
 
-        data[0..4].copy_from_slice(&(size as u32).to_ne_bytes());
-        data[4..8].copy_from_slice(&(Type::Choice as u32).to_ne_bytes());
-        data[8..12].copy_from_slice(&choice_type.to_ne_bytes());
-        // flags is unused, so we don't bother exposing it
-        data[12..16].copy_from_slice(&0u32.to_ne_bytes());
-        data[16..20].copy_from_slice(&(T::pod_size() as u32).to_ne_bytes());
-        data[20..24].copy_from_slice(&(T::pod_type() as u32).to_ne_bytes());
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        let child_size = bitmap_child_size(choice_values(self).into_iter())?;
+        encode_choice(self, data, Type::Bitmap, child_size, encode_bitmap_body)
+    }
 
-        match self {
-            Choice::None(value) => {
-                value.encode_body(&mut data[24..])?;
-            }
-            Choice::Range { default, min, max } => {
-                default.encode_body(&mut data[24..])?;
-                min.encode_body(&mut data[24 + child_size..])?;
-                max.encode_body(&mut data[24 + child_size * 2..])?;
-            }
-            Choice::Step {
-                default,
-                min,
-                max,
-                step,
-            } => {
-                default.encode_body(&mut data[24..])?;
-                min.encode_body(&mut data[24 + child_size..])?;
-                max.encode_body(&mut data[24 + child_size * 2..])?;
-                step.encode_body(&mut data[24 + child_size * 3..])?;
-            }
-            Choice::Enum {
-                default,
-                alternatives,
-            } => {
-                default.encode_body(&mut data[24..])?;
-                for (i, alt) in alternatives.iter().enumerate() {
-                    alt.encode_body(&mut data[24 + child_size * (i + 1)..])?;
-                }
-            }
-            Choice::Flags { default, flags } => {
-                default.encode_body(&mut data[24..])?;
-                flags.encode_body(&mut data[24 + child_size..])?;
-            }
-        }
+    fn decode(data: &[u8]) -> Result<(Choice<BitmapBuf>, usize), Error> {
+        let child_size = read_u32(data, 16)? as usize;
+        let (choice_type, values, total) = read_choice_header(data, Type::Bitmap, child_size)?;
+        let choice = decode_choice_values(choice_type, values, child_size, decode_bitmap_body)?;
 
-        Ok(8 + size + padding)
+        Ok((choice, total))
     }
+}
 
-    fn decode(data: &[u8]) -> Result<(Choice<T>, usize), Error> {
-        if data.len() < 24 {
-            return Err(Error::Invalid);
-        }
+// The values of a choice, in the order they are encoded
+fn choice_values<T>(choice: &Choice<T>) -> Vec<&T> {
+    match choice {
+        Choice::None(value) => vec![value],
+        Choice::Range { default, min, max } => vec![default, min, max],
+        Choice::Step {
+            default,
+            min,
+            max,
+            step,
+        } => vec![default, min, max, step],
+        Choice::Enum {
+            default,
+            alternatives,
+        } => std::iter::once(default).chain(alternatives).collect(),
+        Choice::Flags { default, flags } => vec![default, flags],
+    }
+}
 
-        let size = u32::from_ne_bytes(data[0..4].try_into().unwrap()) as usize;
-        let padding = pad_8(size);
+fn encode_choice<T>(
+    choice: &Choice<T>,
+    data: &mut [u8],
+    child_type: Type,
+    child_size: usize,
+    encode_body: impl Fn(&T, &mut [u8]) -> Result<(), Error>,
+) -> Result<usize, Error> {
+    let values = choice_values(choice);
+    let size = 16 + child_size * values.len();
+    let padding = pad_8(size);
+
+    if data.len() < 24 + size + padding {
+        return Err(Error::NoSpace);
+    }
 
-        if data.len() < 8 + size + padding {
-            return Err(Error::Invalid);
-        }
+    let choice_type = choice.choice_type() as u32;
 
-        if u32::from_ne_bytes(data[4..8].try_into().unwrap()) != Type::Choice as u32 {
-            return Err(Error::Invalid);
-        }
+    data[0..4].copy_from_slice(&(size as u32).to_ne_bytes());
+    data[4..8].copy_from_slice(&(Type::Choice as u32).to_ne_bytes());
+    data[8..12].copy_from_slice(&choice_type.to_ne_bytes());
+    // flags is unused, so we don't bother exposing it
+    data[12..16].copy_from_slice(&0u32.to_ne_bytes());
+    data[16..20].copy_from_slice(&(child_size as u32).to_ne_bytes());
+    data[20..24].copy_from_slice(&(child_type as u32).to_ne_bytes());
 
-        let choice_type = u32::from_ne_bytes(data[8..12].try_into().unwrap());
-        // flags is unused, so we don't decode it at [12..16]
-        let child_size = u32::from_ne_bytes(data[16..20].try_into().unwrap()) as usize;
-        if child_size != T::pod_size() {
-            return Err(Error::Invalid);
-        }
-        let child_type = u32::from_ne_bytes(data[20..24].try_into().unwrap());
-        if child_type != T::pod_type() as u32 {
+    for (i, value) in values.into_iter().enumerate() {
+        encode_body(value, &mut data[24 + child_size * i..])?;
+    }
+
+    Ok(8 + size + padding)
+}
+
+// Put a choice together from the bodies of its values, which must be as many as its type calls for
+fn decode_choice_values<T>(
+    choice_type: u32,
+    values: &[u8],
+    child_size: usize,
+    decode_body: impl Fn(&[u8]) -> Result<T, Error>,
+) -> Result<Choice<T>, Error> {
+    let n_values = values.len() / child_size;
+    check_alloc::<T>(n_values)?;
+
+    let value = |i: usize| {
+        if i >= n_values {
             return Err(Error::Invalid);
         }
 
-        let choice = match choice_type {
-            0 => {
-                let value = T::decode_body(&data[24..])?;
-                Choice::None(value)
+        decode_body(&values[child_size * i..child_size * (i + 1)]).map_err(|e| {
+            e.at(
+                24 + child_size * i,
+                Some(PathSegment::Element(Type::Choice, i)),
+            )
+        })
+    };
+
+    let choice = match ChoiceType::try_from(choice_type).map_err(|_| Error::Invalid)? {
+        ChoiceType::None => {
+            let value = value(0)?;
+            Choice::None(value)
+        }
+        ChoiceType::Range => {
+            if values.len() != child_size * 3 {
+                return Err(Error::Invalid);
             }
-            1 => {
-                if size != 16 + child_size * 3 {
-                    return Err(Error::Invalid);
-                }
 
-                let default = T::decode_body(&data[24..])?;
-                let min = T::decode_body(&data[24 + child_size..])?;
-                let max = T::decode_body(&data[24 + child_size * 2..])?;
+            let default = value(0)?;
+            let min = value(1)?;
+            let max = value(2)?;
 
-                Choice::Range { default, min, max }
+            Choice::Range { default, min, max }
+        }
+        ChoiceType::Step => {
+            if values.len() != child_size * 4 {
+                return Err(Error::Invalid);
             }
-            2 => {
-                if size != 16 + child_size * 4 {
-                    return Err(Error::Invalid);
-                }
 
-                let default = T::decode_body(&data[24..])?;
-                let min = T::decode_body(&data[24 + child_size..])?;
-                let max = T::decode_body(&data[24 + child_size * 2..])?;
-                let step = T::decode_body(&data[24 + child_size * 3..])?;
+            let default = value(0)?;
+            let min = value(1)?;
+            let max = value(2)?;
+            let step = value(3)?;
 
-                Choice::Step {
-                    default,
-                    min,
-                    max,
-                    step,
-                }
+            Choice::Step {
+                default,
+                min,
+                max,
+                step,
             }
-            3 => {
-                let default = T::decode_body(&data[24..])?;
-                let mut alternatives = Vec::new();
+        }
+        ChoiceType::Enum => {
+            let default = value(0)?;
+            let mut alternatives = Vec::with_capacity(n_values.saturating_sub(1));
 
-                for i in 1..(size - 16) / child_size {
-                    alternatives.push(T::decode_body(&data[24 + child_size * i..])?);
-                }
+            for i in 1..n_values {
+                alternatives.push(value(i)?);
+            }
 
-                Choice::Enum {
-                    default,
-                    alternatives,
-                }
+            Choice::Enum {
+                default,
+                alternatives,
+            }
+        }
+        ChoiceType::Flags => {
+            if values.len() != child_size * 2 {
+                return Err(Error::Invalid);
             }
-            4 => {
-                if size != 16 + child_size * 2 {
-                    return Err(Error::Invalid);
-                }
 
-                let default = T::decode_body(&data[24..])?;
-                let flags = T::decode_body(&data[24 + child_size..])?;
+            let default = value(0)?;
+            let flags = value(1)?;
 
-                Choice::Flags { default, flags }
-            }
-            _ => return Err(Error::Invalid),
-        };
+            Choice::Flags { default, flags }
+        }
+    };
 
-        Ok((choice, 8 + size + padding))
-    }
+    Ok(choice)
 }
 
 impl<T, U> Pod for Property<T, U>
@@ -809,23 +1185,191 @@ where
     }
 
     fn decode(data: &[u8]) -> Result<(Self::DecodesTo, usize), Error> {
-        if data.len() < 8 {
-            return Err(Error::Invalid);
-        }
-
-        let key = match T::try_from(u32::from_ne_bytes(data[0..4].try_into().unwrap())) {
+        let key = match T::try_from(read_u32(data, 0)?) {
             Ok(k) => k,
             Err(_) => return Err(Error::Invalid),
         };
 
-        let flags =
-            match PropertyFlags::from_bits(u32::from_ne_bytes(data[4..8].try_into().unwrap())) {
-                Some(f) => f,
-                None => return Err(Error::Invalid),
-            };
+        let flags = PropertyFlags::from_bits_retain(read_u32(data, 4)?);
 
         let (value, size) = U::decode(&data[8..])?;
 
         Ok((Property { key, flags, value }, 8 + size))
     }
 }
+
+// Sequence is encoded as:
+//
+// +--------------+
+// |  total size  | 4 bytes
+// +--------------+
+// |   pod type   | 4 bytes
+// +--------------+
+// |     unit     | 4 bytes
+// +--------------+
+// |   padding    | 4 bytes
+// +--------------+
+// |              |
+// |   controls   |
+// |              |
+// +--------------+
+//
+// where each control is
+//
+// +--------------+
+// |    offset    | 4 bytes
+// +--------------+
+// | control type | 4 bytes
+// +--------------+
+// |              |
+// |    value     |
+// |     pod      |
+// |              |
+// +--------------+
+//
+impl Pod for Sequence {
+    type DecodesTo = Sequence;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        let mut size = 8;
+        for control in self.iter() {
+            // Values must be complete, padded pods
+            if RawPod::wrap(&control.value)?.total_size() != control.value.len() {
+                return Err(Error::Invalid);
+            }
+            size += 8 + control.value.len();
+        }
+
+        if size > u32::MAX as usize || data.len() < 8 + size {
+            return Err(Error::NoSpace);
+        }
+
+        data[0..4].copy_from_slice(&(size as u32).to_ne_bytes());
+        data[4..8].copy_from_slice(&(Type::Sequence as u32).to_ne_bytes());
+        data[8..12].copy_from_slice(&self.unit.to_ne_bytes());
+        data[12..16].copy_from_slice(&[0, 0, 0, 0]);
+
+        let mut pos = 16;
+        for control in self.iter() {
+            data[pos..pos + 4].copy_from_slice(&control.offset.to_ne_bytes());
+            data[pos + 4..pos + 8].copy_from_slice(&(control.type_ as u32).to_ne_bytes());
+            data[pos + 8..pos + 8 + control.value.len()].copy_from_slice(&control.value);
+            pos += 8 + control.value.len();
+        }
+
+        Ok(8 + size)
+    }
+
+    fn decode(data: &[u8]) -> Result<(Sequence, usize), Error> {
+        let (size, total) = read_header(data)?;
+        check_type(data, Type::Sequence)?;
+
+        if size < 8 {
+            return Err(Error::Invalid);
+        }
+
+        let unit = read_u32(data, 8)?;
+        let mut sequence = Sequence::new(unit);
+
+        let body = &data[8..8 + size];
+        let mut pos = 8;
+
+        while pos < size {
+            if size - pos < 16 {
+                return Err(Error::Invalid);
+            }
+
+            let offset = read_u32(body, pos)?;
+            let type_ =
+                ControlType::try_from(read_u32(body, pos + 4)?).map_err(|_| Error::Invalid)?;
+
+            let value = RawPod::wrap(&body[pos + 8..])?;
+            let value_size = value.total_size();
+
+            sequence.insert(Control {
+                offset,
+                type_,
+                value: body[pos + 8..pos + 8 + value_size].to_vec(),
+            });
+
+            pos += 8 + value_size;
+        }
+
+        Ok((sequence, total))
+    }
+}
+
+impl<'a, T> PodBorrow<'a> for T
+where
+    T: Primitive,
+{
+    fn decode_borrowed(data: &'a [u8]) -> Result<(T, usize), Error> {
+        <T as Pod>::decode(data)
+    }
+}
+
+impl<'a> PodBorrow<'a> for &'a str {
+    fn decode_borrowed(data: &'a [u8]) -> Result<(&'a str, usize), Error> {
+        let bytes = borrow_body(data, Type::String)?;
+        let len = bytes.len();
+
+        // Null terminator
+        if len == 0 || bytes[len - 1] != 0 {
+            return Err(Error::Invalid);
+        }
+
+        let s = std::str::from_utf8(&bytes[..len - 1]).map_err(|_| Error::Invalid)?;
+
+        Ok((s, 8 + len + pad_8(len)))
+    }
+}
+
+impl<'a> PodBorrow<'a> for &'a [u8] {
+    fn decode_borrowed(data: &'a [u8]) -> Result<(&'a [u8], usize), Error> {
+        let bytes = borrow_body(data, Type::Bytes)?;
+        let len = bytes.len();
+
+        Ok((bytes, 8 + len + pad_8(len)))
+    }
+}
+
+impl<'a> PodBorrow<'a> for Bitmap<'a> {
+    fn decode_borrowed(data: &'a [u8]) -> Result<(Bitmap<'a>, usize), Error> {
+        let bytes = borrow_body(data, Type::Bitmap)?;
+        let len = bytes.len();
+
+        Ok((Bitmap(bytes), 8 + len + pad_8(len)))
+    }
+}
+
+// Returns the body of a variable-sized pod of the given type
+fn borrow_body(data: &[u8], type_: Type) -> Result<&[u8], Error> {
+    let (len, _) = read_header(data)?;
+    check_type(data, type_)?;
+
+    Ok(&data[8..8 + len])
+}
+
+impl<'a, T> PodBorrow<'a> for ArrayRef<'a, T>
+where
+    T: Primitive,
+{
+    fn decode_borrowed(data: &'a [u8]) -> Result<(ArrayRef<'a, T>, usize), Error> {
+        let (size, total) = read_header(data)?;
+        if size < 8 {
+            return Err(Error::Invalid);
+        }
+
+        check_type(data, Type::Array)?;
+        check_type(&data[8..], T::pod_type())?;
+
+        let child_size = read_u32(data, 8)? as usize;
+        if child_size != T::pod_size() || child_size == 0 || !(size - 8).is_multiple_of(child_size)
+        {
+            return Err(Error::Invalid);
+        }
+
+        // Elements are only decoded when accessed
+        Ok((ArrayRef::new(&data[16..8 + size]), total))
+    }
+}
@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Random access to the properties of an object pod, like spa_pod_find_prop(). Values are only
+// decoded when asked for, and properties can be looked up by key in any order.
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+use super::types::{ObjectType, PropertyFlags, Type};
+use super::{
+    check_type, encode_to_vec, read_header, read_u32, Error, PathSegment, Pod, PodBorrow, RawPod,
+};
+
+// The new flags and encoded value of a changed property, or None if it is removed
+type Change = Option<(PropertyFlags, Vec<u8>)>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PropertyRef<'a> {
+    pub key: u32,
+    pub flags: PropertyFlags,
+    pub value: RawPod<'a>,
+}
+
+// View of an object pod. The layout of all properties is checked up front, so that accessors
+// don't need to fail, and the properties are indexed by key on the first lookup.
+#[derive(Debug)]
+pub struct ObjectRef<'a> {
+    data: &'a [u8],
+    type_: u32,
+    id: u32,
+    // Properties in the order they appear in, along with the offset of their value
+    properties: Vec<(PropertyRef<'a>, usize)>,
+    // Key to position in properties, for the first property with that key
+    index: OnceCell<HashMap<u32, usize>>,
+}
+
+impl<'a> ObjectRef<'a> {
+    pub fn wrap(data: &'a [u8]) -> Result<ObjectRef<'a>, Error> {
+        let (size, total) = read_header(data)?;
+        check_type(data, Type::Object)?;
+
+        if size < 8 {
+            return Err(Error::Invalid);
+        }
+
+        let type_ = read_u32(data, 8)?;
+        let id = read_u32(data, 12)?;
+        let body = &data[..8 + size];
+
+        let mut properties = Vec::new();
+        let mut pos = 16;
+
+        while pos < body.len() {
+            let key = read_u32(body, pos)?;
+            let flags = PropertyFlags::from_bits_retain(read_u32(body, pos + 4)?);
+            let value = RawPod::wrap(&body[pos + 8..]).map_err(|e| {
+                e.at(
+                    pos + 8,
+                    Some(PathSegment::Property {
+                        object_type: type_,
+                        key,
+                    }),
+                )
+                .at(0, Some(PathSegment::Object(type_)))
+            })?;
+
+            properties.push((PropertyRef { key, flags, value }, pos + 8));
+            pos += 8 + value.total_size();
+        }
+
+        Ok(ObjectRef {
+            data: &data[..total],
+            type_,
+            id,
+            properties,
+            index: OnceCell::new(),
+        })
+    }
+
+    pub fn type_(&self) -> u32 {
+        self.type_
+    }
+
+    pub fn object_type(&self) -> Option<ObjectType> {
+        ObjectType::try_from(self.type_).ok()
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn total_size(&self) -> usize {
+        self.data.len()
+    }
+
+    // The complete pod, including the header and padding
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = PropertyRef<'a>> + '_ {
+        self.properties.iter().map(|(p, _)| *p)
+    }
+
+    fn position(&self, key: u32) -> Option<usize> {
+        let index = self.index.get_or_init(|| {
+            let mut index = HashMap::with_capacity(self.properties.len());
+            for (i, (p, _)) in self.properties.iter().enumerate() {
+                index.entry(p.key).or_insert(i);
+            }
+            index
+        });
+
+        index.get(&key).copied()
+    }
+
+    pub fn property<K: Into<u32>>(&self, key: K) -> Option<PropertyRef<'a>> {
+        self.position(key.into()).map(|i| self.properties[i].0)
+    }
+
+    pub fn get<K: Into<u32>>(&self, key: K) -> Option<RawPod<'a>> {
+        self.property(key).map(|p| p.value)
+    }
+
+    // Decode the value of a property, if the object has it
+    pub fn get_as<T: Pod>(&self, key: impl Into<u32>) -> Result<Option<T::DecodesTo>, Error> {
+        let key = key.into();
+        let Some(i) = self.position(key) else {
+            return Ok(None);
+        };
+
+        let (property, offset) = &self.properties[i];
+        property.value.decode::<T>().map(Some).map_err(|e| {
+            e.at(
+                *offset,
+                Some(PathSegment::Property {
+                    object_type: self.type_,
+                    key,
+                }),
+            )
+            .at(0, Some(PathSegment::Object(self.type_)))
+        })
+    }
+
+    // Start building a copy of this object with some properties changed
+    pub fn rewrite(&self) -> ObjectRewrite<'_, 'a> {
+        ObjectRewrite {
+            object: self,
+            changes: Vec::new(),
+            index: HashMap::new(),
+            error: None,
+        }
+    }
+}
+
+impl<'a> PodBorrow<'a> for ObjectRef<'a> {
+    fn decode_borrowed(data: &'a [u8]) -> Result<(ObjectRef<'a>, usize), Error> {
+        let object = ObjectRef::wrap(data)?;
+        let size = object.total_size();
+
+        Ok((object, size))
+    }
+}
+
+// A copy of an object with properties replaced, added or removed. Properties that are kept or
+// replaced stay in the same place, new ones are added at the end in the order they were first set.
+// Errors are deferred until build(), so that calls can be chained.
+pub struct ObjectRewrite<'o, 'a> {
+    object: &'o ObjectRef<'a>,
+    changes: Vec<(u32, Change)>,
+    // Position of each key in changes
+    index: HashMap<u32, usize>,
+    error: Option<Error>,
+}
+
+impl ObjectRewrite<'_, '_> {
+    fn change(&mut self, key: u32, change: Change) {
+        match self.index.get(&key) {
+            Some(&i) => self.changes[i].1 = change,
+            None => {
+                self.index.insert(key, self.changes.len());
+                self.changes.push((key, change));
+            }
+        }
+    }
+
+    pub fn set<K, V>(&mut self, key: K, flags: PropertyFlags, value: &V) -> &mut Self
+    where
+        K: Into<u32>,
+        V: Pod,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+
+        match encode_to_vec(value) {
+            Ok(value) => self.change(key.into(), Some((flags, value))),
+            Err(e) => self.error = Some(e),
+        }
+
+        self
+    }
+
+    pub fn remove<K: Into<u32>>(&mut self, key: K) -> &mut Self {
+        self.change(key.into(), None);
+        self
+    }
+
+    pub fn build(self) -> Result<Vec<u8>, Error> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let mut out = Vec::with_capacity(self.object.total_size());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(Type::Object as u32).to_ne_bytes());
+        out.extend_from_slice(&self.object.type_.to_ne_bytes());
+        out.extend_from_slice(&self.object.id.to_ne_bytes());
+
+        let mut push_property = |key: u32, flags: PropertyFlags, value: &[u8]| {
+            out.extend_from_slice(&key.to_ne_bytes());
+            out.extend_from_slice(&flags.bits().to_ne_bytes());
+            out.extend_from_slice(value);
+        };
+
+        for property in self.object.iter() {
+            match self.index.get(&property.key).map(|&i| &self.changes[i]) {
+                Some((_, None)) => (),
+                Some((_, Some((flags, value)))) => push_property(property.key, *flags, value),
+                None => push_property(property.key, property.flags, property.value.as_bytes()),
+            }
+        }
+
+        for (key, change) in &self.changes {
+            if let Some((flags, value)) = change {
+                if self.object.property(*key).is_none() {
+                    push_property(*key, *flags, value);
+                }
+            }
+        }
+
+        let size = u32::try_from(out.len() - 8).map_err(|_| Error::NoSpace)?;
+        out[0..4].copy_from_slice(&size.to_ne_bytes());
+
+        Ok(out)
+    }
+}
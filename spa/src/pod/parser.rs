@@ -4,23 +4,71 @@
 
 use crate::param::{ParamObject, ParamType};
 
-use super::types::{Choice, Fd, Fraction, Id, ObjectType, Pointer, PropertyFlags, Rectangle, Type};
-use super::{Error, Pod, Primitive, RawPod};
+use super::object::ObjectRef;
+use super::types::{
+    ArrayRef, Bitmap, Choice, Fd, Flags, Fraction, Id, Pointer, PropertyFlags, Rectangle, Sequence,
+    Type,
+};
+use super::value::Value;
+use super::{
+    check_type, read_header, read_u32, Error, PathSegment, Pod, PodBorrow, Primitive, RawPod,
+};
 
 pub struct Parser<'a> {
     data: &'a [u8],
     pos: usize,
+    // For the fields of a struct, the number of fields popped so far
+    index: Option<usize>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(data: &'a [u8]) -> Parser<'a> {
-        Parser { data, pos: 0 }
+        Parser {
+            data,
+            pos: 0,
+            index: None,
+        }
     }
 
-    pub fn pop_pod<U: Pod>(&mut self) -> Result<<U as Pod>::DecodesTo, Error> {
-        let (res, size) = U::decode(&self.data[self.pos..])?;
+    fn new_struct(data: &'a [u8]) -> Parser<'a> {
+        Parser {
+            data,
+            pos: 0,
+            index: Some(0),
+        }
+    }
+
+    // Add the location of the current pod to an error from within it
+    fn error_at(&self, error: Error, offset: usize) -> Error {
+        let segment = self.index.map(|i| PathSegment::Element(Type::Struct, i));
+        error.at(self.pos + offset, segment)
+    }
+
+    // The data from the current position on
+    fn remaining(&self) -> Result<&'a [u8], Error> {
+        let data: &'a [u8] = self.data;
+        data.get(self.pos..).ok_or(Error::Invalid)
+    }
 
+    fn advance(&mut self, size: usize) {
         self.pos += size;
+        if let Some(index) = &mut self.index {
+            *index += 1;
+        }
+    }
+
+    pub fn pop_pod<U: Pod>(&mut self) -> Result<<U as Pod>::DecodesTo, Error> {
+        let (res, size) = U::decode(self.remaining()?).map_err(|e| self.error_at(e, 0))?;
+
+        self.advance(size);
+
+        Ok(res)
+    }
+
+    pub fn pop_borrowed<U: PodBorrow<'a>>(&mut self) -> Result<U, Error> {
+        let (res, size) = U::decode_borrowed(self.remaining()?).map_err(|e| self.error_at(e, 0))?;
+
+        self.advance(size);
 
         Ok(res)
     }
@@ -40,6 +88,13 @@ impl<'a> Parser<'a> {
         self.pop_pod::<Id<T>>()
     }
 
+    pub fn pop_flags<T>(&mut self) -> Result<T, Error>
+    where
+        T: bitflags::Flags<Bits = u32>,
+    {
+        self.pop_pod::<Flags<T>>().map(|f| f.0)
+    }
+
     pub fn pop_int(&mut self) -> Result<i32, Error> {
         self.pop_pod::<i32>()
     }
@@ -64,6 +119,18 @@ impl<'a> Parser<'a> {
         self.pop_pod::<&[u8]>()
     }
 
+    pub fn pop_string_ref(&mut self) -> Result<&'a str, Error> {
+        self.pop_borrowed::<&str>()
+    }
+
+    pub fn pop_bytes_ref(&mut self) -> Result<&'a [u8], Error> {
+        self.pop_borrowed::<&[u8]>()
+    }
+
+    pub fn pop_bitmap(&mut self) -> Result<Bitmap<'a>, Error> {
+        self.pop_borrowed::<Bitmap>()
+    }
+
     pub fn pop_pointer(&mut self) -> Result<Pointer, Error> {
         self.pop_pod::<Pointer>()
     }
@@ -87,6 +154,17 @@ impl<'a> Parser<'a> {
         self.pop_pod::<&[T]>()
     }
 
+    pub fn pop_array_ref<T>(&mut self) -> Result<ArrayRef<'a, T>, Error>
+    where
+        T: Primitive,
+    {
+        self.pop_borrowed::<ArrayRef<T>>()
+    }
+
+    pub fn pop_object_ref(&mut self) -> Result<ObjectRef<'a>, Error> {
+        self.pop_borrowed::<ObjectRef>()
+    }
+
     pub fn pop_choice<T>(&mut self) -> Result<Choice<T>, Error>
     where
         T: Pod + Primitive,
@@ -94,96 +172,104 @@ impl<'a> Parser<'a> {
         self.pop_pod::<Choice<T>>()
     }
 
+    pub fn pop_sequence(&mut self) -> Result<Sequence, Error> {
+        self.pop_pod::<Sequence>()
+    }
+
+    pub fn pop_value(&mut self) -> Result<Value, Error> {
+        self.pop_pod::<Value>()
+    }
+
     pub fn pop_struct<F, T>(&mut self, parse_struct: F) -> Result<(T, usize), Error>
     where
-        F: FnOnce(&mut Parser) -> Result<T, Error>,
+        F: FnOnce(&mut Parser<'a>) -> Result<T, Error>,
     {
-        if self.data.len() < 8 {
-            return Err(Error::Invalid);
-        }
-
-        let size =
-            u32::from_ne_bytes(self.data[self.pos..self.pos + 4].try_into().unwrap()) as usize;
-        if self.data.len() < 8 + size {
-            return Err(Error::Invalid);
-        }
+        let data = self.remaining()?;
+        let (size, total) = read_header(data).map_err(|e| self.error_at(e, 0))?;
 
-        let t = u32::from_ne_bytes(self.data[self.pos + 4..self.pos + 8].try_into().unwrap());
-        if t != Type::Struct as u32 {
-            return Err(Error::Invalid);
-        }
+        check_type(data, Type::Struct).map_err(|e| self.error_at(e, 0))?;
 
-        let mut struct_parser = Parser::new(&self.data[self.pos + 8..self.pos + 8 + size]);
-        let ret = parse_struct(&mut struct_parser)?;
+        let mut struct_parser = Parser::new_struct(&data[8..8 + size]);
+        let ret = parse_struct(&mut struct_parser).map_err(|e| self.error_at(e, 8))?;
 
         // The caller may or may not iterate over all fields, don't depend on that
-        self.pos += size + 8;
+        self.advance(total);
 
-        Ok((ret, size + 8))
+        Ok((ret, total))
     }
 
     pub fn pop_object<K, T>(
-        &'a mut self,
-        parse_object: impl FnOnce(&mut ObjectParser<'_>, ParamType) -> Result<T, Error>,
+        &mut self,
+        parse_object: impl FnOnce(&mut ObjectParser<'a>, ParamType) -> Result<T, Error>,
     ) -> Result<(T, usize), Error>
     where
         K: ParamObject,
     {
-        if self.data.len() < 16 {
-            return Err(Error::Invalid);
-        }
+        let data = self.remaining()?;
+        let (size, total) = read_header(data).map_err(|e| self.error_at(e, 0))?;
 
-        let size =
-            u32::from_ne_bytes(self.data[self.pos..self.pos + 4].try_into().unwrap()) as usize;
-        if self.data.len() < 8 + size {
-            return Err(Error::Invalid);
-        }
+        check_type(data, Type::Object).map_err(|e| self.error_at(e, 0))?;
 
-        let t = u32::from_ne_bytes(self.data[self.pos + 4..self.pos + 8].try_into().unwrap());
-        if t != Type::Object as u32 {
-            return Err(Error::Invalid);
+        if size < 8 {
+            return Err(self.error_at(Error::Invalid, 0));
         }
 
-        let object_type = match ObjectType::try_from(u32::from_ne_bytes(
-            self.data[self.pos + 8..self.pos + 12].try_into().unwrap(),
-        )) {
-            Ok(ot) => ot,
-            Err(_) => return Err(Error::Invalid),
-        };
-
-        let param_type = match ParamType::try_from(u32::from_ne_bytes(
-            self.data[self.pos + 12..self.pos + 16].try_into().unwrap(),
-        )) {
-            Ok(id) => id,
-            Err(_) => return Err(Error::Invalid),
-        };
-
-        if object_type != K::TYPE {
-            return Err(Error::Invalid);
+        let found = read_u32(data, 8)?;
+        if found != K::TYPE as u32 {
+            let e = Error::WrongObjectType {
+                expected: K::TYPE as u32,
+                found,
+            };
+            return Err(self.error_at(e, 8));
         }
+        let object_type = K::TYPE;
 
-        self.pos += 16;
+        let param_type = ParamType::try_from(read_u32(data, 12)?)
+            .map_err(|_| self.error_at(Error::Invalid, 12))?;
 
         let ret = {
-            let mut object_parser = ObjectParser::new(&self.data[self.pos..self.pos + size - 8]);
-            parse_object(&mut object_parser, param_type)?
+            let mut object_parser = ObjectParser::new(&data[16..8 + size]);
+
+            parse_object(&mut object_parser, param_type).map_err(|e| {
+                // Errors raised while a property is being decoded belong to that property, others
+                // to the object itself
+                let e = match object_parser.current {
+                    Some((key, offset)) => e.at(
+                        offset,
+                        Some(PathSegment::Property {
+                            object_type: object_type as u32,
+                            key,
+                        }),
+                    ),
+                    None => e,
+                };
+                self.error_at(e.at(0, Some(PathSegment::Object(object_type as u32))), 16)
+            })?
         };
 
         // The caller may or may not iterate over all properties, don't depend on that
-        self.pos += size - 8;
+        self.advance(total);
 
-        Ok((ret, size + 8))
+        Ok((ret, total))
     }
 }
 
+#[derive(Clone)]
 pub struct ObjectParser<'a> {
     data: &'a [u8],
     pos: usize,
+    // The key and value offset of the property being decoded: the last one that was popped, until
+    // the end of the object is reached
+    current: Option<(u32, usize)>,
 }
 
 impl<'a> ObjectParser<'a> {
     fn new(data: &'a [u8]) -> ObjectParser<'a> {
-        ObjectParser { data, pos: 0 }
+        ObjectParser {
+            data,
+            pos: 0,
+            current: None,
+        }
     }
 
     pub fn done(&self) -> bool {
@@ -194,34 +280,45 @@ impl<'a> ObjectParser<'a> {
     where
         K: TryFrom<u32> + ParamObject,
     {
-        if self.data.len() - self.pos == 0 {
-            return Ok(None);
-        }
+        self.current = None;
 
-        if self.data.len() - self.pos < 16 {
-            return Err(Error::Invalid);
-        }
+        loop {
+            if self.data.len() - self.pos == 0 {
+                return Ok(None);
+            }
 
-        let key = match K::try_from(u32::from_ne_bytes(
-            self.data[self.pos..self.pos + 4].try_into().unwrap(),
-        )) {
-            Ok(k) => k,
-            Err(_) => return Err(Error::Invalid),
-        };
+            if self.data.len() - self.pos < 16 {
+                return Err(Error::Invalid.at(self.pos, None));
+            }
 
-        let flags = match PropertyFlags::from_bits(u32::from_ne_bytes(
-            self.data[self.pos + 4..self.pos + 8].try_into().unwrap(),
-        )) {
-            Some(f) => f,
-            None => return Err(Error::Invalid),
-        };
+            let raw_key = read_u32(self.data, self.pos)?;
 
-        self.pos += 8;
+            // Keep flags we don't know about, like the C API does
+            let flags = PropertyFlags::from_bits_retain(read_u32(self.data, self.pos + 4)?);
 
-        let data = RawPod::wrap(&self.data[self.pos..])?;
+            let data =
+                RawPod::wrap(&self.data[self.pos + 8..]).map_err(|e| e.at(self.pos + 8, None))?;
+            let offset = self.pos + 8;
 
-        self.pos += data.total_size();
+            self.pos += 8 + data.total_size();
 
-        Ok(Some((key, flags, data)))
+            // Objects may carry properties we don't know about (from newer versions, or custom
+            // ones), skip those
+            if let Ok(key) = K::try_from(raw_key) {
+                self.current = Some((raw_key, offset));
+                return Ok(Some((key, flags, data)));
+            }
+        }
+    }
+
+    pub fn pop_property_borrowed<K, V>(&mut self) -> Result<Option<(K, PropertyFlags, V)>, Error>
+    where
+        K: TryFrom<u32> + ParamObject,
+        V: PodBorrow<'a>,
+    {
+        match self.pop_property::<K>()? {
+            Some((key, flags, data)) => Ok(Some((key, flags, data.decode_borrowed::<V>()?))),
+            None => Ok(None),
+        }
     }
 }
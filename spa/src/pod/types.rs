@@ -3,11 +3,14 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
 
 use std::ffi::c_void;
+use std::marker::PhantomData;
 use std::os::fd::RawFd;
 
 use bitflags::bitflags;
 use pipewire_native_macros::EnumU32;
 
+use super::{encode_to_vec, Error, PlainPrimitive, Pod, Primitive, RawPod};
+
 // spa/utils/type.h: Basic SPA_TYPE_*
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
@@ -67,18 +70,196 @@ pub struct Pointer {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Fd(pub RawFd);
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct Rectangle {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct Fraction {
     pub num: u32,
     pub denom: u32,
 }
 
+// Bitmap borrowed from a pod body. Bit i is stored in byte i / 8, least significant bit first.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bitmap<'a>(pub &'a [u8]);
+
+impl<'a> Bitmap<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    // Number of addressable bits
+    pub fn bits(&self) -> usize {
+        self.0.len() * 8
+    }
+
+    pub fn get(&self, bit: usize) -> bool {
+        if bit >= self.bits() {
+            return false;
+        }
+
+        self.0[bit / 8] & (1 << (bit % 8)) != 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.0.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    // Indices of all set bits, in ascending order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + 'a {
+        let bitmap = *self;
+        (0..self.bits()).filter(move |&bit| bitmap.get(bit))
+    }
+
+    pub fn to_owned(&self) -> BitmapBuf {
+        BitmapBuf(self.0.to_vec())
+    }
+}
+
+// Owned bitmap, of any number of bytes
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BitmapBuf(pub Vec<u8>);
+
+impl BitmapBuf {
+    // A bitmap of len bytes, with all bits cleared
+    pub fn new(len: usize) -> Self {
+        Self(vec![0; len])
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bitmap(&self) -> Bitmap<'_> {
+        Bitmap(&self.0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn bits(&self) -> usize {
+        self.as_bitmap().bits()
+    }
+
+    pub fn get(&self, bit: usize) -> bool {
+        self.as_bitmap().get(bit)
+    }
+
+    // Returns false if the bit is out of range
+    pub fn set(&mut self, bit: usize, value: bool) -> bool {
+        if bit >= self.bits() {
+            return false;
+        }
+
+        if value {
+            self.0[bit / 8] |= 1 << (bit % 8);
+        } else {
+            self.0[bit / 8] &= !(1 << (bit % 8));
+        }
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.as_bitmap().count_ones()
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.as_bitmap().iter_ones()
+    }
+}
+
+// Wrapper to encode a set of bitflags as an Int pod, the way SPA encodes flag properties
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Flags<T>(pub T);
+
+// View of the elements of an array pod, decoded on access without copying the array
+#[derive(Clone, Copy, Debug)]
+pub struct ArrayRef<'a, T> {
+    pub(super) data: &'a [u8],
+    _type: PhantomData<T>,
+}
+
+impl<'a, T> ArrayRef<'a, T>
+where
+    T: Primitive,
+{
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            _type: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / T::pod_size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Returns None if the index is out of range or the element is not valid
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let size = T::pod_size();
+        T::decode_body(&self.data[index * size..(index + 1) * size]).ok()
+    }
+
+    // Invalid elements are skipped
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let data = self.data;
+        let size = T::pod_size();
+
+        data.chunks_exact(size)
+            .filter_map(|chunk| T::decode_body(chunk).ok())
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<'a, T> ArrayRef<'a, T>
+where
+    T: PlainPrimitive,
+{
+    // The elements as a slice of the source buffer, or None if it is not aligned for T
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        // SAFETY: PlainPrimitive guarantees any bytes are a valid T, and align_to() only
+        // reinterprets the aligned middle part
+        let (prefix, slice, suffix) = unsafe { self.data.align_to::<T>() };
+
+        if prefix.is_empty() && suffix.is_empty() {
+            Some(slice)
+        } else {
+            None
+        }
+    }
+}
+
+// spa/pod/pod.h: SPA_CHOICE_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum ChoiceType {
+    None = 0,
+    Range,
+    Step,
+    Enum,
+    Flags,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Choice<T> {
     None(T),
@@ -88,14 +269,26 @@ pub enum Choice<T> {
     Flags { default: T, flags: T },
 }
 
+impl<T> Choice<T> {
+    pub fn choice_type(&self) -> ChoiceType {
+        match self {
+            Choice::None(_) => ChoiceType::None,
+            Choice::Range { .. } => ChoiceType::Range,
+            Choice::Step { .. } => ChoiceType::Step,
+            Choice::Enum { .. } => ChoiceType::Enum,
+            Choice::Flags { .. } => ChoiceType::Flags,
+        }
+    }
+}
+
 bitflags! {
-    #[derive(Debug, Eq, PartialEq)]
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     pub struct PropertyFlags: u32 {
         const READ_ONLY = 0x0000_0001;
         const HARDWARE = 0x0000_0002;
         const HINT_DICT = 0x0000_0004;
         const MANDATORY = 0x0000_0008;
-        const DONT_FIXATE = 0x0000_000F;
+        const DONT_FIXATE = 0x0000_0010;
     }
 }
 
@@ -105,3 +298,82 @@ pub struct Property<K, V> {
     pub flags: PropertyFlags,
     pub value: V,
 }
+
+// spa/control/control.h: SPA_CONTROL_*
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumU32)]
+pub enum ControlType {
+    Invalid = 0,
+    Properties,
+    Midi,
+    Osc,
+    Ump,
+}
+
+// A single timed control in a sequence. The value is kept as the complete encoded pod (header,
+// body and padding), since the type of the value depends on the control type: an object for
+// Properties, bytes for MIDI and OSC, and so on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Control {
+    pub offset: u32,
+    pub type_: ControlType,
+    pub value: Vec<u8>,
+}
+
+impl Control {
+    pub fn new<V: Pod>(offset: u32, type_: ControlType, value: &V) -> Result<Self, Error> {
+        Ok(Self {
+            offset,
+            type_,
+            value: encode_to_vec(value)?,
+        })
+    }
+
+    pub fn midi(offset: u32, data: &[u8]) -> Result<Self, Error> {
+        Self::new(offset, ControlType::Midi, &data)
+    }
+
+    pub fn value(&self) -> Result<RawPod<'_>, Error> {
+        RawPod::wrap(&self.value)
+    }
+}
+
+// Sequence of controls, kept sorted by offset
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Sequence {
+    pub unit: u32,
+    controls: Vec<Control>,
+}
+
+impl Sequence {
+    pub fn new(unit: u32) -> Self {
+        Self {
+            unit,
+            controls: Vec::new(),
+        }
+    }
+
+    // Insert a control, after any existing controls with the same offset
+    pub fn insert(&mut self, control: Control) {
+        let pos = self
+            .controls
+            .partition_point(|c| c.offset <= control.offset);
+        self.controls.insert(pos, control);
+    }
+
+    pub fn controls(&self) -> &[Control] {
+        &self.controls
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Control> {
+        self.controls.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.controls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.controls.is_empty()
+    }
+}
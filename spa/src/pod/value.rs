@@ -0,0 +1,481 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use std::ffi::c_void;
+use std::os::fd::RawFd;
+
+use super::types::{
+    ChoiceType, ControlType, Fd, Fraction, ObjectType, Pointer, PropertyFlags, Rectangle, Type,
+};
+use super::{
+    check_alloc, pad_8, read_u32, read_u64, Error, PathSegment, Pod, MAX_DEPTH, MAX_POD_SIZE,
+};
+
+// Dynamically typed, owned representation of any pod. Object types, ids and property keys are kept
+// as raw values, so that objects from any plugin can be represented.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    None,
+    Bool(bool),
+    Id(u32),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Rectangle(Rectangle),
+    Fraction(Fraction),
+    Bitmap(Vec<u8>),
+    Array(ArrayValue),
+    Struct(Vec<Value>),
+    Object(ObjectValue),
+    Sequence(SequenceValue),
+    Pointer(Pointer),
+    Fd(Fd),
+    Choice(ChoiceValue),
+}
+
+// All values must be of child_type, which is also needed to encode an empty array
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrayValue {
+    pub child_type: Type,
+    pub values: Vec<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectValue {
+    pub type_: u32,
+    pub id: u32,
+    pub properties: Vec<PropertyValue>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyValue {
+    pub key: u32,
+    pub flags: PropertyFlags,
+    pub value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequenceValue {
+    pub unit: u32,
+    pub controls: Vec<ControlValue>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlValue {
+    pub offset: u32,
+    pub type_: ControlType,
+    pub value: Value,
+}
+
+// The first value is the default, the meaning of the rest depends on the choice type
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChoiceValue {
+    pub type_: ChoiceType,
+    pub flags: u32,
+    pub child_type: Type,
+    pub values: Vec<Value>,
+}
+
+impl ObjectValue {
+    pub fn object_type(&self) -> Option<ObjectType> {
+        ObjectType::try_from(self.type_).ok()
+    }
+
+    pub fn property<K: Into<u32>>(&self, key: K) -> Option<&PropertyValue> {
+        let key = key.into();
+        self.properties.iter().find(|p| p.key == key)
+    }
+}
+
+fn read_type(data: &[u8], offset: usize) -> Result<Type, Error> {
+    Type::try_from(read_u32(data, offset)?).map_err(|_| Error::Invalid)
+}
+
+// Body size of an element of the given type, used for the child size of empty arrays and choices
+fn default_body_size(type_: Type) -> usize {
+    match type_ {
+        Type::Bool | Type::Id | Type::Int | Type::Float => 4,
+        Type::Long | Type::Double | Type::Fd | Type::Rectangle | Type::Fraction => 8,
+        Type::Pointer => 8 + std::mem::size_of::<*const c_void>(),
+        _ => 0,
+    }
+}
+
+// Decode a complete pod, returning the value and the padded size. The depth is the number of
+// pods this one is nested in.
+fn decode_pod(data: &[u8], depth: usize) -> Result<(Value, usize), Error> {
+    let size = read_u32(data, 0)? as usize;
+    let type_ = read_type(data, 4)?;
+
+    if size > MAX_POD_SIZE || depth > MAX_DEPTH {
+        return Err(Error::Invalid);
+    }
+
+    let body = data.get(8..8 + size).ok_or(Error::Invalid)?;
+    let value = Value::decode_body(type_, body, depth).map_err(|e| e.at(8, None))?;
+
+    Ok((value, 8 + size + pad_8(size)))
+}
+
+// Decode a list of fixed-size bodies, as found in arrays and choices
+fn decode_children(
+    parent_type: Type,
+    child_type: Type,
+    child_size: usize,
+    data: &[u8],
+    depth: usize,
+) -> Result<Vec<Value>, Error> {
+    if child_size == 0 {
+        return Ok(Vec::new());
+    }
+
+    check_alloc::<Value>(data.len() / child_size)?;
+
+    data.chunks_exact(child_size)
+        .enumerate()
+        .map(|(i, body)| {
+            Value::decode_body(child_type, body, depth + 1)
+                .map_err(|e| e.at(i * child_size, Some(PathSegment::Element(parent_type, i))))
+        })
+        .collect()
+}
+
+// Encode a list of bodies that must all be of the same type and size, returning the child size
+fn encode_children(child_type: Type, values: &[Value], out: &mut Vec<u8>) -> Result<u32, Error> {
+    let mut child_size = None;
+
+    for value in values {
+        if value.type_() != child_type {
+            return Err(Error::Invalid);
+        }
+
+        let start = out.len();
+        value.encode_body(out)?;
+        let size = out.len() - start;
+
+        match child_size {
+            None => child_size = Some(size),
+            Some(s) if s != size => return Err(Error::Invalid),
+            Some(_) => (),
+        }
+    }
+
+    Ok(child_size.unwrap_or_else(|| default_body_size(child_type)) as u32)
+}
+
+// Decode the properties of an object body, which start after the object type and id
+fn decode_properties(
+    object_type: u32,
+    body: &[u8],
+    depth: usize,
+) -> Result<Vec<PropertyValue>, Error> {
+    let mut properties = Vec::new();
+    let mut pos = 8;
+
+    while pos < body.len() {
+        let key = read_u32(body, pos)?;
+        let flags = PropertyFlags::from_bits_retain(read_u32(body, pos + 4)?);
+        let (value, size) = decode_pod(&body[pos + 8..], depth + 1)
+            .map_err(|e| e.at(pos + 8, Some(PathSegment::Property { object_type, key })))?;
+
+        properties.push(PropertyValue { key, flags, value });
+        pos += 8 + size;
+    }
+
+    Ok(properties)
+}
+
+impl Value {
+    pub fn type_(&self) -> Type {
+        match self {
+            Value::None => Type::None,
+            Value::Bool(_) => Type::Bool,
+            Value::Id(_) => Type::Id,
+            Value::Int(_) => Type::Int,
+            Value::Long(_) => Type::Long,
+            Value::Float(_) => Type::Float,
+            Value::Double(_) => Type::Double,
+            Value::String(_) => Type::String,
+            Value::Bytes(_) => Type::Bytes,
+            Value::Rectangle(_) => Type::Rectangle,
+            Value::Fraction(_) => Type::Fraction,
+            Value::Bitmap(_) => Type::Bitmap,
+            Value::Array(_) => Type::Array,
+            Value::Struct(_) => Type::Struct,
+            Value::Object(_) => Type::Object,
+            Value::Sequence(_) => Type::Sequence,
+            Value::Pointer(_) => Type::Pointer,
+            Value::Fd(_) => Type::Fd,
+            Value::Choice(_) => Type::Choice,
+        }
+    }
+
+    // Encode the complete pod (header, body and padding) into a new buffer
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        self.write(&mut out)?;
+        Ok(out)
+    }
+
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), Error> {
+        let start = out.len();
+
+        // Header, the size is filled in after the body is written
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(self.type_() as u32).to_ne_bytes());
+
+        self.encode_body(out)?;
+
+        let size = out.len() - start - 8;
+        if size > u32::MAX as usize {
+            return Err(Error::NoSpace);
+        }
+        out[start..start + 4].copy_from_slice(&(size as u32).to_ne_bytes());
+        out.resize(out.len() + pad_8(size), 0);
+
+        Ok(())
+    }
+
+    fn decode_body(type_: Type, body: &[u8], depth: usize) -> Result<Value, Error> {
+        // Array and choice children are bodies without a pod of their own, so the depth is
+        // checked here as well as in decode_pod()
+        if depth > MAX_DEPTH {
+            return Err(Error::Invalid);
+        }
+
+        let value = match type_ {
+            Type::None => Value::None,
+            Type::Bool => Value::Bool(read_u32(body, 0)? != 0),
+            Type::Id => Value::Id(read_u32(body, 0)?),
+            Type::Int => Value::Int(read_u32(body, 0)? as i32),
+            Type::Long => Value::Long(read_u64(body, 0)? as i64),
+            Type::Float => Value::Float(f32::from_bits(read_u32(body, 0)?)),
+            Type::Double => Value::Double(f64::from_bits(read_u64(body, 0)?)),
+            Type::String => {
+                // Null terminator
+                match body.split_last() {
+                    Some((0, s)) => {
+                        Value::String(String::from_utf8(s.to_vec()).map_err(|_| Error::Invalid)?)
+                    }
+                    _ => return Err(Error::Invalid),
+                }
+            }
+            Type::Bytes => Value::Bytes(body.to_vec()),
+            Type::Rectangle => Value::Rectangle(Rectangle {
+                width: read_u32(body, 0)?,
+                height: read_u32(body, 4)?,
+            }),
+            Type::Fraction => Value::Fraction(Fraction {
+                num: read_u32(body, 0)?,
+                denom: read_u32(body, 4)?,
+            }),
+            Type::Bitmap => Value::Bitmap(body.to_vec()),
+            Type::Array => {
+                let child_size = read_u32(body, 0)? as usize;
+                let child_type = read_type(body, 4)?;
+
+                Value::Array(ArrayValue {
+                    child_type,
+                    values: decode_children(Type::Array, child_type, child_size, &body[8..], depth)
+                        .map_err(|e| e.at(8, None))?,
+                })
+            }
+            Type::Struct => {
+                let mut values = Vec::new();
+                let mut pos = 0;
+
+                while pos < body.len() {
+                    let (value, size) = decode_pod(&body[pos..], depth + 1).map_err(|e| {
+                        e.at(pos, Some(PathSegment::Element(Type::Struct, values.len())))
+                    })?;
+                    values.push(value);
+                    pos += size;
+                }
+
+                Value::Struct(values)
+            }
+            Type::Object => {
+                let type_ = read_u32(body, 0)?;
+                let id = read_u32(body, 4)?;
+
+                Value::Object(ObjectValue {
+                    type_,
+                    id,
+                    properties: decode_properties(type_, body, depth)
+                        .map_err(|e| e.at(0, Some(PathSegment::Object(type_))))?,
+                })
+            }
+            Type::Sequence => {
+                if body.len() < 8 {
+                    return Err(Error::Invalid);
+                }
+
+                let unit = read_u32(body, 0)?;
+                let mut controls = Vec::new();
+                let mut pos = 8;
+
+                while pos < body.len() {
+                    let offset = read_u32(body, pos)?;
+                    let type_ = ControlType::try_from(read_u32(body, pos + 4)?)
+                        .map_err(|_| Error::Invalid)?;
+                    let (value, size) = decode_pod(&body[pos + 8..], depth + 1).map_err(|e| {
+                        e.at(
+                            pos + 8,
+                            Some(PathSegment::Element(Type::Sequence, controls.len())),
+                        )
+                    })?;
+
+                    controls.push(ControlValue {
+                        offset,
+                        type_,
+                        value,
+                    });
+                    pos += 8 + size;
+                }
+
+                Value::Sequence(SequenceValue { unit, controls })
+            }
+            Type::Pointer => {
+                let type_ = read_type(body, 0)?;
+                let ptr = if std::mem::size_of::<*const c_void>() == 8 {
+                    read_u64(body, 8)? as *const c_void
+                } else {
+                    read_u32(body, 8)? as *const c_void
+                };
+
+                Value::Pointer(Pointer { type_, ptr })
+            }
+            Type::Fd => Value::Fd(Fd(read_u64(body, 0)? as i64 as RawFd)),
+            Type::Choice => {
+                let choice_type =
+                    ChoiceType::try_from(read_u32(body, 0)?).map_err(|_| Error::Invalid)?;
+                let flags = read_u32(body, 4)?;
+                let child_size = read_u32(body, 8)? as usize;
+                let child_type = read_type(body, 12)?;
+
+                Value::Choice(ChoiceValue {
+                    type_: choice_type,
+                    flags,
+                    child_type,
+                    values: decode_children(
+                        Type::Choice,
+                        child_type,
+                        child_size,
+                        &body[16..],
+                        depth,
+                    )
+                    .map_err(|e| e.at(16, None))?,
+                })
+            }
+            Type::Start | Type::Pod => return Err(Error::Invalid),
+        };
+
+        Ok(value)
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) -> Result<(), Error> {
+        match self {
+            Value::None => (),
+            Value::Bool(v) => out.extend_from_slice(&(*v as u32).to_ne_bytes()),
+            Value::Id(v) => out.extend_from_slice(&v.to_ne_bytes()),
+            Value::Int(v) => out.extend_from_slice(&v.to_ne_bytes()),
+            Value::Long(v) => out.extend_from_slice(&v.to_ne_bytes()),
+            Value::Float(v) => out.extend_from_slice(&v.to_ne_bytes()),
+            Value::Double(v) => out.extend_from_slice(&v.to_ne_bytes()),
+            Value::String(v) => {
+                out.extend_from_slice(v.as_bytes());
+                // Null terminator
+                out.push(0);
+            }
+            Value::Bytes(v) | Value::Bitmap(v) => out.extend_from_slice(v),
+            Value::Rectangle(v) => {
+                out.extend_from_slice(&v.width.to_ne_bytes());
+                out.extend_from_slice(&v.height.to_ne_bytes());
+            }
+            Value::Fraction(v) => {
+                out.extend_from_slice(&v.num.to_ne_bytes());
+                out.extend_from_slice(&v.denom.to_ne_bytes());
+            }
+            Value::Array(array) => {
+                let start = out.len();
+                out.extend_from_slice(&[0; 4]);
+                out.extend_from_slice(&(array.child_type as u32).to_ne_bytes());
+
+                let child_size = encode_children(array.child_type, &array.values, out)?;
+                out[start..start + 4].copy_from_slice(&child_size.to_ne_bytes());
+            }
+            Value::Struct(values) => {
+                for value in values {
+                    value.write(out)?;
+                }
+            }
+            Value::Object(object) => {
+                out.extend_from_slice(&object.type_.to_ne_bytes());
+                out.extend_from_slice(&object.id.to_ne_bytes());
+
+                for prop in &object.properties {
+                    out.extend_from_slice(&prop.key.to_ne_bytes());
+                    out.extend_from_slice(&prop.flags.bits().to_ne_bytes());
+                    prop.value.write(out)?;
+                }
+            }
+            Value::Sequence(sequence) => {
+                out.extend_from_slice(&sequence.unit.to_ne_bytes());
+                out.extend_from_slice(&[0; 4]);
+
+                for control in &sequence.controls {
+                    out.extend_from_slice(&control.offset.to_ne_bytes());
+                    out.extend_from_slice(&(control.type_ as u32).to_ne_bytes());
+                    control.value.write(out)?;
+                }
+            }
+            Value::Pointer(pointer) => {
+                out.extend_from_slice(&(pointer.type_ as u32).to_ne_bytes());
+                out.extend_from_slice(&[0; 4]);
+                if std::mem::size_of::<*const c_void>() == 8 {
+                    out.extend_from_slice(&(pointer.ptr as u64).to_ne_bytes());
+                } else {
+                    out.extend_from_slice(&(pointer.ptr as u32).to_ne_bytes());
+                }
+            }
+            Value::Fd(fd) => out.extend_from_slice(&(fd.0 as i64).to_ne_bytes()),
+            Value::Choice(choice) => {
+                out.extend_from_slice(&(choice.type_ as u32).to_ne_bytes());
+                out.extend_from_slice(&choice.flags.to_ne_bytes());
+
+                let start = out.len();
+                out.extend_from_slice(&[0; 4]);
+                out.extend_from_slice(&(choice.child_type as u32).to_ne_bytes());
+
+                let child_size = encode_children(choice.child_type, &choice.values, out)?;
+                out[start..start + 4].copy_from_slice(&child_size.to_ne_bytes());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Pod for Value {
+    type DecodesTo = Value;
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, Error> {
+        let bytes = self.to_bytes()?;
+
+        if data.len() < bytes.len() {
+            return Err(Error::NoSpace);
+        }
+
+        data[0..bytes.len()].copy_from_slice(&bytes);
+
+        Ok(bytes.len())
+    }
+
+    fn decode(data: &[u8]) -> Result<(Value, usize), Error> {
+        decode_pod(data, 0)
+    }
+}
@@ -0,0 +1,535 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use pipewire_native_spa::param::audio::dsd::{AudioInfoDsd, BitOrder};
+use pipewire_native_spa::param::audio::encoded::{
+    AacStreamFormat, AmrBandMode, AudioInfoAac, AudioInfoAlac, AudioInfoAmr, AudioInfoApe,
+    AudioInfoFlac, AudioInfoMp3, AudioInfoOpus, AudioInfoRa, AudioInfoVorbis, AudioInfoWma,
+    WmaProfile,
+};
+use pipewire_native_spa::param::audio::iec958::{self, AudioInfoIec958, Iec958Codec};
+use pipewire_native_spa::param::audio::raw::{AudioChannel, AudioFlags, AudioFormat, AudioInfoRaw};
+use pipewire_native_spa::param::format::Format;
+use pipewire_native_spa::param::props::Prop;
+use pipewire_native_spa::param::ParamType;
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::object::ObjectRef;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::{Id, ObjectType, PropertyFlags};
+use pipewire_native_spa::pod::RawPod;
+
+use libspa::pod as spa_pod;
+use libspa::sys::{self as spa_sys};
+
+mod common;
+use common::{assert_dump, assert_rejects, round_trip};
+
+fn stereo() -> AudioInfoRaw {
+    AudioInfoRaw {
+        format: AudioFormat::F32P,
+        flags: AudioFlags::empty(),
+        rate: 48000,
+        channels: 2,
+        position: vec![AudioChannel::FL, AudioChannel::FR],
+    }
+}
+
+#[test]
+fn test_audio_format() {
+    assert_eq!(AudioFormat::S16LE as u32, 0x103);
+    assert_eq!(AudioFormat::F32LE as u32, 0x11b);
+    assert_eq!(AudioFormat::F32P as u32, 0x206);
+    assert_eq!(AudioChannel::FL as u32, 3);
+    assert_eq!(AudioChannel::BRC as u32, 37);
+    assert_eq!(AudioChannel::Aux0 as u32, 0x1000);
+    assert_eq!(AudioChannel::Aux63 as u32, 0x103f);
+
+    #[cfg(target_endian = "little")]
+    assert_eq!(AudioFormat::F32, AudioFormat::F32LE);
+    assert_eq!(AudioFormat::DSP_F32, AudioFormat::F32P);
+
+    assert_eq!(AudioFormat::S16LE.sample_size(), Some(2));
+    assert_eq!(AudioFormat::S24BE.sample_size(), Some(3));
+    assert_eq!(AudioFormat::S24_32P.sample_size(), Some(4));
+    assert_eq!(AudioFormat::F64LE.sample_size(), Some(8));
+    assert_eq!(AudioFormat::Ulaw.sample_size(), Some(1));
+    assert_eq!(AudioFormat::Encoded.sample_size(), None);
+
+    assert!(AudioFormat::S16LE.is_interleaved());
+    assert!(!AudioFormat::S16LE.is_planar());
+    assert!(AudioFormat::S8P.is_planar());
+    assert!(!AudioFormat::Unknown.is_planar() && !AudioFormat::Unknown.is_interleaved());
+
+    let info = stereo();
+    assert_eq!(info.frame_size(), Some(4));
+    assert_eq!(info.n_planes(), 2);
+
+    let info = AudioInfoRaw {
+        format: AudioFormat::S16LE,
+        channels: 6,
+        ..AudioInfoRaw::default()
+    };
+    assert_eq!(info.frame_size(), Some(12));
+    assert_eq!(info.n_planes(), 1);
+}
+
+#[test]
+fn test_audio_info_raw() {
+    let raw = round_trip(&stereo(), AudioInfoRaw::build_format, AudioInfoRaw::parse);
+    assert_dump(
+        &raw,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Raw (1)",
+            "  AudioFormat: Id F32P (518)",
+            "  AudioRate: Int 48000",
+            "  AudioChannels: Int 2",
+            "  AudioPosition: Array of Id: [FL (3), FR (4)]",
+        ],
+    );
+
+    // Without positions, the format is unpositioned
+    let info = AudioInfoRaw {
+        format: AudioFormat::S16LE,
+        flags: AudioFlags::UNPOSITIONED,
+        rate: 44100,
+        channels: 2,
+        position: vec![],
+    };
+    round_trip(&info, AudioInfoRaw::build_enum_format, AudioInfoRaw::parse);
+
+    // Positions without a name are unknown, and negative rates are invalid
+    let raw_format = |rate: i32, position: &[Id<u32>]| {
+        let mut buf = [0u8; 1024];
+        Builder::new(&mut buf)
+            .push_object(ObjectType::Format, ParamType::Format, |b| {
+                b.push_property(Format::AudioRate, PropertyFlags::empty(), rate)
+                    .push_property(Format::AudioChannels, PropertyFlags::empty(), 3i32)
+                    .push_property(Format::AudioPosition, PropertyFlags::empty(), position)
+            })
+            .build()
+            .unwrap()
+            .to_vec()
+    };
+    let pod = raw_format(
+        48000,
+        &[Id(AudioChannel::FL as u32), Id(0x1040), Id(0x10000)],
+    );
+    assert_eq!(
+        AudioInfoRaw::parse(&mut Parser::new(&pod))
+            .unwrap()
+            .position,
+        vec![
+            AudioChannel::FL,
+            AudioChannel::Unknown,
+            AudioChannel::Unknown
+        ]
+    );
+    let pod = raw_format(-1, &[]);
+    assert!(AudioInfoRaw::parse(&mut Parser::new(&pod)).is_err());
+
+    // Not a raw audio format
+    let mp3 = AudioInfoMp3 {
+        rate: 44100,
+        channels: 2,
+    };
+    let other = round_trip(&mp3, AudioInfoMp3::build_format, AudioInfoMp3::parse);
+    assert_rejects(AudioInfoRaw::parse, &raw, &[&other]);
+}
+
+#[test]
+fn test_audio_info_raw_libspa() {
+    let info = stereo();
+
+    // Parsed by libspa
+    let mut buf = [0u8; 1024];
+    let pod = info.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_raw = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_raw_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!(raw.format, AudioFormat::F32P as u32);
+    assert_eq!(raw.flags, 0);
+    assert_eq!(raw.rate, 48000);
+    assert_eq!(raw.channels, 2);
+    assert_eq!(
+        raw.position[..2],
+        [AudioChannel::FL as u32, AudioChannel::FR as u32]
+    );
+
+    // Built by libspa
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_raw_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+
+    assert_eq!(sbuf, pod);
+    assert_eq!(AudioInfoRaw::parse(&mut Parser::new(&sbuf)).unwrap(), info);
+}
+
+#[test]
+fn test_audio_encoded() {
+    let aac = AudioInfoAac {
+        rate: 44100,
+        channels: 2,
+        bitrate: 128000,
+        stream_format: AacStreamFormat::Mp4Adts,
+    };
+    let pod = round_trip(&aac, AudioInfoAac::build_format, AudioInfoAac::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Aac (65538)",
+            "  AudioFormat: Id Encoded (1)",
+            "  AudioRate: Int 44100",
+            "  AudioChannels: Int 2",
+            "  AudioBitrate: Int 128000",
+            "  AudioAacStreamFormat: Id Mp4Adts (3)",
+        ],
+    );
+
+    let wma = AudioInfoWma {
+        rate: 48000,
+        channels: 2,
+        bitrate: 192000,
+        block_align: 8192,
+        profile: WmaProfile::Wma9Pro,
+    };
+    round_trip(&wma, AudioInfoWma::build_enum_format, AudioInfoWma::parse);
+
+    let amr = AudioInfoAmr {
+        rate: 16000,
+        channels: 1,
+        band_mode: AmrBandMode::Wb,
+    };
+    round_trip(&amr, AudioInfoAmr::build_format, AudioInfoAmr::parse);
+
+    // Unset fields are left out
+    let mp3 = AudioInfoMp3 {
+        rate: 44100,
+        channels: 0,
+    };
+    let mp3_pod = round_trip(&mp3, AudioInfoMp3::build_format, AudioInfoMp3::parse);
+    assert_dump(
+        &mp3_pod,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Mp3 (65537)",
+            "  AudioFormat: Id Encoded (1)",
+            "  AudioRate: Int 44100",
+        ],
+    );
+
+    // A format of another subtype is rejected
+    assert_rejects(AudioInfoMp3::parse, &mp3_pod, &[&pod]);
+
+    macro_rules! round_trip {
+        ($($info:ident),*) => {
+            $(
+                let info = $info {
+                    rate: 48000,
+                    channels: 2,
+                };
+                round_trip(&info, $info::build_format, $info::parse);
+            )*
+        };
+    }
+
+    round_trip!(
+        AudioInfoOpus,
+        AudioInfoFlac,
+        AudioInfoVorbis,
+        AudioInfoAlac,
+        AudioInfoApe,
+        AudioInfoRa
+    );
+}
+
+#[test]
+fn test_audio_encoded_libspa() {
+    let aac = AudioInfoAac {
+        rate: 44100,
+        channels: 2,
+        bitrate: 128000,
+        stream_format: AacStreamFormat::Raw,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = aac.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_aac = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_aac_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!(
+        (raw.rate, raw.channels, raw.bitrate, raw.stream_format),
+        (44100, 2, 128000, AacStreamFormat::Raw as u32)
+    );
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_aac_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+
+    let wma = AudioInfoWma {
+        rate: 48000,
+        channels: 2,
+        bitrate: 192000,
+        block_align: 8192,
+        profile: WmaProfile::Wma10,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = wma.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_wma = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_wma_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!(
+        (
+            raw.rate,
+            raw.channels,
+            raw.bitrate,
+            raw.block_align,
+            raw.profile
+        ),
+        (48000, 2, 192000, 8192, WmaProfile::Wma10 as u32)
+    );
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_wma_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+
+    let amr = AudioInfoAmr {
+        rate: 8000,
+        channels: 1,
+        band_mode: AmrBandMode::Nb,
+    };
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let raw = spa_sys::spa_audio_info_amr {
+        rate: amr.rate,
+        channels: amr.channels,
+        band_mode: amr.band_mode as u32,
+    };
+    let spod = unsafe {
+        spa_sys::spa_format_audio_amr_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(AudioInfoAmr::parse(&mut Parser::new(&sbuf)).unwrap(), amr);
+
+    let opus = AudioInfoOpus {
+        rate: 48000,
+        channels: 2,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = opus.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_opus = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_opus_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!((raw.rate, raw.channels), (48000, 2));
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_opus_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+}
+
+#[test]
+fn test_audio_iec958() {
+    let info = AudioInfoIec958 {
+        codec: Iec958Codec::Ac3,
+        flags: 0,
+        rate: 48000,
+    };
+
+    let pod = round_trip(&info, AudioInfoIec958::build_format, AudioInfoIec958::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Iec958 (3)",
+            "  AudioIec958Codec: Id Ac3 (3)",
+            "  AudioRate: Int 48000",
+        ],
+    );
+
+    // The codecs a device can pass through
+    let codecs = [Iec958Codec::Pcm, Iec958Codec::Ac3, Iec958Codec::Eac3];
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            let b = b.push_property(Prop::Mute, PropertyFlags::empty(), false);
+            iec958::push_codecs_property(b, &codecs)
+        })
+        .build()
+        .unwrap();
+    assert_eq!(
+        iec958::parse_codecs(&mut Parser::new(pod)).unwrap(),
+        Some(codecs.to_vec())
+    );
+
+    let object = ObjectRef::wrap(pod).unwrap();
+    assert_eq!(
+        iec958::decode_codecs_property(object.get(Prop::Iec958Codecs).unwrap()).unwrap(),
+        codecs
+    );
+    assert_eq!(
+        RawPod::wrap(pod)
+            .unwrap()
+            .to_string()
+            .lines()
+            .last()
+            .unwrap(),
+        "  Iec958Codecs: Array of Id: [Pcm (1), Ac3 (3), Eac3 (6)]"
+    );
+
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Mute, PropertyFlags::empty(), false)
+        })
+        .build()
+        .unwrap();
+    assert_eq!(iec958::parse_codecs(&mut Parser::new(pod)).unwrap(), None);
+}
+
+#[test]
+fn test_audio_dsd() {
+    let info = AudioInfoDsd {
+        bitorder: BitOrder::Lsb,
+        flags: AudioFlags::empty(),
+        interleave: 4,
+        rate: 352800,
+        channels: 2,
+        position: vec![AudioChannel::FL, AudioChannel::FR],
+    };
+    let pod = round_trip(&info, AudioInfoDsd::build_format, AudioInfoDsd::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Dsd (4)",
+            "  AudioBitorder: Id Lsb (2)",
+            "  AudioInterleave: Int 4",
+            "  AudioRate: Int 352800",
+            "  AudioChannels: Int 2",
+            "  AudioPosition: Array of Id: [FL (3), FR (4)]",
+        ],
+    );
+
+    // Reversed bytes, and no positions
+    let info = AudioInfoDsd {
+        interleave: -4,
+        flags: AudioFlags::UNPOSITIONED,
+        position: vec![],
+        ..info
+    };
+    round_trip(&info, AudioInfoDsd::build_enum_format, AudioInfoDsd::parse);
+
+    let iec958 = AudioInfoIec958 {
+        codec: Iec958Codec::Ac3,
+        flags: 0,
+        rate: 48000,
+    };
+    let other = round_trip(
+        &iec958,
+        AudioInfoIec958::build_format,
+        AudioInfoIec958::parse,
+    );
+    assert_rejects(AudioInfoDsd::parse, &pod, &[&other]);
+    assert_rejects(AudioInfoIec958::parse, &other, &[&pod]);
+}
+
+#[test]
+fn test_audio_iec958_dsd_libspa() {
+    let info = AudioInfoIec958 {
+        codec: Iec958Codec::Dts,
+        flags: 0,
+        rate: 44100,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = info.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_iec958 = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_iec958_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!((raw.codec, raw.rate), (Iec958Codec::Dts as u32, 44100));
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_iec958_build(
+            sbuilder.as_raw_ptr(),
+            ParamType::Format as u32,
+            &raw,
+        )
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+
+    let info = AudioInfoDsd {
+        bitorder: BitOrder::Msb,
+        flags: AudioFlags::empty(),
+        interleave: 4,
+        rate: 352800,
+        channels: 2,
+        position: vec![AudioChannel::FL, AudioChannel::FR],
+    };
+    let mut buf = [0u8; 1024];
+    let pod = info.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_audio_info_dsd = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_audio_dsd_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!(raw.bitorder, BitOrder::Msb as u32);
+    assert_eq!((raw.interleave, raw.rate, raw.channels), (4, 352800, 2));
+    assert_eq!(
+        raw.position[..2],
+        [AudioChannel::FL as u32, AudioChannel::FR as u32]
+    );
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_audio_dsd_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+// Checks shared by the tests of typed params, which all build a param, look at the pod, parse it
+// back, and make sure other pods are not mistaken for it.
+
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::ObjectType;
+use pipewire_native_spa::pod::{Error, RawPod};
+
+// Build a param and parse it back, which must give the same value. Returns the pod.
+pub fn round_trip<T, B, P>(value: &T, build: B, parse: P) -> Vec<u8>
+where
+    T: Debug + PartialEq,
+    B: for<'a> FnOnce(&T, Builder<'a>) -> Builder<'a>,
+    P: FnOnce(&mut Parser) -> Result<T, Error>,
+{
+    let mut buf = [0u8; 4096];
+    let pod = build(value, Builder::new(&mut buf))
+        .build()
+        .unwrap()
+        .to_vec();
+
+    assert_eq!(parse(&mut Parser::new(&pod)).unwrap(), *value);
+    pod
+}
+
+// Compare a pod with the lines of its expected Display output, which shows the types, keys and
+// flags
+pub fn assert_dump(pod: &[u8], expected: &[&str]) {
+    assert_eq!(RawPod::wrap(pod).unwrap().to_string(), expected.join("\n"));
+}
+
+// Parsing must fail on the given pods of other params, and on a valid pod that has the wrong
+// object type, is cut short, or is not an object at all
+pub fn assert_rejects<T, P>(parse: P, pod: &[u8], others: &[&[u8]])
+where
+    T: Debug,
+    P: Fn(&mut Parser) -> Result<T, Error>,
+{
+    for other in others {
+        assert!(parse(&mut Parser::new(other)).is_err());
+    }
+
+    let mut wrong_type = pod.to_vec();
+    let object_type = u32::from_ne_bytes(pod[8..12].try_into().unwrap());
+    let other_type = if object_type == ObjectType::Props as u32 {
+        ObjectType::Format
+    } else {
+        ObjectType::Props
+    };
+    wrong_type[8..12].copy_from_slice(&(other_type as u32).to_ne_bytes());
+    assert!(parse(&mut Parser::new(&wrong_type)).is_err());
+
+    assert!(parse(&mut Parser::new(&pod[..pod.len() - 8])).is_err());
+
+    let mut buf = [0u8; 64];
+    let int = Builder::new(&mut buf).push_int(1).build().unwrap();
+    assert!(parse(&mut Parser::new(int)).is_err());
+}
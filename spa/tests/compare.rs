@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use std::cmp::Ordering;
+
+use pipewire_native_spa::param::format::Format;
+use pipewire_native_spa::param::ParamType;
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::compare::{compare, compare_values};
+use pipewire_native_spa::pod::types::{
+    Choice, ChoiceType, Fraction, ObjectType, PropertyFlags, Rectangle, Type,
+};
+use pipewire_native_spa::pod::value::{ChoiceValue, Value};
+
+use libspa::sys::{self as spa_sys};
+
+fn rate_object(rate: Option<Choice<i32>>, channels: Option<i32>) -> Vec<u8> {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    builder
+        .push_object(ObjectType::Format, ParamType::Format, |b| {
+            let b = match rate {
+                Some(rate) => b.push_property(Format::AudioRate, PropertyFlags::empty(), rate),
+                None => b,
+            };
+            match channels {
+                Some(channels) => {
+                    b.push_property(Format::AudioChannels, PropertyFlags::empty(), channels)
+                }
+                None => b,
+            }
+        })
+        .build()
+        .unwrap()
+        .to_vec()
+}
+
+// Pairs of pods along with their expected ordering
+fn compare_cases() -> Vec<(Vec<u8>, Vec<u8>, Ordering)> {
+    let value = |v: Value| v.to_bytes().unwrap();
+    let rect = |width, height| Value::Rectangle(Rectangle { width, height });
+    let frac = |num, denom| Value::Fraction(Fraction { num, denom });
+
+    vec![
+        (value(Value::Int(1)), value(Value::Int(2)), Ordering::Less),
+        (
+            value(Value::Long(3)),
+            value(Value::Long(3)),
+            Ordering::Equal,
+        ),
+        (
+            value(Value::Double(2.0)),
+            value(Value::Double(-1.0)),
+            Ordering::Greater,
+        ),
+        (
+            value(Value::String("abc".to_string())),
+            value(Value::String("abd".to_string())),
+            Ordering::Less,
+        ),
+        // Larger areas order after smaller ones
+        (
+            value(rect(640, 480)),
+            value(rect(320, 240)),
+            Ordering::Greater,
+        ),
+        (value(rect(320, 480)), value(rect(480, 320)), Ordering::Less),
+        (value(frac(30, 1)), value(frac(60, 2)), Ordering::Equal),
+        (value(frac(25, 1)), value(frac(30000, 1001)), Ordering::Less),
+        // Choices are compared by their default
+        (
+            value(Value::Choice(ChoiceValue {
+                type_: ChoiceType::None,
+                flags: 0,
+                child_type: Type::Int,
+                values: vec![Value::Int(5)],
+            })),
+            value(Value::Int(5)),
+            Ordering::Equal,
+        ),
+        (
+            rate_object(
+                Some(Choice::Range {
+                    default: 44100,
+                    min: 1,
+                    max: 48000,
+                }),
+                Some(2),
+            ),
+            rate_object(
+                Some(Choice::Range {
+                    default: 48000,
+                    min: 8000,
+                    max: 96000,
+                }),
+                Some(2),
+            ),
+            Ordering::Less,
+        ),
+        // Objects with extra properties order after those without
+        (
+            rate_object(Some(Choice::None(48000)), Some(2)),
+            rate_object(Some(Choice::None(48000)), None),
+            Ordering::Greater,
+        ),
+        (
+            rate_object(None, Some(2)),
+            rate_object(Some(Choice::None(48000)), Some(2)),
+            Ordering::Less,
+        ),
+        (
+            rate_object(Some(Choice::None(48000)), Some(2)),
+            rate_object(Some(Choice::None(48000)), Some(2)),
+            Ordering::Equal,
+        ),
+    ]
+}
+
+#[test]
+fn test_compare() {
+    for (a, b, expected) in compare_cases() {
+        assert_eq!(compare(&a, &b).unwrap(), expected);
+        assert_eq!(compare(&b, &a).unwrap(), expected.reverse());
+    }
+
+    // Structs are compared element-wise
+    let a = Value::Struct(vec![Value::Int(1), Value::String("a".to_string())]);
+    let b = Value::Struct(vec![Value::Int(1), Value::String("b".to_string())]);
+    assert_eq!(compare_values(&a, &a).unwrap(), Ordering::Equal);
+    assert_eq!(compare_values(&a, &b).unwrap(), Ordering::Less);
+
+    // Different types, sizes or number of choice values can't be compared
+    assert!(compare_values(&Value::Int(1), &Value::Long(1)).is_err());
+    assert!(compare_values(
+        &Value::String("a".to_string()),
+        &Value::String("ab".to_string())
+    )
+    .is_err());
+    assert!(compare_values(&a, &Value::Struct(vec![Value::Int(1)])).is_err());
+    assert!(compare_values(
+        &Value::Choice(ChoiceValue {
+            type_: ChoiceType::Enum,
+            flags: 0,
+            child_type: Type::Int,
+            values: vec![Value::Int(1), Value::Int(1), Value::Int(2)],
+        }),
+        &Value::Int(1)
+    )
+    .is_err());
+}
+
+#[test]
+fn test_compare_libspa() {
+    for (a, b, expected) in compare_cases() {
+        let res = unsafe {
+            spa_sys::spa_pod_compare(
+                a.as_ptr() as *const spa_sys::spa_pod,
+                b.as_ptr() as *const spa_sys::spa_pod,
+            )
+        };
+
+        assert_eq!(res.cmp(&0), expected);
+    }
+}
@@ -0,0 +1,492 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use pipewire_native_spa::param::format::{Format, MediaSubtype, MediaType};
+use pipewire_native_spa::param::ParamType;
+use pipewire_native_spa::pod::builder::{Builder, ObjectBuilder};
+use pipewire_native_spa::pod::filter::{filter, fixate};
+use pipewire_native_spa::pod::types::{
+    Choice, ChoiceType, Fraction, Id, ObjectType, PropertyFlags, Rectangle, Type,
+};
+use pipewire_native_spa::pod::value::{ChoiceValue, Value};
+use pipewire_native_spa::pod::Pod;
+
+use libspa::pod as spa_pod;
+use libspa::sys::{self as spa_sys};
+
+fn raw_format<F>(media_type: MediaType, id: ParamType, build: F) -> Vec<u8>
+where
+    F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+{
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    builder
+        .push_object(ObjectType::Format, id, |b| {
+            build(
+                b.push_property(Format::MediaType, PropertyFlags::empty(), Id(media_type))
+                    .push_property(
+                        Format::MediaSubtype,
+                        PropertyFlags::empty(),
+                        Id(MediaSubtype::Raw),
+                    ),
+            )
+        })
+        .build()
+        .unwrap()
+        .to_vec()
+}
+
+fn audio_format<F>(id: ParamType, build: F) -> Vec<u8>
+where
+    F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+{
+    raw_format(MediaType::Audio, id, build)
+}
+
+fn video_format<F>(id: ParamType, build: F) -> Vec<u8>
+where
+    F: FnOnce(ObjectBuilder) -> ObjectBuilder,
+{
+    raw_format(MediaType::Video, id, build)
+}
+
+fn choice(type_: ChoiceType, child_type: Type, values: Vec<Value>) -> Value {
+    Value::Choice(ChoiceValue {
+        type_,
+        flags: 0,
+        child_type,
+        values,
+    })
+}
+
+fn filtered_property(pod: &[u8], key: Format) -> Value {
+    let (value, _) = Value::decode(pod).unwrap();
+    let Value::Object(object) = value else {
+        panic!("Expected an object, got {:?}", value);
+    };
+
+    object.property(key).unwrap().value.clone()
+}
+
+// Pairs of (pod, filter) that are run through both libspa and our implementation
+fn filter_cases() -> Vec<(Vec<u8>, Vec<u8>)> {
+    vec![
+        // Enums and ranges against enums and fixed values
+        (
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioFormat,
+                    PropertyFlags::empty(),
+                    Choice::Enum {
+                        default: Id(259u32),
+                        alternatives: vec![Id(259u32), Id(283u32)],
+                    },
+                )
+                .push_property(
+                    Format::AudioRate,
+                    PropertyFlags::empty(),
+                    Choice::Range {
+                        default: 48000i32,
+                        min: 1,
+                        max: 384000,
+                    },
+                )
+                .push_property(
+                    Format::AudioChannels,
+                    PropertyFlags::empty(),
+                    Choice::Enum {
+                        default: 2i32,
+                        alternatives: vec![1, 2],
+                    },
+                )
+            }),
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(Format::AudioFormat, PropertyFlags::empty(), Id(283u32))
+                    .push_property(
+                        Format::AudioRate,
+                        PropertyFlags::empty(),
+                        Choice::Enum {
+                            default: 44100i32,
+                            alternatives: vec![44100, 48000],
+                        },
+                    )
+                    .push_property(Format::AudioChannels, PropertyFlags::empty(), 2i32)
+            }),
+        ),
+        // Ranges against ranges and steps, plus a property only the filter has
+        (
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioRate,
+                    PropertyFlags::empty(),
+                    Choice::Range {
+                        default: 48000i32,
+                        min: 1,
+                        max: 384000,
+                    },
+                )
+                .push_property(
+                    Format::AudioChannels,
+                    PropertyFlags::empty(),
+                    64i32,
+                )
+            }),
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioRate,
+                    PropertyFlags::empty(),
+                    Choice::Range {
+                        default: 44100i32,
+                        min: 8000,
+                        max: 44100,
+                    },
+                )
+                .push_property(
+                    Format::AudioChannels,
+                    PropertyFlags::empty(),
+                    Choice::Step {
+                        default: 32i32,
+                        min: 32,
+                        max: 1024,
+                        step: 32,
+                    },
+                )
+                .push_property(Format::AudioFlags, PropertyFlags::empty(), 1i32)
+            }),
+        ),
+        // Steps against enums
+        (
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioChannels,
+                    PropertyFlags::empty(),
+                    Choice::Step {
+                        default: 2i32,
+                        min: 2,
+                        max: 8,
+                        step: 2,
+                    },
+                )
+            }),
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioChannels,
+                    PropertyFlags::empty(),
+                    Choice::Enum {
+                        default: 3i32,
+                        alternatives: vec![3, 4, 6, 10],
+                    },
+                )
+            }),
+        ),
+        // Flags
+        (
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::AudioFlags,
+                    PropertyFlags::empty(),
+                    Choice::Flags {
+                        default: 3i32,
+                        flags: 7,
+                    },
+                )
+            }),
+            audio_format(ParamType::EnumFormat, |b| {
+                b.push_property(Format::AudioFlags, PropertyFlags::empty(), 6i32)
+            }),
+        ),
+        // Rectangles and fractions
+        (
+            video_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::VideoSize,
+                    PropertyFlags::empty(),
+                    Choice::Range {
+                        default: Rectangle {
+                            width: 640,
+                            height: 480,
+                        },
+                        min: Rectangle {
+                            width: 1,
+                            height: 1,
+                        },
+                        max: Rectangle {
+                            width: 4096,
+                            height: 4096,
+                        },
+                    },
+                )
+                .push_property(
+                    Format::VideoFramerate,
+                    PropertyFlags::empty(),
+                    Choice::Range {
+                        default: Fraction { num: 30, denom: 1 },
+                        min: Fraction { num: 0, denom: 1 },
+                        max: Fraction { num: 120, denom: 1 },
+                    },
+                )
+            }),
+            video_format(ParamType::EnumFormat, |b| {
+                b.push_property(
+                    Format::VideoSize,
+                    PropertyFlags::empty(),
+                    Choice::Enum {
+                        default: Rectangle {
+                            width: 1920,
+                            height: 1080,
+                        },
+                        alternatives: vec![
+                            Rectangle {
+                                width: 1920,
+                                height: 1080,
+                            },
+                            Rectangle {
+                                width: 8192,
+                                height: 8192,
+                            },
+                        ],
+                    },
+                )
+                .push_property(
+                    Format::VideoFramerate,
+                    PropertyFlags::empty(),
+                    Fraction { num: 60, denom: 1 },
+                )
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn test_filter() {
+    let cases = filter_cases();
+
+    let res = filter(&cases[0].0, &cases[0].1).unwrap();
+    assert_eq!(
+        filtered_property(&res, Format::MediaType),
+        choice(
+            ChoiceType::None,
+            Type::Id,
+            vec![Value::Id(MediaType::Audio as u32)]
+        )
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioFormat),
+        choice(
+            ChoiceType::Enum,
+            Type::Id,
+            vec![Value::Id(283), Value::Id(283)]
+        )
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioRate),
+        choice(
+            ChoiceType::Enum,
+            Type::Int,
+            vec![Value::Int(48000), Value::Int(44100), Value::Int(48000)]
+        )
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioChannels),
+        choice(
+            ChoiceType::Enum,
+            Type::Int,
+            vec![Value::Int(2), Value::Int(2)]
+        )
+    );
+
+    let res = filter(&cases[1].0, &cases[1].1).unwrap();
+    assert_eq!(
+        filtered_property(&res, Format::AudioRate),
+        choice(
+            ChoiceType::Range,
+            Type::Int,
+            vec![Value::Int(44100), Value::Int(8000), Value::Int(44100)]
+        )
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioChannels),
+        choice(
+            ChoiceType::Enum,
+            Type::Int,
+            vec![Value::Int(64), Value::Int(64)]
+        )
+    );
+    assert_eq!(filtered_property(&res, Format::AudioFlags), Value::Int(1));
+
+    // Only the values on the step are kept, and the default moves to one of them
+    let res = filter(&cases[2].0, &cases[2].1).unwrap();
+    assert_eq!(
+        filtered_property(&res, Format::AudioChannels),
+        choice(
+            ChoiceType::Enum,
+            Type::Int,
+            vec![Value::Int(4), Value::Int(4), Value::Int(6)]
+        )
+    );
+
+    let res = filter(&cases[3].0, &cases[3].1).unwrap();
+    assert_eq!(
+        filtered_property(&res, Format::AudioFlags),
+        choice(
+            ChoiceType::Flags,
+            Type::Int,
+            vec![Value::Int(2), Value::Int(2)]
+        )
+    );
+
+    let res = filter(&cases[4].0, &cases[4].1).unwrap();
+    assert_eq!(
+        filtered_property(&res, Format::MediaType),
+        choice(
+            ChoiceType::None,
+            Type::Id,
+            vec![Value::Id(MediaType::Video as u32)]
+        )
+    );
+    let size = Value::Rectangle(Rectangle {
+        width: 1920,
+        height: 1080,
+    });
+    assert_eq!(
+        filtered_property(&res, Format::VideoSize),
+        choice(ChoiceType::Enum, Type::Rectangle, vec![size.clone(), size])
+    );
+    let framerate = Value::Fraction(Fraction { num: 60, denom: 1 });
+    assert_eq!(
+        filtered_property(&res, Format::VideoFramerate),
+        choice(
+            ChoiceType::Enum,
+            Type::Fraction,
+            vec![framerate.clone(), framerate]
+        )
+    );
+}
+
+#[test]
+fn test_filter_errors() {
+    let pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(
+            Format::AudioRate,
+            PropertyFlags::empty(),
+            Choice::Enum {
+                default: 48000i32,
+                alternatives: vec![44100, 48000],
+            },
+        )
+    });
+
+    // No common value
+    let filter_pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(Format::AudioRate, PropertyFlags::empty(), 96000i32)
+    });
+    assert!(filter(&pod, &filter_pod).is_err());
+
+    // Mismatched types
+    let filter_pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(Format::AudioRate, PropertyFlags::empty(), 48000i64)
+    });
+    assert!(filter(&pod, &filter_pod).is_err());
+
+    // Mandatory properties must be present on both sides
+    let filter_pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(Format::AudioChannels, PropertyFlags::MANDATORY, 2i32)
+    });
+    assert!(filter(&pod, &filter_pod).is_err());
+
+    let filter_pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(Format::AudioChannels, PropertyFlags::empty(), 2i32)
+    });
+    assert!(filter(&pod, &filter_pod).is_ok());
+
+    // Fixed values outside of objects must match
+    let mut buf = [0u8; 64];
+    let a = Builder::new(&mut buf).push_int(1).build().unwrap().to_vec();
+    let mut buf = [0u8; 64];
+    let b = Builder::new(&mut buf).push_int(2).build().unwrap().to_vec();
+    assert_eq!(filter(&a, &a).unwrap(), a);
+    assert!(filter(&a, &b).is_err());
+}
+
+#[test]
+fn test_fixate() {
+    let pod = audio_format(ParamType::EnumFormat, |b| {
+        b.push_property(
+            Format::AudioRate,
+            PropertyFlags::empty(),
+            Choice::Range {
+                default: 1000i32,
+                min: 8000,
+                max: 384000,
+            },
+        )
+        .push_property(
+            Format::AudioChannels,
+            PropertyFlags::empty(),
+            Choice::Enum {
+                default: 8i32,
+                alternatives: vec![1, 2],
+            },
+        )
+        .push_property(
+            Format::AudioFormat,
+            PropertyFlags::DONT_FIXATE,
+            Choice::Enum {
+                default: Id(259u32),
+                alternatives: vec![Id(259u32), Id(283u32)],
+            },
+        )
+    });
+
+    let res = fixate(&pod).unwrap();
+
+    // Defaults are brought within bounds
+    assert_eq!(
+        filtered_property(&res, Format::AudioRate),
+        choice(ChoiceType::None, Type::Int, vec![Value::Int(8000)])
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioChannels),
+        choice(ChoiceType::None, Type::Int, vec![Value::Int(1)])
+    );
+    assert_eq!(
+        filtered_property(&res, Format::AudioFormat),
+        choice(
+            ChoiceType::Enum,
+            Type::Id,
+            vec![Value::Id(259), Value::Id(259), Value::Id(283)]
+        )
+    );
+    assert_eq!(
+        filtered_property(&res, Format::MediaType),
+        Value::Id(MediaType::Audio as u32)
+    );
+
+    // Only objects can be fixated
+    let mut buf = [0u8; 64];
+    let int = Builder::new(&mut buf).push_int(1).build().unwrap().to_vec();
+    assert!(fixate(&int).is_err());
+}
+
+#[test]
+fn test_filter_libspa() {
+    for (pod, filter_pod) in filter_cases() {
+        let mut sbuf = Vec::with_capacity(1024);
+        let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+        let mut result: *mut spa_sys::spa_pod = std::ptr::null_mut();
+
+        let res = unsafe {
+            spa_sys::spa_pod_filter(
+                sbuilder.as_raw_ptr(),
+                &mut result,
+                pod.as_ptr() as *const spa_sys::spa_pod,
+                filter_pod.as_ptr() as *const spa_sys::spa_pod,
+            )
+        };
+        assert!(res >= 0);
+        drop(sbuilder);
+
+        assert_eq!(filter(&pod, &filter_pod).unwrap(), sbuf);
+    }
+}
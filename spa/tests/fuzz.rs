@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use std::ffi::c_void;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use pipewire_native_spa::param::format::Format;
+use pipewire_native_spa::pod::compare::compare;
+use pipewire_native_spa::pod::filter::{filter, fixate};
+use pipewire_native_spa::pod::json::to_json;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::{
+    ArrayRef, Bitmap, Choice, ChoiceType, ControlType, Fd, Fraction, Pointer, PropertyFlags,
+    Rectangle, Sequence, Type,
+};
+use pipewire_native_spa::pod::value::{
+    ArrayValue, ChoiceValue, ControlValue, ObjectValue, PropertyValue, SequenceValue, Value,
+};
+use pipewire_native_spa::pod::{Pod, RawPod, MAX_DEPTH, MAX_POD_SIZE};
+
+// Values are nested at most this deep, to keep test cases reasonably small
+const MAX_TEST_DEPTH: usize = 4;
+
+// A simple xorshift generator, so that test runs are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+// Types that can be array and choice children
+#[derive(Arbitrary, Clone, Copy, Debug)]
+enum ChildType {
+    Bool,
+    Id,
+    Int,
+    Long,
+    Float,
+    Double,
+    Rectangle,
+    Fraction,
+    Fd,
+}
+
+impl ChildType {
+    fn type_(self) -> Type {
+        match self {
+            ChildType::Bool => Type::Bool,
+            ChildType::Id => Type::Id,
+            ChildType::Int => Type::Int,
+            ChildType::Long => Type::Long,
+            ChildType::Float => Type::Float,
+            ChildType::Double => Type::Double,
+            ChildType::Rectangle => Type::Rectangle,
+            ChildType::Fraction => Type::Fraction,
+            ChildType::Fd => Type::Fd,
+        }
+    }
+
+    fn value(self, u: &mut Unstructured) -> arbitrary::Result<Value> {
+        Ok(match self {
+            ChildType::Bool => Value::Bool(u.arbitrary()?),
+            ChildType::Id => Value::Id(u.arbitrary()?),
+            ChildType::Int => Value::Int(u.arbitrary()?),
+            ChildType::Long => Value::Long(u.arbitrary()?),
+            ChildType::Float => Value::Float(u.arbitrary()?),
+            ChildType::Double => Value::Double(u.arbitrary()?),
+            ChildType::Rectangle => Value::Rectangle(Rectangle {
+                width: u.arbitrary()?,
+                height: u.arbitrary()?,
+            }),
+            ChildType::Fraction => Value::Fraction(Fraction {
+                num: u.arbitrary()?,
+                denom: u.arbitrary()?,
+            }),
+            ChildType::Fd => Value::Fd(Fd(u.arbitrary()?)),
+        })
+    }
+
+    fn values(self, u: &mut Unstructured) -> arbitrary::Result<Vec<Value>> {
+        let len = u.int_in_range(0..=8)?;
+        (0..len).map(|_| self.value(u)).collect()
+    }
+}
+
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+    // Only leaves once we are deep enough
+    let n_kinds = if depth >= MAX_TEST_DEPTH { 6 } else { 10 };
+
+    Ok(match u.choose_index(n_kinds)? {
+        0 => Value::None,
+        1 => u.arbitrary::<ChildType>()?.value(u)?,
+        2 => Value::String(u.arbitrary()?),
+        3 => Value::Bytes(u.arbitrary()?),
+        4 => Value::Bitmap(u.arbitrary()?),
+        5 => Value::Pointer(Pointer {
+            type_: *u.choose(&[Type::None, Type::Int, Type::Struct, Type::Object])?,
+            ptr: u.arbitrary::<usize>()? as *const c_void,
+        }),
+        6 => {
+            let child_type = u.arbitrary::<ChildType>()?;
+            Value::Array(ArrayValue {
+                child_type: child_type.type_(),
+                values: child_type.values(u)?,
+            })
+        }
+        7 => {
+            let child_type = u.arbitrary::<ChildType>()?;
+            Value::Choice(ChoiceValue {
+                type_: *u.choose(&[
+                    ChoiceType::None,
+                    ChoiceType::Range,
+                    ChoiceType::Step,
+                    ChoiceType::Enum,
+                    ChoiceType::Flags,
+                ])?,
+                flags: u.arbitrary()?,
+                child_type: child_type.type_(),
+                values: child_type.values(u)?,
+            })
+        }
+        8 => {
+            let len = u.int_in_range(0..=4)?;
+            Value::Struct(
+                (0..len)
+                    .map(|_| arbitrary_value(u, depth + 1))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        _ => {
+            if u.arbitrary()? {
+                let len = u.int_in_range(0..=4)?;
+                Value::Object(ObjectValue {
+                    type_: u.int_in_range(0x40001..=0x40010)?,
+                    id: u.int_in_range(0..=16)?,
+                    properties: (0..len)
+                        .map(|_| {
+                            Ok(PropertyValue {
+                                key: u.int_in_range(0..=0x20010)?,
+                                flags: PropertyFlags::from_bits_retain(u.arbitrary()?),
+                                value: arbitrary_value(u, depth + 1)?,
+                            })
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            } else {
+                let len = u.int_in_range(0..=4)?;
+                Value::Sequence(SequenceValue {
+                    unit: u.arbitrary()?,
+                    controls: (0..len)
+                        .map(|_| {
+                            Ok(ControlValue {
+                                offset: u.arbitrary()?,
+                                type_: *u.choose(&[
+                                    ControlType::Properties,
+                                    ControlType::Midi,
+                                    ControlType::Osc,
+                                    ControlType::Ump,
+                                ])?,
+                                value: arbitrary_value(u, depth + 1)?,
+                            })
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+        }
+    })
+}
+
+#[derive(Debug)]
+struct ArbitraryValue(Value);
+
+impl<'a> Arbitrary<'a> for ArbitraryValue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_value(u, 0).map(ArbitraryValue)
+    }
+}
+
+// Valid pods to start mutating from
+fn arbitrary_pods(rng: &mut Rng, count: usize) -> Vec<Vec<u8>> {
+    (0..count)
+        .filter_map(|_| {
+            let len = rng.below(512);
+            let data = rng.bytes(len);
+            let mut u = Unstructured::new(&data);
+
+            let ArbitraryValue(value) = u.arbitrary().ok()?;
+            Some(value.to_bytes().unwrap())
+        })
+        .collect()
+}
+
+// Corrupt a pod in one of a number of ways that are likely to hit edge cases
+fn mutate(rng: &mut Rng, pod: &[u8]) -> Vec<u8> {
+    let mut data = pod.to_vec();
+
+    for _ in 0..1 + rng.below(4) {
+        if data.is_empty() {
+            break;
+        }
+
+        match rng.below(4) {
+            // Flip a byte
+            0 => {
+                let pos = rng.below(data.len());
+                data[pos] ^= 1 << rng.below(8);
+            }
+            // Overwrite a size, type or other word with something interesting
+            1 => {
+                let pos = rng.below(data.len()) & !3;
+                let word: u32 = match rng.below(6) {
+                    0 => 0,
+                    1 => u32::MAX,
+                    2 => i32::MAX as u32,
+                    3 => MAX_POD_SIZE as u32 + 1,
+                    4 => rng.below(32) as u32,
+                    _ => rng.next() as u32,
+                };
+                let end = (pos + 4).min(data.len());
+                data[pos..end].copy_from_slice(&word.to_ne_bytes()[..end - pos]);
+            }
+            // Truncate
+            2 => data.truncate(rng.below(data.len())),
+            // Random garbage at the end
+            _ => {
+                let len = rng.below(32);
+                data.extend(rng.bytes(len));
+            }
+        }
+    }
+
+    data
+}
+
+// Feed data to every decoder, none of which may panic
+fn decode_all(data: &[u8], other: &[u8]) {
+    let _ = Value::decode(data);
+    let _ = i32::decode(data);
+    let _ = i64::decode(data);
+    let _ = f64::decode(data);
+    let _ = Rectangle::decode(data);
+    let _ = Fraction::decode(data);
+    let _ = Bitmap::decode(data);
+    let _ = <&str>::decode(data);
+    let _ = <&[u8]>::decode(data);
+    let _ = <&[i32]>::decode(data);
+    let _ = <&[Fraction]>::decode(data);
+    let _ = Choice::<i32>::decode(data);
+    let _ = Choice::<Rectangle>::decode(data);
+    let _ = Option::<i64>::decode(data);
+    let _ = Pointer::decode(data);
+    let _ = Sequence::decode(data);
+
+    if let Ok(pod) = RawPod::wrap(data) {
+        let _ = pod.to_string();
+        let _ = pod.decode::<Value>();
+        let _ = pod.decode_borrowed::<&str>();
+        let _ = pod.decode_borrowed::<&[u8]>();
+        let _ = pod.decode_borrowed::<Bitmap>();
+        let _ = pod.decode_borrowed::<ArrayRef<i32>>();
+    }
+
+    let _ = Parser::new(data).pop_struct(|p| {
+        while p.pop_value().is_ok() {}
+        Ok(())
+    });
+    let _ = Parser::new(data).pop_object::<Format, _>(|p, _| {
+        while let Some((_, _, value)) = p.pop_property::<Format>()? {
+            let _ = value.decode::<Value>();
+            let _ = value.decode::<i32>();
+        }
+        Ok(())
+    });
+
+    let _ = to_json(data);
+    let _ = fixate(data);
+    let _ = filter(data, other);
+    let _ = filter(other, data);
+    let _ = compare(data, other);
+    let _ = compare(data, data);
+}
+
+#[test]
+fn test_fuzz_round_trip() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2000 {
+        let len = rng.below(1024);
+        let data = rng.bytes(len);
+        let mut u = Unstructured::new(&data);
+
+        let Ok(ArbitraryValue(value)) = u.arbitrary() else {
+            continue;
+        };
+
+        let bytes = value.to_bytes().unwrap();
+        let (decoded, size) = Value::decode(&bytes).unwrap();
+        assert_eq!(size, bytes.len());
+        // Compare encodings rather than values, as NaN is not equal to itself
+        assert_eq!(decoded.to_bytes().unwrap(), bytes, "{value:?}");
+    }
+}
+
+#[test]
+fn test_fuzz_robustness() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let pods = arbitrary_pods(&mut rng, 300);
+
+    for pod in &pods {
+        let other = &pods[rng.below(pods.len())];
+
+        decode_all(pod, other);
+
+        for _ in 0..20 {
+            let data = mutate(&mut rng, pod);
+            decode_all(&data, other);
+            decode_all(other, &data);
+        }
+    }
+
+    // Plain garbage
+    for _ in 0..2000 {
+        let len = rng.below(64);
+        let data = rng.bytes(len);
+        decode_all(&data, &pods[0]);
+    }
+}
+
+#[test]
+fn test_fuzz_limits() {
+    let nested = |depth: usize| {
+        let mut value = Value::Int(1);
+        for _ in 0..depth {
+            value = Value::Struct(vec![value]);
+        }
+        value.to_bytes().unwrap()
+    };
+
+    // The innermost pod is at depth MAX_DEPTH
+    assert!(Value::decode(&nested(MAX_DEPTH)).is_ok());
+    assert!(Value::decode(&nested(MAX_DEPTH + 1)).is_err());
+    assert!(Value::decode(&nested(10 * MAX_DEPTH)).is_err());
+
+    // Arrays and choices whose children are arrays and choices, built by hand so that a few MB
+    // of nesting stays cheap: each level only adds its child header
+    let nested_children = |type_: Type, depth: usize| {
+        let header = |child_size: usize, child_type: Type| {
+            let mut out = Vec::new();
+            if type_ == Type::Choice {
+                out.extend_from_slice(&(ChoiceType::None as u32).to_ne_bytes());
+                out.extend_from_slice(&0u32.to_ne_bytes());
+            }
+            out.extend_from_slice(&(child_size as u32).to_ne_bytes());
+            out.extend_from_slice(&(child_type as u32).to_ne_bytes());
+            out
+        };
+        let level = header(0, type_).len();
+        let body_size = |levels: usize| level * (levels + 1) + 4;
+
+        let mut pod = Vec::with_capacity(8 + body_size(depth) + 8);
+        pod.extend_from_slice(&(body_size(depth) as u32).to_ne_bytes());
+        pod.extend_from_slice(&(type_ as u32).to_ne_bytes());
+        for i in (0..depth).rev() {
+            pod.extend(header(body_size(i), type_));
+        }
+        pod.extend(header(4, Type::Int));
+        pod.extend_from_slice(&1i32.to_ne_bytes());
+        pod.resize(pod.len().next_multiple_of(8), 0);
+        pod
+    };
+
+    for type_ in [Type::Array, Type::Choice] {
+        assert!(Value::decode(&nested_children(type_, 10)).is_ok());
+        assert!(Value::decode(&nested_children(type_, 100)).is_err());
+        // Megabytes of nesting, which would overflow the stack if the depth was not checked
+        let pod = nested_children(type_, 4 << 20 >> 3);
+        assert!(pod.len() <= MAX_POD_SIZE);
+        assert!(Value::decode(&pod).is_err());
+    }
+
+    // Sizes that don't fit in the data
+    let mut pod = Value::Bytes(vec![1, 2, 3]).to_bytes().unwrap();
+    pod[0..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+    assert!(Value::decode(&pod).is_err());
+    assert!(<&[u8]>::decode(&pod).is_err());
+    assert!(RawPod::wrap(&pod).is_err());
+
+    // An array of many small elements that would take too much memory to decode
+    let mut pod = vec![0u8; 16 + MAX_POD_SIZE - 8];
+    pod[0..4].copy_from_slice(&(MAX_POD_SIZE as u32).to_ne_bytes());
+    pod[4..8].copy_from_slice(&(Type::Array as u32).to_ne_bytes());
+    pod[8..12].copy_from_slice(&1u32.to_ne_bytes());
+    pod[12..16].copy_from_slice(&(Type::None as u32).to_ne_bytes());
+    assert!(Value::decode(&pod).is_err());
+
+    // The same, but with a size that is over the limit
+    pod[0..4].copy_from_slice(&(MAX_POD_SIZE as u32 + 8).to_ne_bytes());
+    pod.resize(16 + MAX_POD_SIZE, 0);
+    assert!(Value::decode(&pod).is_err());
+    assert!(RawPod::wrap(&pod).is_err());
+
+    // Child sizes that don't match the type
+    let mut pod = Value::Array(ArrayValue {
+        child_type: Type::Int,
+        values: vec![Value::Int(1), Value::Int(2)],
+    })
+    .to_bytes()
+    .unwrap();
+    pod[8..12].copy_from_slice(&0u32.to_ne_bytes());
+    assert!(<&[i32]>::decode(&pod).is_err());
+    pod[8..12].copy_from_slice(&u32::MAX.to_ne_bytes());
+    assert!(<&[i32]>::decode(&pod).is_err());
+}
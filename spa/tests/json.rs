@@ -0,0 +1,418 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use tinyjson::JsonValue;
+
+use pipewire_native_spa::param::audio::encoded::{AacStreamFormat, AudioInfoAac};
+use pipewire_native_spa::param::audio::format::AudioInfo;
+use pipewire_native_spa::param::audio::raw::{AudioChannel, AudioFlags, AudioFormat, AudioInfoRaw};
+use pipewire_native_spa::param::format::{Format, MediaSubtype, MediaType};
+use pipewire_native_spa::param::port_config::{ParamPortConfig, PortConfigMode};
+use pipewire_native_spa::param::props::Prop;
+use pipewire_native_spa::param::route::{ParamRoute, Route};
+use pipewire_native_spa::param::video::encoded::{H264Alignment, H264StreamFormat, VideoInfoH264};
+use pipewire_native_spa::param::{Availability, Direction, ParamType};
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::json::{from_json, to_json};
+use pipewire_native_spa::pod::types::{Choice, Fraction, Id, ObjectType, PropertyFlags, Rectangle};
+use pipewire_native_spa::pod::value::{ObjectValue, PropertyValue, Value};
+
+fn parse(json: &str) -> JsonValue {
+    json.parse().unwrap()
+}
+
+#[test]
+fn test_json_format() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let pod = builder
+        .push_object(ObjectType::Format, ParamType::EnumFormat, |b| {
+            b.push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Raw),
+            )
+            .push_property(
+                Format::AudioRate,
+                PropertyFlags::empty(),
+                Choice::Range {
+                    default: 48000i32,
+                    min: 1,
+                    max: 384000,
+                },
+            )
+            .push_property(
+                Format::AudioChannels,
+                PropertyFlags::empty(),
+                Choice::Enum {
+                    default: 2i32,
+                    alternatives: vec![1, 2],
+                },
+            )
+            .push_property(
+                Format::AudioPosition,
+                PropertyFlags::empty(),
+                &[Id(3u32), Id(4u32)][..],
+            )
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let json = to_json(&pod).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "mediaType": "audio",
+  "mediaSubtype": "raw",
+  "rate": { "default": 48000, "min": 1, "max": 384000 },
+  "channels": {
+    "default": 2,
+    "alt1": 1,
+    "alt2": 2
+  },
+  "position": [ "FL", "FR" ]
+}"#
+    );
+
+    assert_eq!(
+        from_json(&parse(&json), ParamType::EnumFormat).unwrap(),
+        pod
+    );
+}
+
+#[test]
+fn test_json_video_format() {
+    // "format" is both an audio and a video key, the media type decides which one is meant
+    let json = r#"{
+  "mediaType": "video",
+  "mediaSubtype": "raw",
+  "format": "I420",
+  "size": { "width": 1920, "height": 1080 },
+  "framerate": { "num": 30, "denom": 1 }
+}"#;
+
+    let pod = from_json(&parse(json), ParamType::Format).unwrap();
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::Format, ParamType::Format, |b| {
+            b.push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Video),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Raw),
+            )
+            .push_property(Format::VideoFormat, PropertyFlags::empty(), Id(2u32))
+            .push_property(
+                Format::VideoSize,
+                PropertyFlags::empty(),
+                Rectangle {
+                    width: 1920,
+                    height: 1080,
+                },
+            )
+            .push_property(
+                Format::VideoFramerate,
+                PropertyFlags::empty(),
+                Fraction { num: 30, denom: 1 },
+            )
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(pod, res);
+    assert_eq!(to_json(&pod).unwrap(), json);
+}
+
+#[test]
+fn test_json_encoded_format() {
+    // Ids of encoded formats have names too
+    let aac = AudioInfoAac {
+        rate: 44100,
+        channels: 2,
+        bitrate: 128000,
+        stream_format: AacStreamFormat::Mp4Latm,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = aac
+        .build_format(Builder::new(&mut buf))
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let json = to_json(&pod).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "mediaType": "audio",
+  "mediaSubtype": "aac",
+  "format": "ENCODED",
+  "rate": 44100,
+  "channels": 2,
+  "bitrate": 128000,
+  "streamFormat": "MP4LATM"
+}"#
+    );
+    assert_eq!(from_json(&parse(&json), ParamType::Format).unwrap(), pod);
+
+    let h264 = VideoInfoH264 {
+        size: Rectangle {
+            width: 1280,
+            height: 720,
+        },
+        framerate: Fraction { num: 25, denom: 1 },
+        stream_format: H264StreamFormat::ByteStream,
+        alignment: H264Alignment::Au,
+        ..Default::default()
+    };
+    let pod = h264
+        .build_format(Builder::new(&mut buf))
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let json = to_json(&pod).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "mediaType": "video",
+  "mediaSubtype": "h264",
+  "size": { "width": 1280, "height": 720 },
+  "framerate": { "num": 25, "denom": 1 },
+  "streamFormat": "bytestream",
+  "alignment": "au"
+}"#
+    );
+    assert_eq!(from_json(&parse(&json), ParamType::Format).unwrap(), pod);
+}
+
+#[test]
+fn test_json_props() {
+    let json = r#"{
+  "volume": 0.5,
+  "mute": false,
+  "channelVolumes": [ 1.0, 0.25 ],
+  "latencyOffsetNsec": 1000,
+  "params": [
+    "audio.channels",
+    2
+  ]
+}"#;
+
+    let pod = from_json(&parse(json), ParamType::Props).unwrap();
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Volume, PropertyFlags::empty(), 0.5f32)
+                .push_property(Prop::Mute, PropertyFlags::empty(), false)
+                .push_property(
+                    Prop::ChannelVolumes,
+                    PropertyFlags::empty(),
+                    &[1.0f32, 0.25][..],
+                )
+                .push_property(Prop::LatencyOffsetNsec, PropertyFlags::empty(), 1000i64)
+                .push_property(
+                    Prop::Params,
+                    PropertyFlags::empty(),
+                    Value::Struct(vec![
+                        Value::String("audio.channels".to_string()),
+                        Value::Int(2),
+                    ]),
+                )
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(pod, res);
+    assert_eq!(
+        to_json(&pod).unwrap(),
+        r#"{
+  "volume": 0.500000,
+  "mute": false,
+  "channelVolumes": [ 1.000000, 0.250000 ],
+  "latencyOffsetNsec": 1000,
+  "params": [
+    "audio.channels",
+    2
+  ]
+}"#
+    );
+
+    // Unknown keys and mistyped values are errors
+    assert!(from_json(&parse(r#"{ "volum": 1.0 }"#), ParamType::Props).is_err());
+    assert!(from_json(&parse(r#"{ "mute": "yes" }"#), ParamType::Props).is_err());
+}
+
+#[test]
+fn test_json_nested_object() {
+    let json = r#"{
+  "index": 0,
+  "props": {
+    "volume": 0.5,
+    "mute": true
+  }
+}"#;
+
+    let pod = from_json(&parse(json), ParamType::Route).unwrap();
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::ParamRoute, ParamType::Route, |b| {
+            b.push_property(Route::Index, PropertyFlags::empty(), 0i32)
+                .push_property(
+                    Route::Props,
+                    PropertyFlags::empty(),
+                    Value::Object(ObjectValue {
+                        type_: ObjectType::Props as u32,
+                        id: ParamType::Route as u32,
+                        properties: vec![
+                            PropertyValue {
+                                key: Prop::Volume as u32,
+                                flags: PropertyFlags::empty(),
+                                value: Value::Float(0.5),
+                            },
+                            PropertyValue {
+                                key: Prop::Mute as u32,
+                                flags: PropertyFlags::empty(),
+                                value: Value::Bool(true),
+                            },
+                        ],
+                    }),
+                )
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(pod, res);
+    assert_eq!(
+        to_json(&pod).unwrap(),
+        r#"{
+  "index": 0,
+  "props": {
+    "volume": 0.500000,
+    "mute": true
+  }
+}"#
+    );
+}
+
+#[test]
+fn test_json_route() {
+    let route = ParamRoute {
+        index: 1,
+        direction: Direction::Input,
+        name: "analog-input-mic".to_string(),
+        priority: 8700,
+        available: Availability::No,
+        info: vec![("port.type".to_string(), "mic".to_string())],
+        profiles: vec![2, 3],
+        props: Some(ObjectValue {
+            type_: ObjectType::Props as u32,
+            id: ParamType::Route as u32,
+            properties: vec![
+                PropertyValue {
+                    key: Prop::Volume as u32,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Float(0.25),
+                },
+                PropertyValue {
+                    key: Prop::Mute as u32,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Bool(true),
+                },
+            ],
+        }),
+        ..Default::default()
+    };
+    let mut buf = [0u8; 1024];
+    let pod = route
+        .build_enum_route(Builder::new(&mut buf))
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let json = to_json(&pod).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "index": 1,
+  "direction": "Input",
+  "name": "analog-input-mic",
+  "priority": 8700,
+  "available": "no",
+  "info": [
+    1,
+    "port.type",
+    "mic"
+  ],
+  "profiles": [ 2, 3 ],
+  "props": {
+    "volume": 0.250000,
+    "mute": true
+  },
+  "save": false
+}"#
+    );
+    assert_eq!(from_json(&parse(&json), ParamType::EnumRoute).unwrap(), pod);
+}
+
+#[test]
+fn test_json_port_config() {
+    let config = ParamPortConfig {
+        direction: Direction::Output,
+        mode: PortConfigMode::Dsp,
+        monitor: true,
+        control: false,
+        format: Some(AudioInfo::Raw(AudioInfoRaw {
+            format: AudioFormat::F32P,
+            flags: AudioFlags::empty(),
+            rate: 48000,
+            channels: 2,
+            position: vec![AudioChannel::FL, AudioChannel::FR],
+        })),
+    };
+    let mut buf = [0u8; 1024];
+    let pod = config
+        .build_port_config(Builder::new(&mut buf))
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let json = to_json(&pod).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "direction": "Output",
+  "mode": "dsp",
+  "monitor": true,
+  "control": false,
+  "format": {
+    "mediaType": "audio",
+    "mediaSubtype": "raw",
+    "format": "F32P",
+    "rate": 48000,
+    "channels": 2,
+    "position": [ "FL", "FR" ]
+  }
+}"#
+    );
+    assert_eq!(
+        from_json(&parse(&json), ParamType::PortConfig).unwrap(),
+        pod
+    );
+}
@@ -0,0 +1,905 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use pipewire_native_spa::param::audio::format::AudioInfo;
+use pipewire_native_spa::param::audio::iec958::{AudioInfoIec958, Iec958Codec};
+use pipewire_native_spa::param::audio::raw::{AudioChannel, AudioFlags, AudioFormat, AudioInfoRaw};
+use pipewire_native_spa::param::buffers::{
+    Buffers, BuffersChoices, DataType, IoType, MetaType, ParamBuffers, ParamIo, ParamMeta,
+};
+use pipewire_native_spa::param::latency::{LatencyInfo, ProcessLatencyInfo};
+use pipewire_native_spa::param::port_config::{ParamPortConfig, PortConfig, PortConfigMode};
+use pipewire_native_spa::param::profile::{ParamProfile, ProfileClass};
+use pipewire_native_spa::param::props::Prop;
+use pipewire_native_spa::param::route::{ParamRoute, Route};
+use pipewire_native_spa::param::tag::{ParamTag, Tag};
+use pipewire_native_spa::param::{Availability, Direction, ParamType};
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::object::ObjectRef;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::{Choice, ChoiceType, ObjectType, PropertyFlags};
+use pipewire_native_spa::pod::value::{ObjectValue, PropertyValue, Value};
+
+use libspa::pod as spa_pod;
+use libspa::sys::{self as spa_sys};
+use libspa::utils as spa_utils;
+
+mod common;
+use common::{assert_dump, assert_rejects, round_trip};
+
+#[test]
+fn test_param_types() {
+    assert_eq!(DataType::MEM_PTR.bits(), 1 << 1);
+    assert_eq!(DataType::DMA_BUF.bits(), 1 << 3);
+    assert_eq!(MetaType::Header as u32, 1);
+    assert_eq!(MetaType::Busy as u32, 7);
+    assert_eq!(MetaType::SyncTimeline as u32, 9);
+    assert_eq!(IoType::Buffers as u32, 1);
+    assert_eq!(IoType::Position as u32, 7);
+    assert_eq!(IoType::AsyncBuffers as u32, 10);
+}
+
+#[test]
+fn test_param_buffers() {
+    let param = ParamBuffers {
+        buffers: 8,
+        blocks: 1,
+        size: 4096,
+        stride: 8,
+        align: 16,
+        data_type: DataType::MEM_PTR | DataType::MEM_FD,
+        meta_type: 1 << MetaType::Header as u32,
+    };
+    let pod = round_trip(&param, ParamBuffers::build, ParamBuffers::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object ParamBuffers, id Buffers:",
+            "  Buffers: Int 8",
+            "  Blocks: Int 1",
+            "  Size: Int 4096",
+            "  Stride: Int 8",
+            "  Align: Int 16",
+            "  DataType: Int 6",
+            "  MetaType: Int 2",
+        ],
+    );
+
+    // Zero fields are left out
+    let param = ParamBuffers {
+        buffers: 2,
+        ..Default::default()
+    };
+    let pod = round_trip(&param, ParamBuffers::build, ParamBuffers::parse);
+    assert_eq!(ObjectRef::wrap(&pod).unwrap().len(), 1);
+}
+
+#[test]
+fn test_param_buffers_choices() {
+    let param = ParamBuffers {
+        blocks: 1,
+        stride: 4,
+        align: 16,
+        data_type: DataType::MEM_PTR,
+        ..Default::default()
+    };
+    let choices = BuffersChoices {
+        buffers: Some(Choice::Range {
+            default: 4,
+            min: 2,
+            max: 16,
+        }),
+        size: Some(Choice::Step {
+            default: 4096,
+            min: 1024,
+            max: 8192,
+            step: 1024,
+        }),
+        ..Default::default()
+    };
+    let mut buf = [0u8; 1024];
+    let pod = param
+        .build_choices(Builder::new(&mut buf), &choices)
+        .build()
+        .unwrap();
+
+    let object = ObjectRef::wrap(pod).unwrap();
+    assert_eq!(
+        object.get_as::<Choice<i32>>(Buffers::Buffers).unwrap(),
+        choices.buffers
+    );
+    assert_eq!(
+        object.get_as::<Choice<i32>>(Buffers::Size).unwrap(),
+        choices.size
+    );
+    assert_eq!(object.get_as::<i32>(Buffers::Stride).unwrap(), Some(4));
+
+    // Choices that are not fixated yet resolve to their defaults
+    assert_eq!(
+        ParamBuffers::parse(&mut Parser::new(pod)).unwrap(),
+        ParamBuffers {
+            buffers: 4,
+            size: 4096,
+            ..param
+        }
+    );
+
+    // SPA_POD_CHOICE_FLAGS_Int(mask) is a Flags choice with only one value
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::ParamBuffers, ParamType::Buffers, |b| {
+            b.push_property(
+                Buffers::DataType,
+                PropertyFlags::empty(),
+                Choice::None(0x6i32),
+            )
+        })
+        .build()
+        .unwrap();
+    let mut pod = pod.to_vec();
+    // The choice type follows the object, property and choice headers
+    pod[32..36].copy_from_slice(&(ChoiceType::Flags as u32).to_ne_bytes());
+    assert_eq!(
+        ParamBuffers::parse(&mut Parser::new(&pod))
+            .unwrap()
+            .data_type,
+        DataType::MEM_PTR | DataType::MEM_FD
+    );
+}
+
+#[test]
+fn test_param_meta_io() {
+    let meta = ParamMeta {
+        type_: MetaType::VideoCrop,
+        size: 16,
+    };
+    let meta_pod = round_trip(&meta, ParamMeta::build, ParamMeta::parse);
+    assert_dump(
+        &meta_pod,
+        &[
+            "Object ParamMeta, id Meta:",
+            "  Type: Id VideoCrop (2)",
+            "  Size: Int 16",
+        ],
+    );
+
+    let io = ParamIo {
+        id: IoType::Clock,
+        size: 160,
+    };
+    let io_pod = round_trip(&io, ParamIo::build, ParamIo::parse);
+    assert_dump(
+        &io_pod,
+        &[
+            "Object ParamIo, id IO:",
+            "  Id: Id Clock (3)",
+            "  Size: Int 160",
+        ],
+    );
+
+    // Neither is mistaken for the other
+    assert_rejects(ParamMeta::parse, &meta_pod, &[&io_pod]);
+    assert_rejects(ParamIo::parse, &io_pod, &[&meta_pod]);
+}
+
+#[test]
+fn test_param_buffers_libspa() {
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_object(
+                &mut frame,
+                spa_sys::SPA_TYPE_OBJECT_ParamBuffers,
+                spa_sys::SPA_PARAM_Buffers,
+            )
+            .unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_BUFFERS_buffers, 0)
+            .unwrap();
+        let mut choice: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_choice(&mut choice, spa_sys::SPA_CHOICE_Range, 0)
+            .unwrap();
+        sbuilder.add_int(4).unwrap();
+        sbuilder.add_int(2).unwrap();
+        sbuilder.add_int(16).unwrap();
+        sbuilder.pop(&mut choice.assume_init());
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_BUFFERS_blocks, 0)
+            .unwrap();
+        sbuilder.add_int(1).unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_BUFFERS_size, 0)
+            .unwrap();
+        sbuilder.add_int(4096).unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_BUFFERS_stride, 0)
+            .unwrap();
+        sbuilder.add_int(4).unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_BUFFERS_dataType, 0)
+            .unwrap();
+        sbuilder
+            .add_int(1 << spa_sys::SPA_DATA_MemPtr | 1 << spa_sys::SPA_DATA_DmaBuf)
+            .unwrap();
+        sbuilder.pop(&mut frame.assume_init());
+    };
+
+    let param = ParamBuffers {
+        buffers: 4,
+        blocks: 1,
+        size: 4096,
+        stride: 4,
+        align: 0,
+        data_type: DataType::MEM_PTR | DataType::DMA_BUF,
+        meta_type: 0,
+    };
+    let choices = BuffersChoices {
+        buffers: Some(Choice::Range {
+            default: 4,
+            min: 2,
+            max: 16,
+        }),
+        ..Default::default()
+    };
+    let mut buf = [0u8; 1024];
+    let pod = param
+        .build_choices(Builder::new(&mut buf), &choices)
+        .build()
+        .unwrap();
+    assert_eq!(sbuf, pod);
+    assert_eq!(ParamBuffers::parse(&mut Parser::new(&sbuf)).unwrap(), param);
+}
+
+#[test]
+fn test_param_meta_io_libspa() {
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_object(
+                &mut frame,
+                spa_sys::SPA_TYPE_OBJECT_ParamMeta,
+                spa_sys::SPA_PARAM_Meta,
+            )
+            .unwrap();
+        sbuilder.add_prop(spa_sys::SPA_PARAM_META_type, 0).unwrap();
+        sbuilder
+            .add_id(spa_utils::Id(spa_sys::SPA_META_Header))
+            .unwrap();
+        sbuilder.add_prop(spa_sys::SPA_PARAM_META_size, 0).unwrap();
+        sbuilder
+            .add_int(std::mem::size_of::<spa_sys::spa_meta_header>() as i32)
+            .unwrap();
+        sbuilder.pop(&mut frame.assume_init());
+    };
+
+    let meta = ParamMeta {
+        type_: MetaType::Header,
+        size: std::mem::size_of::<spa_sys::spa_meta_header>() as u32,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = meta.build(Builder::new(&mut buf)).build().unwrap();
+    assert_eq!(sbuf, pod);
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_object(
+                &mut frame,
+                spa_sys::SPA_TYPE_OBJECT_ParamIO,
+                spa_sys::SPA_PARAM_IO,
+            )
+            .unwrap();
+        sbuilder.add_prop(spa_sys::SPA_PARAM_IO_id, 0).unwrap();
+        sbuilder
+            .add_id(spa_utils::Id(spa_sys::SPA_IO_Buffers))
+            .unwrap();
+        sbuilder.add_prop(spa_sys::SPA_PARAM_IO_size, 0).unwrap();
+        sbuilder
+            .add_int(std::mem::size_of::<spa_sys::spa_io_buffers>() as i32)
+            .unwrap();
+        sbuilder.pop(&mut frame.assume_init());
+    };
+
+    let io = ParamIo {
+        id: IoType::Buffers,
+        size: std::mem::size_of::<spa_sys::spa_io_buffers>() as u32,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = io.build(Builder::new(&mut buf)).build().unwrap();
+    assert_eq!(sbuf, pod);
+    assert_eq!(ParamIo::parse(&mut Parser::new(&sbuf)).unwrap(), io);
+}
+
+#[test]
+fn test_param_latency() {
+    let info = LatencyInfo {
+        direction: Direction::Output,
+        min_quantum: 1.0,
+        max_quantum: 2.0,
+        min_rate: 256,
+        max_rate: 512,
+        min_ns: 1_000_000,
+        max_ns: 2_000_000,
+    };
+    let pod = round_trip(&info, LatencyInfo::build, LatencyInfo::parse);
+
+    let process = ProcessLatencyInfo {
+        quantum: 0.5,
+        rate: 64,
+        ns: 500_000,
+    };
+    let process_pod = round_trip(
+        &process,
+        ProcessLatencyInfo::build,
+        ProcessLatencyInfo::parse,
+    );
+
+    // Neither is mistaken for the other
+    assert_rejects(LatencyInfo::parse, &pod, &[&process_pod]);
+    assert_rejects(ProcessLatencyInfo::parse, &process_pod, &[&pod]);
+
+    // Rates are encoded as Int, so those that don't fit are negative and invalid
+    let mut buf = [0u8; 1024];
+    let negative = LatencyInfo {
+        max_rate: u32::MAX,
+        ..info
+    }
+    .build(Builder::new(&mut buf))
+    .build()
+    .unwrap()
+    .to_vec();
+    assert!(LatencyInfo::parse(&mut Parser::new(&negative)).is_err());
+
+    let negative = ProcessLatencyInfo {
+        rate: u32::MAX,
+        ..process
+    }
+    .build(Builder::new(&mut buf))
+    .build()
+    .unwrap()
+    .to_vec();
+    assert!(ProcessLatencyInfo::parse(&mut Parser::new(&negative)).is_err());
+}
+
+#[test]
+fn test_param_latency_combine() {
+    let a = LatencyInfo {
+        min_quantum: 1.0,
+        max_quantum: 1.0,
+        min_rate: 128,
+        max_rate: 256,
+        ..LatencyInfo::new(Direction::Input)
+    };
+    let b = LatencyInfo {
+        min_quantum: 0.5,
+        max_quantum: 2.0,
+        min_ns: 1000,
+        max_ns: 5000,
+        ..LatencyInfo::new(Direction::Input)
+    };
+
+    let mut info = LatencyInfo::combine_start(Direction::Input);
+    info.combine(&a).unwrap();
+    info.combine(&b).unwrap();
+    info.combine_finish();
+    assert_eq!(
+        info,
+        LatencyInfo {
+            direction: Direction::Input,
+            min_quantum: 0.5,
+            max_quantum: 2.0,
+            min_rate: 0,
+            max_rate: 256,
+            min_ns: 0,
+            max_ns: 5000,
+        }
+    );
+
+    // Latencies in the other direction can't be combined
+    assert!(info.combine(&LatencyInfo::new(Direction::Output)).is_err());
+
+    // Nothing combined
+    let mut empty = LatencyInfo::combine_start(Direction::Output);
+    empty.combine_finish();
+    assert_eq!(empty, LatencyInfo::new(Direction::Output));
+
+    let process = ProcessLatencyInfo {
+        quantum: 1.0,
+        rate: 64,
+        ns: 1000,
+    };
+    process.add(&mut info);
+    assert_eq!(
+        info,
+        LatencyInfo {
+            direction: Direction::Input,
+            min_quantum: 1.5,
+            max_quantum: 3.0,
+            min_rate: 64,
+            max_rate: 320,
+            min_ns: 1000,
+            max_ns: 6000,
+        }
+    );
+}
+
+#[test]
+fn test_param_latency_libspa() {
+    let info = LatencyInfo {
+        direction: Direction::Output,
+        min_quantum: 1.0,
+        max_quantum: 2.0,
+        min_rate: 256,
+        max_rate: 512,
+        min_ns: 1_000_000,
+        max_ns: 2_000_000,
+    };
+
+    // Parsed by libspa
+    let mut buf = [0u8; 1024];
+    let pod = info.build(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_latency_info = unsafe { std::mem::zeroed() };
+    let res =
+        unsafe { spa_sys::spa_latency_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw) };
+    assert!(res >= 0);
+    assert_eq!(raw.direction, spa_sys::SPA_DIRECTION_OUTPUT);
+    assert_eq!(raw.min_quantum, 1.0);
+    assert_eq!(raw.max_rate, 512);
+    assert_eq!(raw.max_ns, 2_000_000);
+
+    // Built by libspa
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_latency_build(sbuilder.as_raw_ptr(), spa_sys::SPA_PARAM_Latency, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+
+    assert_eq!(sbuf, pod);
+    assert_eq!(LatencyInfo::parse(&mut Parser::new(&sbuf)).unwrap(), info);
+
+    // Combined and added up by libspa
+    let other = LatencyInfo {
+        min_quantum: 0.5,
+        max_quantum: 4.0,
+        min_rate: 128,
+        max_rate: 128,
+        ..LatencyInfo::new(Direction::Output)
+    };
+    let mut buf = [0u8; 1024];
+    let pod = other.build(Builder::new(&mut buf)).build().unwrap();
+    let mut raw_other: spa_sys::spa_latency_info = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_latency_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw_other)
+    };
+    assert!(res >= 0);
+
+    let process = ProcessLatencyInfo {
+        quantum: 0.25,
+        rate: 32,
+        ns: 1000,
+    };
+    let mut buf = [0u8; 1024];
+    let pod = process.build(Builder::new(&mut buf)).build().unwrap();
+    let mut raw_process: spa_sys::spa_process_latency_info = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_process_latency_parse(
+            pod.as_ptr() as *const spa_sys::spa_pod,
+            &mut raw_process,
+        )
+    };
+    assert!(res >= 0);
+    assert_eq!(raw_process.rate, 32);
+
+    unsafe {
+        assert_eq!(spa_sys::spa_latency_info_combine(&mut raw, &raw_other), 0);
+        assert_eq!(
+            spa_sys::spa_process_latency_info_add(&raw_process, &mut raw),
+            0
+        );
+    }
+
+    let mut combined = info;
+    combined.combine(&other).unwrap();
+    process.add(&mut combined);
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_latency_build(sbuilder.as_raw_ptr(), spa_sys::SPA_PARAM_Latency, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+
+    assert_eq!(
+        LatencyInfo::parse(&mut Parser::new(&sbuf)).unwrap(),
+        combined
+    );
+}
+
+fn headphones() -> ParamRoute {
+    ParamRoute {
+        index: 2,
+        direction: Direction::Output,
+        device: Some(4),
+        name: "analog-output-headphones".to_string(),
+        description: "Headphones".to_string(),
+        priority: 9900,
+        available: Availability::Yes,
+        info: vec![
+            ("port.type".to_string(), "headphones".to_string()),
+            (
+                "port.availability-group".to_string(),
+                "Legacy 2".to_string(),
+            ),
+        ],
+        profiles: vec![1, 3],
+        devices: vec![4],
+        props: Some(ObjectValue {
+            type_: ObjectType::Props as u32,
+            id: ParamType::Route as u32,
+            properties: vec![
+                PropertyValue {
+                    key: Prop::Mute as u32,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Bool(false),
+                },
+                PropertyValue {
+                    key: Prop::Volume as u32,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Float(0.5),
+                },
+            ],
+        }),
+        profile: Some(1),
+        save: true,
+    }
+}
+
+#[test]
+fn test_param_route() {
+    let pod = round_trip(&headphones(), ParamRoute::build_route, ParamRoute::parse);
+    assert_eq!(ObjectRef::wrap(&pod).unwrap().id(), ParamType::Route as u32);
+
+    // What is needed to switch to the route
+    let route = ParamRoute {
+        index: 2,
+        device: Some(4),
+        save: true,
+        ..Default::default()
+    };
+    let pod = round_trip(&route, ParamRoute::build_route, ParamRoute::parse);
+    assert_eq!(ObjectRef::wrap(&pod).unwrap().len(), 6);
+
+    // The props must be a Props object
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::ParamRoute, ParamType::Route, |b| {
+            b.push_property(Route::Props, PropertyFlags::empty(), 1i32)
+        })
+        .build()
+        .unwrap();
+    assert!(ParamRoute::parse(&mut Parser::new(pod)).is_err());
+}
+
+#[test]
+fn test_param_profile() {
+    let profile = ParamProfile {
+        index: 1,
+        name: "output:analog-stereo".to_string(),
+        description: "Analog Stereo Output".to_string(),
+        priority: 6500,
+        available: Availability::Unknown,
+        info: vec![],
+        classes: vec![
+            ProfileClass {
+                name: "Audio/Sink".to_string(),
+                n_nodes: 1,
+                property: "card.profile.devices".to_string(),
+                devices: vec![4],
+            },
+            ProfileClass {
+                name: "Video/Source".to_string(),
+                n_nodes: 2,
+                ..Default::default()
+            },
+        ],
+        save: false,
+    };
+    let pod = round_trip(
+        &profile,
+        ParamProfile::build_enum_profile,
+        ParamProfile::parse,
+    );
+    assert_eq!(
+        ObjectRef::wrap(&pod).unwrap().id(),
+        ParamType::EnumProfile as u32
+    );
+
+    // Switching profiles only needs the index
+    let profile = ParamProfile {
+        index: 3,
+        ..Default::default()
+    };
+    round_trip(&profile, ParamProfile::build_profile, ParamProfile::parse);
+
+    // Not a Profile param
+    let route = round_trip(
+        &headphones(),
+        ParamRoute::build_enum_route,
+        ParamRoute::parse,
+    );
+    assert_rejects(ParamProfile::parse, &pod, &[&route]);
+}
+
+fn dsp_config() -> ParamPortConfig {
+    ParamPortConfig {
+        direction: Direction::Output,
+        mode: PortConfigMode::Dsp,
+        monitor: true,
+        control: false,
+        format: Some(AudioInfo::Raw(AudioInfoRaw {
+            format: AudioFormat::F32P,
+            flags: AudioFlags::empty(),
+            rate: 48000,
+            channels: 2,
+            position: vec![AudioChannel::FL, AudioChannel::FR],
+        })),
+    }
+}
+
+#[test]
+fn test_param_port_config() {
+    let pod = round_trip(
+        &dsp_config(),
+        ParamPortConfig::build_port_config,
+        ParamPortConfig::parse,
+    );
+    assert_dump(
+        &pod,
+        &[
+            "Object ParamPortConfig, id PortConfig:",
+            "  Direction: Id Output (1)",
+            "  Mode: Id Dsp (3)",
+            "  Monitor: Bool true",
+            "  Control: Bool false",
+            "  Format: Object Format, id Format:",
+            "    MediaType: Id Audio (1)",
+            "    MediaSubtype: Id Raw (1)",
+            "    AudioFormat: Id F32P (518)",
+            "    AudioRate: Int 48000",
+            "    AudioChannels: Int 2",
+            "    AudioPosition: Array of Id: [FL (3), FR (4)]",
+        ],
+    );
+
+    // Passthrough does not need a format
+    let config = ParamPortConfig {
+        direction: Direction::Input,
+        mode: PortConfigMode::Passthrough,
+        ..Default::default()
+    };
+    let pod = round_trip(
+        &config,
+        ParamPortConfig::build_port_config,
+        ParamPortConfig::parse,
+    );
+    assert_eq!(ObjectRef::wrap(&pod).unwrap().len(), 4);
+
+    // or it can be any audio format that the device passes through
+    let config = ParamPortConfig {
+        direction: Direction::Input,
+        mode: PortConfigMode::Passthrough,
+        format: Some(AudioInfo::Iec958(AudioInfoIec958 {
+            codec: Iec958Codec::Ac3,
+            flags: 0,
+            rate: 48000,
+        })),
+        ..Default::default()
+    };
+    let pod = round_trip(
+        &config,
+        ParamPortConfig::build_port_config,
+        ParamPortConfig::parse,
+    );
+    assert_dump(
+        &pod,
+        &[
+            "Object ParamPortConfig, id PortConfig:",
+            "  Direction: Id Input (0)",
+            "  Mode: Id Passthrough (1)",
+            "  Monitor: Bool false",
+            "  Control: Bool false",
+            "  Format: Object Format, id Format:",
+            "    MediaType: Id Audio (1)",
+            "    MediaSubtype: Id Iec958 (3)",
+            "    AudioIec958Codec: Id Ac3 (3)",
+            "    AudioRate: Int 48000",
+        ],
+    );
+
+    // The format must be a Format object
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::ParamPortConfig, ParamType::PortConfig, |b| {
+            b.push_property(
+                PortConfig::Format,
+                PropertyFlags::empty(),
+                Value::Object(ObjectValue {
+                    type_: ObjectType::Props as u32,
+                    id: ParamType::Props as u32,
+                    properties: vec![],
+                }),
+            )
+        })
+        .build()
+        .unwrap();
+    assert!(ParamPortConfig::parse(&mut Parser::new(pod)).is_err());
+}
+
+#[test]
+fn test_param_port_config_libspa() {
+    let config = dsp_config();
+    let Some(AudioInfo::Raw(info)) = &config.format else {
+        panic!("Expected a raw audio format");
+    };
+
+    let mut raw: spa_sys::spa_audio_info_raw = unsafe { std::mem::zeroed() };
+    raw.format = info.format as u32;
+    raw.rate = info.rate;
+    raw.channels = info.channels;
+    raw.position[0] = AudioChannel::FL as u32;
+    raw.position[1] = AudioChannel::FR as u32;
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_object(
+                &mut frame,
+                spa_sys::SPA_TYPE_OBJECT_ParamPortConfig,
+                spa_sys::SPA_PARAM_PortConfig,
+            )
+            .unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_PORT_CONFIG_direction, 0)
+            .unwrap();
+        sbuilder
+            .add_id(spa_utils::Id(spa_sys::SPA_DIRECTION_OUTPUT))
+            .unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_PORT_CONFIG_mode, 0)
+            .unwrap();
+        sbuilder
+            .add_id(spa_utils::Id(spa_sys::SPA_PARAM_PORT_CONFIG_MODE_dsp))
+            .unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_PORT_CONFIG_monitor, 0)
+            .unwrap();
+        sbuilder.add_bool(true).unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_PORT_CONFIG_control, 0)
+            .unwrap();
+        sbuilder.add_bool(false).unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_PARAM_PORT_CONFIG_format, 0)
+            .unwrap();
+        let spod = spa_sys::spa_format_audio_raw_build(
+            sbuilder.as_raw_ptr(),
+            spa_sys::SPA_PARAM_Format,
+            &raw,
+        );
+        assert!(!spod.is_null());
+        sbuilder.pop(&mut frame.assume_init());
+    };
+
+    let mut buf = [0u8; 2048];
+    let pod = config
+        .build_port_config(Builder::new(&mut buf))
+        .build()
+        .unwrap();
+    assert_eq!(sbuf, pod);
+    assert_eq!(
+        ParamPortConfig::parse(&mut Parser::new(&sbuf)).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn test_param_tag() {
+    let tag = ParamTag {
+        direction: Direction::Input,
+        info: vec![
+            ("media.title".to_string(), "So What".to_string()),
+            ("media.artist".to_string(), "Miles Davis".to_string()),
+        ],
+    };
+    let pod = round_trip(&tag, ParamTag::build, ParamTag::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object ParamTag, id Tag:",
+            "  Direction: Id Input (0)",
+            "  Info (HINT_DICT): Struct:",
+            "    Int 2",
+            "    String \"media.title\"",
+            "    String \"So What\"",
+            "    String \"media.artist\"",
+            "    String \"Miles Davis\"",
+        ],
+    );
+
+    let dict = tag.dict();
+    assert_eq!(dict.lookup("media.title"), Some("So What"));
+    assert_eq!(ParamTag::new_dict(Direction::Input, &dict), tag);
+
+    // The items of each Info property are collected
+    let mut buf = [0u8; 1024];
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::ParamTag, ParamType::Tag, |b| {
+            let b = ParamTag {
+                direction: Direction::Output,
+                info: vec![("media.title".to_string(), "So What".to_string())],
+            }
+            .push_properties(b);
+            b.push_property(
+                Tag::Info,
+                PropertyFlags::HINT_DICT,
+                Value::Struct(vec![
+                    Value::Int(1),
+                    Value::String("media.album".to_string()),
+                    Value::String("Kind of Blue".to_string()),
+                ]),
+            )
+        })
+        .build()
+        .unwrap();
+    let tag = ParamTag::parse(&mut Parser::new(pod)).unwrap();
+    assert_eq!(tag.direction, Direction::Output);
+    assert_eq!(
+        ParamTag::new_dict(Direction::Output, &tag.dict()).info,
+        vec![
+            ("media.title".to_string(), "So What".to_string()),
+            ("media.album".to_string(), "Kind of Blue".to_string()),
+        ]
+    );
+
+    let empty = ParamTag::default();
+    assert_eq!(ParamTag::new_dict(Direction::Input, &empty.dict()), empty);
+
+    // The count must match the number of pairs
+    for n_items in [-1, 0, 2] {
+        let mut buf = [0u8; 1024];
+        let pod = Builder::new(&mut buf)
+            .push_object(ObjectType::ParamTag, ParamType::Tag, |b| {
+                b.push_property(
+                    Tag::Info,
+                    PropertyFlags::HINT_DICT,
+                    Value::Struct(vec![
+                        Value::Int(n_items),
+                        Value::String("media.title".to_string()),
+                        Value::String("So What".to_string()),
+                    ]),
+                )
+            })
+            .build()
+            .unwrap();
+        assert!(ParamTag::parse(&mut Parser::new(pod)).is_err());
+    }
+}
@@ -4,14 +4,18 @@
 
 use std::ffi::c_void;
 
-use pipewire_native_spa::param::props::PropInfo;
+use pipewire_native_spa::param::format::{Format, MediaSubtype, MediaType};
+use pipewire_native_spa::param::props::{Prop, PropInfo};
 use pipewire_native_spa::param::ParamType;
-use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::builder::{Builder, DynamicBuilder};
+use pipewire_native_spa::pod::object::ObjectRef;
 use pipewire_native_spa::pod::parser::Parser;
 use pipewire_native_spa::pod::types::{
-    Choice, Fd, Fraction, Id, ObjectType, Pointer, PropertyFlags, Rectangle, Type,
+    ArrayRef, Bitmap, BitmapBuf, Choice, ChoiceType, Control, ControlType, Fd, Flags, Fraction, Id,
+    ObjectType, Pointer, Property, PropertyFlags, Rectangle, Sequence, Type,
 };
-use pipewire_native_spa::pod::Pod;
+use pipewire_native_spa::pod::value::{ArrayValue, ChoiceValue, PropertyValue, Value};
+use pipewire_native_spa::pod::{Error, PathSegment, Pod, PodBorrow, RawPod};
 
 use libspa::pod as spa_pod;
 use libspa::sys::{self as spa_sys};
@@ -483,3 +487,1019 @@ fn test_pod_builder_object() {
         ((), 64)
     );
 }
+
+#[test]
+fn test_pod_builder_sequence() {
+    let mut buf = [0u8; 1024];
+
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_sequence(0, |b| {
+            b.push_midi(0, &[0x90, 0x40, 0x7f])
+                .push_midi(128, &[0x80, 0x40, 0x00])
+                .push_control(256, ControlType::Properties, 1.0f32)
+        })
+        .build()
+        .unwrap();
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder.push_sequence(&mut frame, 0).unwrap();
+        sbuilder.add_control(0, spa_sys::SPA_CONTROL_Midi);
+        sbuilder.add_bytes(&[0x90, 0x40, 0x7f]).unwrap();
+        sbuilder.add_control(128, spa_sys::SPA_CONTROL_Midi);
+        sbuilder.add_bytes(&[0x80, 0x40, 0x00]).unwrap();
+        sbuilder.add_control(256, spa_sys::SPA_CONTROL_Properties);
+        sbuilder.add_float(1.0).unwrap();
+        sbuilder.pop(&mut frame.assume_init());
+    };
+    assert_eq!(res, sbuf.as_slice());
+
+    let mut parser = Parser::new(&buf);
+    let sequence = parser.pop_sequence().unwrap();
+
+    assert_eq!(sequence.unit, 0);
+    assert_eq!(sequence.len(), 3);
+
+    let controls = sequence.controls();
+    assert_eq!(controls[0].offset, 0);
+    assert_eq!(controls[0].type_, ControlType::Midi);
+    assert_eq!(
+        controls[0].value().unwrap().decode::<&[u8]>().unwrap(),
+        vec![0x90, 0x40, 0x7f]
+    );
+    assert_eq!(controls[1].offset, 128);
+    assert_eq!(controls[1].type_, ControlType::Midi);
+    assert_eq!(
+        controls[1].value().unwrap().decode::<&[u8]>().unwrap(),
+        vec![0x80, 0x40, 0x00]
+    );
+    assert_eq!(controls[2].offset, 256);
+    assert_eq!(controls[2].type_, ControlType::Properties);
+    assert_eq!(controls[2].value().unwrap().decode::<f32>().unwrap(), 1.0);
+}
+
+#[test]
+fn test_pod_dynamic_builder() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let expected = builder
+        .push_struct(|b| {
+            b.push_int(0)
+                .push_int(1)
+                .push_int(2)
+                .push_object(ObjectType::Props, ParamType::Props, |b| {
+                    b.push_property(Prop::Volume, PropertyFlags::empty(), 0.5f32)
+                        .push_property(
+                            Prop::ChannelVolumes,
+                            PropertyFlags::HARDWARE,
+                            &[1.0f32, 0.5][..],
+                        )
+                })
+                .push_struct(|b| b)
+                .push_sequence(0, |b| b.push_midi(16, &[0x90, 0x40, 0x7f]))
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    // Start out with no space at all, so that the buffer has to grow
+    let mut builder = DynamicBuilder::new();
+    let frame = builder.push_frame_struct();
+    for i in 0..3 {
+        builder.push_int(i);
+    }
+
+    let object = builder.push_frame_object(ObjectType::Props, ParamType::Props);
+    builder
+        .push_property(Prop::Volume, PropertyFlags::empty(), &0.5f32)
+        .push_property_key(Prop::ChannelVolumes, PropertyFlags::HARDWARE)
+        .push_array(&[1.0f32, 0.5])
+        .pop_frame(object);
+
+    let empty = builder.push_frame_struct();
+    builder.pop_frame(empty);
+
+    let sequence = builder.push_frame_sequence(0);
+    builder
+        .push_control(16, ControlType::Midi, &&[0x90u8, 0x40, 0x7f][..])
+        .pop_frame(sequence)
+        .pop_frame(frame);
+
+    assert_eq!(builder.len(), expected.len());
+    assert_eq!(builder.build().unwrap(), expected);
+
+    // Values much larger than the space that is left are encoded in one go
+    let values = (0..10000).collect::<Vec<i32>>();
+    let mut builder = DynamicBuilder::new();
+    builder.push_int(1).push_array(&values).push_int(2);
+
+    let data = builder.build().unwrap();
+    let mut parser = Parser::new(&data);
+    assert_eq!(parser.pop_int().unwrap(), 1);
+    assert_eq!(parser.pop_array_ref::<i32>().unwrap().to_vec(), values);
+    assert_eq!(parser.pop_int().unwrap(), 2);
+
+    // Frames that are left open or closed out of order are an error
+    let mut builder = DynamicBuilder::with_capacity(64);
+    builder.reserve(1024);
+    let _frame = builder.push_frame_struct();
+    builder.push_int(1);
+    assert!(builder.build().is_err());
+
+    let mut builder = DynamicBuilder::new();
+    let outer = builder.push_frame_struct();
+    let inner = builder.push_frame_struct();
+    builder.pop_frame(outer).pop_frame(inner);
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn test_pod_sequence() {
+    let mut sequence = Sequence::new(0);
+
+    sequence.insert(Control::midi(64, &[0x80, 0x40, 0x00]).unwrap());
+    sequence.insert(Control::midi(0, &[0x90, 0x40, 0x7f]).unwrap());
+    sequence.insert(Control::new(64, ControlType::Properties, &2.0f64).unwrap());
+
+    // Controls are ordered by offset, and insertion order is preserved for equal offsets
+    let offsets = sequence
+        .iter()
+        .map(|c| (c.offset, c.type_))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        offsets,
+        vec![
+            (0, ControlType::Midi),
+            (64, ControlType::Midi),
+            (64, ControlType::Properties)
+        ]
+    );
+
+    test_a_pod(&Sequence::new(0));
+    test_a_pod(&sequence);
+}
+
+#[test]
+fn test_pod_bitmap() {
+    let mut bitmap = BitmapBuf::new(3);
+    assert!(bitmap.set(0, true));
+    assert!(bitmap.set(9, true));
+    assert!(bitmap.set(23, true));
+    assert!(!bitmap.set(24, true));
+    assert!(bitmap.get(9));
+    assert!(!bitmap.get(10));
+    assert_eq!(bitmap.count_ones(), 3);
+    assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), vec![0, 9, 23]);
+    assert_eq!(bitmap.as_bytes(), &[0x01, 0x02, 0x80]);
+
+    test_a_pod(&bitmap);
+    test_a_pod(&BitmapBuf::new(0));
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_bitmap(bitmap.as_bitmap())
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Params, PropertyFlags::empty(), bitmap.as_bitmap())
+        })
+        .build()
+        .unwrap();
+
+    // Bitmap bodies are raw bytes, padded to 8 bytes
+    assert_eq!(res[0..4], 3u32.to_ne_bytes());
+    assert_eq!(res[4..8], (Type::Bitmap as u32).to_ne_bytes());
+    assert_eq!(res[8..16], [0x01, 0x02, 0x80, 0, 0, 0, 0, 0]);
+
+    let mut parser = Parser::new(&buf);
+    assert_eq!(parser.pop_bitmap().unwrap(), bitmap.as_bitmap());
+    parser
+        .pop_object::<Prop, _>(|p, _| {
+            let (key, _flags, data) = p.pop_property::<Prop>()?.unwrap();
+            assert_eq!(key, Prop::Params);
+            assert_eq!(data.type_(), Type::Bitmap);
+            assert_eq!(data.decode::<BitmapBuf>().unwrap(), bitmap);
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_pod_bitmap_any_length() {
+    // The decoder learns the bitmap length from the pod, not the caller
+    for len in [1usize, 7, 8, 13, 64] {
+        let mut bytes = vec![0u8; len];
+        bytes[len - 1] = 0x80;
+        let mut buf = [0u8; 128];
+        let n = BitmapBuf::from_bytes(bytes.clone())
+            .encode(&mut buf)
+            .unwrap();
+        let encoded = &buf[..n];
+
+        let (bitmap, size) = Bitmap::decode_borrowed(encoded).unwrap();
+        assert_eq!(size, encoded.len());
+        assert_eq!(bitmap.as_bytes(), bytes.as_slice());
+        assert_eq!(bitmap.bits(), len * 8);
+        assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), vec![len * 8 - 1]);
+
+        let (owned, _) = BitmapBuf::decode(encoded).unwrap();
+        assert_eq!(owned.as_bitmap(), bitmap);
+    }
+}
+
+#[test]
+fn test_pod_bitmap_choice_array() {
+    let a = BitmapBuf::from_bytes(vec![0x01, 0x80]);
+    let b = BitmapBuf::from_bytes(vec![0xff, 0x00]);
+
+    // Bitmaps have no fixed size, so arrays and choices take it from the bitmaps they hold
+    test_a_pod(&[a.clone(), b.clone()].as_slice());
+    test_a_pod(&Vec::<BitmapBuf>::new().as_slice());
+    test_a_pod(&Choice::None(a.clone()));
+    test_a_pod(&Choice::Enum {
+        default: a.clone(),
+        alternatives: vec![a.clone(), b.clone()],
+    });
+    test_a_pod(&Choice::Flags {
+        default: a.clone(),
+        flags: b.clone(),
+    });
+
+    let mut buf = [0u8; 1024];
+    let size = [a.clone(), b.clone()].as_slice().encode(&mut buf).unwrap();
+    assert_eq!(size, 24);
+    assert_eq!(buf[8..12], 2u32.to_ne_bytes());
+    assert_eq!(buf[12..16], (Type::Bitmap as u32).to_ne_bytes());
+    assert_eq!(buf[16..20], [0x01, 0x80, 0xff, 0x00]);
+    assert_eq!(
+        Value::decode(&buf).unwrap().0,
+        Value::Array(ArrayValue {
+            child_type: Type::Bitmap,
+            values: vec![
+                Value::Bitmap(vec![0x01, 0x80]),
+                Value::Bitmap(vec![0xff, 0x00])
+            ],
+        })
+    );
+
+    // All of them must be of the same size
+    let short = BitmapBuf::new(1);
+    assert!([a.clone(), short.clone()]
+        .as_slice()
+        .encode(&mut buf)
+        .is_err());
+    assert!(Choice::Range {
+        default: a.clone(),
+        min: short,
+        max: b.clone(),
+    }
+    .encode(&mut buf)
+    .is_err());
+
+    // Inside a property
+    let choice = Choice::Enum {
+        default: a.clone(),
+        alternatives: vec![a.clone(), b.clone()],
+    };
+    let pod = Builder::new(&mut buf)
+        .push_object(ObjectType::Props, ParamType::Props, |builder| {
+            builder.push_property(Prop::Params, PropertyFlags::empty(), choice.clone())
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let object = ObjectRef::wrap(&pod).unwrap();
+    assert_eq!(
+        object.get_as::<Choice<BitmapBuf>>(Prop::Params).unwrap(),
+        Some(choice)
+    );
+    assert_eq!(
+        object.get_as::<Value>(Prop::Params).unwrap(),
+        Some(Value::Choice(ChoiceValue {
+            type_: ChoiceType::Enum,
+            flags: 0,
+            child_type: Type::Bitmap,
+            values: vec![
+                Value::Bitmap(vec![0x01, 0x80]),
+                Value::Bitmap(vec![0x01, 0x80]),
+                Value::Bitmap(vec![0xff, 0x00]),
+            ],
+        }))
+    );
+}
+
+#[test]
+fn test_pod_flags() {
+    let flags = PropertyFlags::READ_ONLY | PropertyFlags::MANDATORY;
+
+    test_a_pod(&Flags(flags));
+    test_a_pod(&vec![Flags(flags), Flags(PropertyFlags::HARDWARE)].as_slice());
+    test_a_pod(&Choice::Flags {
+        default: Flags(PropertyFlags::READ_ONLY),
+        flags: Flags(flags),
+    });
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder.push_flags(flags).build().unwrap();
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    sbuilder.add_int(flags.bits() as i32).unwrap();
+    assert_eq!(res, sbuf.as_slice());
+
+    let mut parser = Parser::new(&buf);
+    assert_eq!(parser.pop_flags::<PropertyFlags>().unwrap(), flags);
+}
+
+#[test]
+fn test_pod_property_unknown_flags() {
+    // Property flags we don't know about are kept rather than rejected
+    let flags = PropertyFlags::READ_ONLY | PropertyFlags::from_bits_retain(1 << 20);
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Volume, flags, 1.0f32)
+        })
+        .build()
+        .unwrap();
+
+    let mut parser = Parser::new(&buf);
+    parser
+        .pop_object::<Prop, _>(|p, _| {
+            let (key, parsed, _) = p.pop_property::<Prop>()?.unwrap();
+            assert_eq!(key, Prop::Volume);
+            assert_eq!(parsed, flags);
+            Ok(())
+        })
+        .unwrap();
+
+    // The property body starts after the pod and object headers
+    let property = Property::<Prop, f32>::decode(&buf[16..]).unwrap().0;
+    assert_eq!(property.flags, flags);
+
+    let Value::Object(object) = Value::decode(&buf).unwrap().0 else {
+        panic!("Expected an object");
+    };
+    assert_eq!(object.properties[0].flags, flags);
+    assert_eq!(Value::Object(object).to_bytes().unwrap(), buf[..40]);
+}
+
+#[test]
+fn test_pod_borrowed() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    builder
+        .push_string("hello")
+        .push_bytes(&[1, 2, 3])
+        .push_array(&[Id(1u32), Id(2), Id(3)])
+        .push_int(4)
+        .push_struct(|b| b.push_string("nested"))
+        .push_object(ObjectType::PropInfo, ParamType::PropInfo, |b| {
+            b.push_property(PropInfo::Name, PropertyFlags::empty(), "name")
+                .push_property(
+                    PropInfo::Labels,
+                    PropertyFlags::empty(),
+                    [5i32, 6].as_slice(),
+                )
+        })
+        .build()
+        .unwrap();
+
+    let buf_range = buf.as_ptr_range();
+    let mut parser = Parser::new(&buf);
+
+    let s = parser.pop_string_ref().unwrap();
+    assert_eq!(s, "hello");
+    assert!(buf_range.contains(&s.as_ptr()));
+
+    let b = parser.pop_bytes_ref().unwrap();
+    assert_eq!(b, &[1, 2, 3]);
+    assert!(buf_range.contains(&b.as_ptr()));
+
+    let a = parser.pop_array_ref::<Id<u32>>().unwrap();
+    assert_eq!(a.len(), 3);
+    assert_eq!(a.get(1), Some(Id(2)));
+    assert_eq!(a.get(3), None);
+    assert_eq!(a.to_vec(), vec![Id(1), Id(2), Id(3)]);
+
+    assert_eq!(parser.pop_borrowed::<i32>().unwrap(), 4);
+
+    // Borrowed values can outlive the struct and object parsers
+    let (nested, _) = parser.pop_struct(|p| p.pop_string_ref()).unwrap();
+    assert_eq!(nested, "nested");
+
+    let ((name, labels), _) = parser
+        .pop_object::<PropInfo, _>(|p, _| {
+            let (_, _, name) = p.pop_property_borrowed::<PropInfo, &str>()?.unwrap();
+            let (_, _, labels) = p
+                .pop_property_borrowed::<PropInfo, ArrayRef<i32>>()?
+                .unwrap();
+            Ok((name, labels))
+        })
+        .unwrap();
+    assert_eq!(name, "name");
+    assert_eq!(labels.iter().collect::<Vec<_>>(), vec![5, 6]);
+
+    // Type mismatches are still caught
+    let mut parser = Parser::new(&buf);
+    assert!(parser.pop_bytes_ref().is_err());
+    assert!(parser.pop_array_ref::<i32>().is_err());
+}
+
+#[test]
+fn test_pod_array_ref_lazy() {
+    #[repr(align(8))]
+    struct Aligned([u8; 256]);
+
+    let mut buf = Aligned([0u8; 256]);
+    let builder = Builder::new(&mut buf.0);
+    builder
+        .push_array(&[1.0f64, 2.0, 3.0])
+        .push_array(&[Id(100u32), Id(1)])
+        .build()
+        .unwrap();
+
+    let mut parser = Parser::new(&buf.0);
+    let doubles = parser.pop_array_ref::<f64>().unwrap();
+    assert_eq!(doubles.as_slice(), Some(&[1.0f64, 2.0, 3.0][..]));
+
+    // Elements that do not decode are only reported when accessed
+    let formats = parser.pop_array_ref::<Id<MediaType>>().unwrap();
+    assert_eq!(formats.len(), 2);
+    assert_eq!(formats.get(0), None);
+    assert_eq!(formats.get(1), Some(Id(MediaType::Audio)));
+    assert_eq!(formats.to_vec(), vec![Id(MediaType::Audio)]);
+
+    // Misaligned bodies cannot be viewed as a slice
+    let mut buf = Aligned([0u8; 256]);
+    let builder = Builder::new(&mut buf.0[4..]);
+    builder.push_array(&[1i64, 2]).build().unwrap();
+
+    let mut parser = Parser::new(&buf.0[4..]);
+    let longs = parser.pop_array_ref::<i64>().unwrap();
+    assert_eq!(longs.as_slice(), None);
+    assert_eq!(longs.to_vec(), vec![1, 2]);
+}
+
+#[test]
+fn test_pod_object_ref() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let pod = builder
+        .push_object(ObjectType::Format, ParamType::EnumFormat, |b| {
+            b.push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Raw),
+            )
+            .push_property(
+                Format::AudioRate,
+                PropertyFlags::MANDATORY,
+                Choice::Range {
+                    default: 48000i32,
+                    min: 1,
+                    max: 384000,
+                },
+            )
+            .push_property(Format::AudioChannels, PropertyFlags::empty(), 2i32)
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let mut parser = Parser::new(&pod);
+    let object = parser.pop_object_ref().unwrap();
+
+    assert_eq!(object.object_type(), Some(ObjectType::Format));
+    assert_eq!(object.id(), ParamType::EnumFormat as u32);
+    assert_eq!(object.total_size(), pod.len());
+    assert_eq!(object.len(), 4);
+
+    // Lookups in any order
+    assert_eq!(
+        object.get_as::<i32>(Format::AudioChannels).unwrap(),
+        Some(2)
+    );
+    assert_eq!(
+        object.get_as::<Id<MediaType>>(Format::MediaType).unwrap(),
+        Some(Id(MediaType::Audio))
+    );
+    assert_eq!(
+        object.get_as::<Choice<i32>>(Format::AudioRate).unwrap(),
+        Some(Choice::Range {
+            default: 48000,
+            min: 1,
+            max: 384000
+        })
+    );
+    assert_eq!(object.get(Format::AudioRate).unwrap().type_(), Type::Choice);
+    assert!(object.get(Format::AudioFormat).is_none());
+    assert_eq!(object.get_as::<i32>(Format::AudioFormat).unwrap(), None);
+    assert_eq!(
+        object.property(Format::AudioRate).unwrap().flags,
+        PropertyFlags::MANDATORY
+    );
+
+    // Errors carry the location of the property
+    let err = object.get_as::<i32>(Format::AudioRate).unwrap_err();
+    assert!(matches!(
+        err.cause(),
+        Error::WrongType {
+            expected: Type::Int,
+            found: Type::Choice
+        }
+    ));
+    assert_eq!(
+        err.path(),
+        &[
+            PathSegment::Object(ObjectType::Format as u32),
+            PathSegment::Property {
+                object_type: ObjectType::Format as u32,
+                key: Format::AudioRate as u32
+            },
+        ]
+    );
+
+    // Iteration is in order, with flags
+    let keys = object.iter().map(|p| (p.key, p.flags)).collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec![
+            (Format::MediaType as u32, PropertyFlags::empty()),
+            (Format::MediaSubtype as u32, PropertyFlags::empty()),
+            (Format::AudioRate as u32, PropertyFlags::MANDATORY),
+            (Format::AudioChannels as u32, PropertyFlags::empty()),
+        ]
+    );
+
+    // Replace, remove and add properties
+    let mut rewrite = object.rewrite();
+    rewrite
+        .set(Format::AudioRate, PropertyFlags::empty(), &44100i32)
+        .remove(Format::MediaSubtype)
+        .set(Format::AudioChannels, PropertyFlags::empty(), &6i32)
+        .set(Format::AudioFormat, PropertyFlags::empty(), &Id(1u32));
+    let rewritten = rewrite.build().unwrap();
+
+    let object = RawPod::wrap(&rewritten)
+        .unwrap()
+        .decode_borrowed::<ObjectRef>()
+        .unwrap();
+    let values = object
+        .iter()
+        .map(|p| (p.key, p.value.decode::<Value>().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            (Format::MediaType as u32, Value::Id(MediaType::Audio as u32)),
+            (Format::AudioRate as u32, Value::Int(44100)),
+            (Format::AudioChannels as u32, Value::Int(6)),
+            (Format::AudioFormat as u32, Value::Id(1)),
+        ]
+    );
+
+    // Without changes, the object is copied as is
+    let object = ObjectRef::wrap(&pod).unwrap();
+    assert_eq!(object.rewrite().build().unwrap(), pod);
+
+    // Truncated objects are rejected up front
+    let mut truncated = pod.clone();
+    truncated[0..4].copy_from_slice(&(pod.len() as u32 - 16).to_ne_bytes());
+    assert!(ObjectRef::wrap(&truncated).is_err());
+    assert!(ObjectRef::wrap(&Value::Int(1).to_bytes().unwrap()).is_err());
+}
+
+#[test]
+fn test_pod_value() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_struct(|b| {
+            b.push_none()
+                .push_bool(true)
+                .push_id(Id(1u32))
+                .push_long(2)
+                .push_double(3.0)
+                .push_string("hello")
+                .push_bytes(&[4, 5, 6])
+                .push_fd(7)
+                .push_rectangle(1920, 1080)
+                .push_fraction(30, 1)
+                .push_bitmap(Bitmap(&[0x81]))
+                .push_array(&[8i32, 9])
+                .push_array::<f32>(&[])
+                .push_choice(Choice::Range {
+                    default: 48000i32,
+                    min: 1,
+                    max: 384000,
+                })
+                .push_object(ObjectType::Props, ParamType::Props, |b| {
+                    b.push_property(Prop::Volume, PropertyFlags::HARDWARE, 0.5f32)
+                        .push_property(Prop::Mute, PropertyFlags::empty(), false)
+                })
+                .push_sequence(0, |b| b.push_midi(16, &[0x90, 0x40, 0x7f]))
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    let mut parser = Parser::new(&res);
+    let value = parser.pop_value().unwrap();
+
+    let Value::Struct(fields) = &value else {
+        panic!("Expected a struct, got {:?}", value);
+    };
+    assert_eq!(fields.len(), 16);
+    assert_eq!(fields[0], Value::None);
+    assert_eq!(fields[2], Value::Id(1));
+    assert_eq!(fields[5], Value::String("hello".to_string()));
+    assert_eq!(fields[10], Value::Bitmap(vec![0x81]));
+    assert_eq!(
+        fields[11],
+        Value::Array(ArrayValue {
+            child_type: Type::Int,
+            values: vec![Value::Int(8), Value::Int(9)],
+        })
+    );
+    assert_eq!(
+        fields[12],
+        Value::Array(ArrayValue {
+            child_type: Type::Float,
+            values: vec![],
+        })
+    );
+    assert_eq!(
+        fields[13],
+        Value::Choice(ChoiceValue {
+            type_: ChoiceType::Range,
+            flags: 0,
+            child_type: Type::Int,
+            values: vec![Value::Int(48000), Value::Int(1), Value::Int(384000)],
+        })
+    );
+
+    let Value::Object(object) = &fields[14] else {
+        panic!("Expected an object, got {:?}", fields[14]);
+    };
+    assert_eq!(object.object_type(), Some(ObjectType::Props));
+    assert_eq!(object.id, ParamType::Props as u32);
+    assert_eq!(
+        object.property(Prop::Volume),
+        Some(&PropertyValue {
+            key: Prop::Volume as u32,
+            flags: PropertyFlags::HARDWARE,
+            value: Value::Float(0.5),
+        })
+    );
+
+    let Value::Sequence(sequence) = &fields[15] else {
+        panic!("Expected a sequence, got {:?}", fields[15]);
+    };
+    assert_eq!(sequence.controls.len(), 1);
+    assert_eq!(sequence.controls[0].offset, 16);
+    assert_eq!(sequence.controls[0].type_, ControlType::Midi);
+    assert_eq!(
+        sequence.controls[0].value,
+        Value::Bytes(vec![0x90, 0x40, 0x7f])
+    );
+
+    // Encoding the value gives back the same pod
+    assert_eq!(value.to_bytes().unwrap(), res);
+    test_a_pod(&value);
+
+    // Mismatched array elements can't be encoded
+    let bad = Value::Array(ArrayValue {
+        child_type: Type::Int,
+        values: vec![Value::Int(1), Value::Long(2)],
+    });
+    assert!(bad.to_bytes().is_err());
+
+    // Strings must be valid UTF-8
+    let mut pod = Vec::new();
+    pod.extend_from_slice(&3u32.to_ne_bytes());
+    pod.extend_from_slice(&(Type::String as u32).to_ne_bytes());
+    pod.extend_from_slice(&[0xff, 0xfe, 0, 0, 0, 0, 0, 0]);
+    assert!(matches!(
+        Value::decode(&pod).unwrap_err().cause(),
+        Error::Invalid
+    ));
+}
+
+#[test]
+fn test_pod_errors() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let mut pod = builder
+        .push_object(ObjectType::Format, ParamType::EnumFormat, |b| {
+            b.push_property(
+                Format::AudioRate,
+                PropertyFlags::empty(),
+                Choice::Enum {
+                    default: 48000i32,
+                    alternatives: vec![44100, 48000],
+                },
+            )
+        })
+        .build()
+        .unwrap()
+        .to_vec();
+
+    // Turn the choice into one of strings, with the last one missing its terminator
+    pod[44..48].copy_from_slice(&(Type::String as u32).to_ne_bytes());
+    pod[48..56].copy_from_slice(b"abc\0def\0");
+    pod[56..60].copy_from_slice(&[1, 1, 1, 1]);
+
+    let err = Value::decode(&pod).unwrap_err();
+    assert!(matches!(err.cause(), Error::Invalid));
+    assert_eq!(err.offset(), Some(56));
+    assert_eq!(
+        err.path(),
+        &[
+            PathSegment::Object(ObjectType::Format as u32),
+            PathSegment::Property {
+                object_type: ObjectType::Format as u32,
+                key: Format::AudioRate as u32,
+            },
+            PathSegment::Element(Type::Choice, 2),
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid pod at offset 56 (Object(Format)/AudioRate/Choice[2])"
+    );
+
+    // Decoding a property as the wrong type
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let pod = builder
+        .push_object(ObjectType::Format, ParamType::Format, |b| {
+            b.push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(Format::AudioRate, PropertyFlags::empty(), 48000.0f32)
+        })
+        .build()
+        .unwrap();
+
+    let mut parser = Parser::new(pod);
+    let err = parser
+        .pop_object::<Format, _>(|p, _| {
+            while let Some((key, _, value)) = p.pop_property::<Format>()? {
+                if key == Format::AudioRate {
+                    value.decode::<i32>()?;
+                }
+            }
+            Ok(())
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.cause(),
+        Error::WrongType {
+            expected: Type::Int,
+            found: Type::Float
+        }
+    ));
+    assert_eq!(err.offset(), Some(48));
+    assert_eq!(
+        err.to_string(),
+        "expected Int pod, found Float at offset 48 (Object(Format)/AudioRate)"
+    );
+
+    // Errors raised by the caller belong to the property being decoded, if any
+    let mut parser = Parser::new(pod);
+    let err = parser
+        .pop_object::<Format, _>(|p, _| {
+            p.pop_property::<Format>()?;
+            Err::<(), _>(Error::Invalid)
+        })
+        .unwrap_err();
+    assert!(matches!(err.cause(), Error::Invalid));
+    assert_eq!(err.offset(), Some(24));
+    assert_eq!(
+        err.to_string(),
+        "invalid pod at offset 24 (Object(Format)/MediaType)"
+    );
+
+    // and to the object once all properties have been decoded
+    let mut parser = Parser::new(pod);
+    let err = parser
+        .pop_object::<Format, _>(|p, _| {
+            while p.pop_property::<Format>()?.is_some() {}
+            Err::<(), _>(Error::Invalid)
+        })
+        .unwrap_err();
+    assert!(matches!(err.cause(), Error::Invalid));
+    assert_eq!(err.offset(), Some(16));
+    assert_eq!(
+        err.path(),
+        &[PathSegment::Object(ObjectType::Format as u32)]
+    );
+
+    // Objects of another type are rejected where their type is
+    let mut parser = Parser::new(pod);
+    let err = parser.pop_object::<Prop, _>(|_, _| Ok(())).unwrap_err();
+    assert!(matches!(
+        err.cause(),
+        Error::WrongObjectType { expected, found }
+            if *expected == ObjectType::Props as u32 && *found == ObjectType::Format as u32
+    ));
+    assert_eq!(
+        err.to_string(),
+        "expected Props object, found Format at offset 8"
+    );
+
+    // as are unknown param ids
+    let mut unknown = pod.to_vec();
+    unknown[12..16].copy_from_slice(&0x1000u32.to_ne_bytes());
+    let err = Parser::new(&unknown)
+        .pop_object::<Format, _>(|_, _| Ok(()))
+        .unwrap_err();
+    assert!(matches!(err.cause(), Error::Invalid));
+    assert_eq!(err.offset(), Some(12));
+
+    // Fields of a struct are counted
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let pod = builder
+        .push_struct(|b| b.push_int(1).push_string("two"))
+        .build()
+        .unwrap();
+
+    let mut parser = Parser::new(pod);
+    let err = parser
+        .pop_struct(|p| {
+            p.pop_int()?;
+            p.pop_int()
+        })
+        .unwrap_err();
+    assert_eq!(err.offset(), Some(24));
+    assert_eq!(err.path(), &[PathSegment::Element(Type::Struct, 1)]);
+
+    // Deferred builder errors carry the location of the pod that did not fit
+    let mut buf = [0u8; 48];
+    let builder = Builder::new(&mut buf);
+    let err = builder
+        .push_object(ObjectType::Props, ParamType::Props, |b| {
+            b.push_property(Prop::Volume, PropertyFlags::empty(), 1.0f32)
+                .push_property(
+                    Prop::DeviceName,
+                    PropertyFlags::empty(),
+                    "a long device name",
+                )
+        })
+        .build()
+        .unwrap_err();
+    assert!(matches!(err.cause(), Error::NoSpace));
+    assert_eq!(err.offset(), Some(40));
+    assert_eq!(
+        err.to_string(),
+        "not enough space for pod at offset 40 (Object(Props)/DeviceName)"
+    );
+}
+
+#[test]
+fn test_pod_value_libspa() {
+    let mut sbuf = Vec::with_capacity(1024);
+    let mut sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    unsafe {
+        let mut frame: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_object(
+                &mut frame,
+                spa_sys::SPA_TYPE_OBJECT_Format,
+                spa_sys::SPA_PARAM_EnumFormat,
+            )
+            .unwrap();
+        sbuilder.add_prop(spa_sys::SPA_FORMAT_mediaType, 0).unwrap();
+        sbuilder
+            .add_id(spa_utils::Id(spa_sys::SPA_MEDIA_TYPE_audio))
+            .unwrap();
+        sbuilder
+            .add_prop(spa_sys::SPA_FORMAT_AUDIO_rate, 0)
+            .unwrap();
+        let mut choice: std::mem::MaybeUninit<spa_sys::spa_pod_frame> =
+            std::mem::MaybeUninit::uninit();
+        sbuilder
+            .push_choice(&mut choice, spa_sys::SPA_CHOICE_Enum, 0)
+            .unwrap();
+        sbuilder.add_int(48000).unwrap();
+        sbuilder.add_int(44100).unwrap();
+        sbuilder.add_int(48000).unwrap();
+        sbuilder.pop(&mut choice.assume_init());
+        sbuilder.pop(&mut frame.assume_init());
+    };
+
+    let (value, size) = Value::decode(&sbuf).unwrap();
+    assert_eq!(size, sbuf.len());
+    assert_eq!(value.to_bytes().unwrap(), sbuf);
+}
+
+#[test]
+fn test_pod_debug() {
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_object(ObjectType::Format, ParamType::EnumFormat, |b| {
+            b.push_property(
+                Format::MediaType,
+                PropertyFlags::empty(),
+                Id(MediaType::Audio),
+            )
+            .push_property(
+                Format::MediaSubtype,
+                PropertyFlags::empty(),
+                Id(MediaSubtype::Raw),
+            )
+            .push_property(
+                Format::AudioRate,
+                PropertyFlags::MANDATORY,
+                Choice::Range {
+                    default: 48000i32,
+                    min: 1,
+                    max: 384000,
+                },
+            )
+            .push_property(
+                Format::AudioChannels,
+                PropertyFlags::empty(),
+                Choice::Enum {
+                    default: 2i32,
+                    alternatives: vec![1, 2],
+                },
+            )
+            .push_property(
+                Format::AudioFlags,
+                PropertyFlags::empty(),
+                Choice::Flags {
+                    default: 0i32,
+                    flags: 0x3,
+                },
+            )
+            .push_property(
+                Format::AudioPosition,
+                PropertyFlags::empty(),
+                [Id(3u32), Id(4)].as_slice(),
+            )
+        })
+        .build()
+        .unwrap();
+
+    let pod = RawPod::wrap(res).unwrap();
+    assert_eq!(
+        pod.to_string(),
+        [
+            "Object Format, id EnumFormat:",
+            "  MediaType: Id Audio (1)",
+            "  MediaSubtype: Id Raw (1)",
+            "  AudioRate (MANDATORY): Choice Range of Int: default 48000, min 1, max 384000",
+            "  AudioChannels: Choice Enum of Int: default 2, alternatives [1, 2]",
+            "  AudioFlags: Choice Flags of Int: default 0x0, flags 0x3",
+            "  AudioPosition: Array of Id: [FL (3), FR (4)]",
+        ]
+        .join("\n")
+    );
+
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_struct(|b| {
+            b.push_string("hello")
+                .push_rectangle(1920, 1080)
+                .push_struct(|b| b.push_fraction(30, 1).push_bytes(&[0xde, 0xad]))
+                .push_sequence(0, |b| b.push_midi(8, &[0x90, 0x40, 0x7f]))
+        })
+        .build()
+        .unwrap();
+
+    let pod = RawPod::wrap(res).unwrap();
+    assert_eq!(
+        pod.to_string(),
+        [
+            "Struct:",
+            "  String \"hello\"",
+            "  Rectangle 1920x1080",
+            "  Struct:",
+            "    Fraction 30/1",
+            "    Bytes [de ad]",
+            "  Sequence, unit 0:",
+            "    Control Midi at 8: Bytes [90 40 7f]",
+        ]
+        .join("\n")
+    );
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::de::from_bytes;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::ser::to_bytes;
+use pipewire_native_spa::pod::types::Id;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Off,
+    Fixed(u32),
+    Range(i32, i32),
+    Named { name: String, level: f32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Settings {
+    enabled: bool,
+    rate: u32,
+    position: i64,
+    gain: f64,
+    name: String,
+    nick: Option<String>,
+    channels: Vec<u32>,
+    labels: Vec<String>,
+    modes: Vec<Mode>,
+    extra: BTreeMap<String, i32>,
+    pair: (u8, char),
+    data: Vec<u8>,
+}
+
+#[test]
+fn test_serde_roundtrip() {
+    let settings = Settings {
+        enabled: true,
+        rate: 48000,
+        position: -12345678901,
+        gain: 0.75,
+        name: "speaker".to_string(),
+        nick: None,
+        channels: vec![3, 4],
+        labels: vec!["FL".to_string(), "FR".to_string()],
+        modes: vec![
+            Mode::Off,
+            Mode::Fixed(u32::MAX),
+            Mode::Range(-1, 1),
+            Mode::Named {
+                name: "night".to_string(),
+                level: 0.25,
+            },
+        ],
+        extra: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+        pair: (7, 'x'),
+        data: Vec::new(),
+    };
+
+    let bytes = to_bytes(&settings).unwrap();
+    let decoded: Settings = from_bytes(&bytes).unwrap();
+    assert_eq!(settings, decoded);
+
+    let some: Option<Vec<i32>> = Some(vec![1, 2, 3]);
+    let decoded: Option<Vec<i32>> = from_bytes(&to_bytes(&some).unwrap()).unwrap();
+    assert_eq!(some, decoded);
+
+    let none: Option<Vec<i32>> = None;
+    let decoded: Option<Vec<i32>> = from_bytes(&to_bytes(&none).unwrap()).unwrap();
+    assert_eq!(none, decoded);
+
+    // Type mismatches are reported, not panicked on
+    assert!(from_bytes::<Settings>(&to_bytes(&(1i32, 2i32)).unwrap()).is_err());
+    assert!(from_bytes::<String>(&to_bytes(&1i32).unwrap()).is_err());
+    assert!(from_bytes::<Mode>(&to_bytes(&7u32).unwrap()).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Simple {
+    value: i32,
+    name: String,
+    mode: Mode,
+}
+
+#[test]
+fn test_serde_builder_compat() {
+    let simple = Simple {
+        value: 42,
+        name: "hello".to_string(),
+        mode: Mode::Off,
+    };
+
+    // Serialized output matches what the builder produces
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder
+        .push_struct(|b| b.push_int(42).push_string("hello").push_id(Id(0u32)))
+        .build()
+        .unwrap();
+
+    let bytes = to_bytes(&simple).unwrap();
+    assert_eq!(bytes, res);
+
+    // ... and can be parsed with the parser
+    let mut parser = Parser::new(&bytes);
+    parser
+        .pop_struct(|p| {
+            assert_eq!(p.pop_int().unwrap(), 42);
+            assert_eq!(p.pop_string().unwrap(), "hello");
+            assert_eq!(p.pop_id::<u32>().unwrap(), Id(0));
+            Ok(())
+        })
+        .unwrap();
+
+    // Builder output can be deserialized
+    let decoded: Simple = from_bytes(res).unwrap();
+    assert_eq!(decoded, simple);
+
+    // Sequences of a fixed-size type become arrays
+    let mut buf = [0u8; 1024];
+    let builder = Builder::new(&mut buf);
+    let res = builder.push_array(&[1i32, 2, 3]).build().unwrap();
+
+    assert_eq!(to_bytes(&vec![1i32, 2, 3]).unwrap(), res);
+    assert_eq!(from_bytes::<Vec<i32>>(res).unwrap(), vec![1, 2, 3]);
+}
@@ -0,0 +1,413 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: Copyright (c) 2025 Asymptotic Inc.
+// SPDX-FileCopyrightText: Copyright (c) 2025 Arun Raghavan
+
+use pipewire_native_spa::param::audio::encoded::AudioInfoMp3;
+use pipewire_native_spa::param::format::{Format, MediaSubtype};
+use pipewire_native_spa::param::video::color::{
+    VideoChromaSite, VideoColorMatrix, VideoColorPrimaries, VideoColorRange, VideoTransferFunction,
+};
+use pipewire_native_spa::param::video::encoded::{
+    H264Alignment, H264StreamFormat, H265Alignment, H265StreamFormat, VideoInfoEncoded,
+    VideoInfoH264, VideoInfoH265, VideoInfoMjpg,
+};
+use pipewire_native_spa::param::video::raw::{
+    VideoChoices, VideoFlags, VideoFormat, VideoInfoRaw, VideoInterlaceMode, VideoMultiviewMode,
+};
+use pipewire_native_spa::param::ParamType;
+use pipewire_native_spa::pod::builder::Builder;
+use pipewire_native_spa::pod::object::ObjectRef;
+use pipewire_native_spa::pod::parser::Parser;
+use pipewire_native_spa::pod::types::{Choice, Fraction, Id, PropertyFlags, Rectangle};
+
+use libspa::pod as spa_pod;
+use libspa::sys::{self as spa_sys};
+
+mod common;
+use common::{assert_dump, assert_rejects, round_trip};
+
+fn hd() -> VideoInfoRaw {
+    VideoInfoRaw {
+        format: VideoFormat::NV12,
+        size: Rectangle {
+            width: 1920,
+            height: 1080,
+        },
+        framerate: Fraction { num: 30, denom: 1 },
+        ..VideoInfoRaw::default()
+    }
+}
+
+#[test]
+fn test_video_format() {
+    assert_eq!(VideoFormat::I420 as u32, 2);
+    assert_eq!(VideoFormat::BGRx as u32, 8);
+    assert_eq!(VideoFormat::NV12 as u32, 23);
+    assert_eq!(VideoFormat::RGBA_F32 as u32, 79);
+    assert_eq!(VideoFormat::BGRA_102LE as u32, 87);
+    assert_eq!(VideoMultiviewMode::None as u32, u32::MAX);
+    assert_eq!(VideoMultiviewMode::FrameByFrame as u32, 32);
+    assert_eq!(VideoChromaSite::DV.bits(), 0xe);
+}
+
+#[test]
+fn test_video_info_raw() {
+    let raw = round_trip(&hd(), VideoInfoRaw::build_format, VideoInfoRaw::parse);
+    assert_dump(
+        &raw,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Video (2)",
+            "  MediaSubtype: Id Raw (1)",
+            "  VideoFormat: Id NV12 (23)",
+            "  VideoSize: Rectangle 1920x1080",
+            "  VideoFramerate: Fraction 30/1",
+        ],
+    );
+
+    // Everything set
+    let info = VideoInfoRaw {
+        flags: VideoFlags::MODIFIER,
+        modifier: 0x0100_0000_0000_0001,
+        max_framerate: Fraction { num: 60, denom: 1 },
+        views: 1,
+        interlace_mode: VideoInterlaceMode::Mixed,
+        pixel_aspect_ratio: Fraction { num: 1, denom: 1 },
+        multiview_mode: VideoMultiviewMode::None,
+        multiview_flags: Default::default(),
+        chroma_site: VideoChromaSite::MPEG2,
+        color_range: VideoColorRange::Limited,
+        color_matrix: VideoColorMatrix::BT709,
+        transfer_function: VideoTransferFunction::BT709,
+        color_primaries: VideoColorPrimaries::BT709,
+        ..hd()
+    };
+    let pod = round_trip(&info, VideoInfoRaw::build_format, VideoInfoRaw::parse);
+    let object = ObjectRef::wrap(&pod).unwrap();
+    let modifier = object.property(Format::VideoModifier).unwrap();
+    assert_eq!(modifier.flags, PropertyFlags::MANDATORY);
+    assert_eq!(
+        modifier.value.decode::<i64>().unwrap(),
+        0x0100_0000_0000_0001
+    );
+
+    // Not a raw video format
+    let mp3 = AudioInfoMp3 {
+        rate: 44100,
+        channels: 2,
+    };
+    let other = round_trip(&mp3, AudioInfoMp3::build_format, AudioInfoMp3::parse);
+    assert_rejects(VideoInfoRaw::parse, &raw, &[&other]);
+}
+
+#[test]
+fn test_video_enum_format() {
+    let info = VideoInfoRaw {
+        modifier: 0,
+        modifiers: vec![0, 0x0100_0000_0000_0001, 0x0100_0000_0000_0002],
+        ..VideoInfoRaw::default()
+    };
+    let choices = VideoChoices {
+        formats: vec![VideoFormat::BGRx, VideoFormat::RGBx],
+        size: Some(Choice::Range {
+            default: Rectangle {
+                width: 640,
+                height: 480,
+            },
+            min: Rectangle {
+                width: 1,
+                height: 1,
+            },
+            max: Rectangle {
+                width: 4096,
+                height: 4096,
+            },
+        }),
+        framerate: Some(Choice::Range {
+            default: Fraction { num: 30, denom: 1 },
+            min: Fraction { num: 0, denom: 1 },
+            max: Fraction { num: 60, denom: 1 },
+        }),
+        max_framerate: None,
+    };
+
+    let mut buf = [0u8; 1024];
+    let pod = info
+        .build_enum_format(Builder::new(&mut buf), &choices)
+        .build()
+        .unwrap();
+
+    let mut parser = Parser::new(pod);
+    let object = parser.pop_object_ref().unwrap();
+    assert_eq!(object.id(), ParamType::EnumFormat as u32);
+    assert_eq!(
+        object
+            .get_as::<Choice<Id<VideoFormat>>>(Format::VideoFormat)
+            .unwrap(),
+        Some(Choice::Enum {
+            default: Id(VideoFormat::BGRx),
+            alternatives: vec![Id(VideoFormat::BGRx), Id(VideoFormat::RGBx)],
+        })
+    );
+    assert_eq!(
+        object
+            .get_as::<Choice<Rectangle>>(Format::VideoSize)
+            .unwrap(),
+        choices.size
+    );
+    assert_eq!(
+        object
+            .get_as::<Choice<Fraction>>(Format::VideoFramerate)
+            .unwrap(),
+        choices.framerate
+    );
+    assert!(object.get(Format::VideoMaxFramerate).is_none());
+
+    let modifier = object.property(Format::VideoModifier).unwrap();
+    assert_eq!(
+        modifier.flags,
+        PropertyFlags::MANDATORY | PropertyFlags::DONT_FIXATE
+    );
+
+    // A format with a list of modifiers still to be fixated
+    let info = VideoInfoRaw {
+        flags: VideoFlags::MODIFIER | VideoFlags::MODIFIER_FIXATION_REQUIRED,
+        ..info
+    };
+    round_trip(&info, VideoInfoRaw::build_format, VideoInfoRaw::parse);
+
+    // Once fixated, only one modifier remains
+    let info = VideoInfoRaw {
+        flags: VideoFlags::MODIFIER,
+        modifier: 0x0100_0000_0000_0002,
+        modifiers: vec![],
+        ..info
+    };
+    round_trip(&info, VideoInfoRaw::build_format, VideoInfoRaw::parse);
+}
+
+#[test]
+fn test_video_info_raw_libspa() {
+    let info = VideoInfoRaw {
+        flags: VideoFlags::MODIFIER,
+        modifier: 0x0100_0000_0000_0001,
+        color_range: VideoColorRange::Full,
+        ..hd()
+    };
+
+    // Parsed by libspa
+    let mut buf = [0u8; 1024];
+    let pod = info.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_video_info_raw = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_video_raw_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!(raw.format, VideoFormat::NV12 as u32);
+    assert_eq!(raw.flags, VideoFlags::MODIFIER.bits());
+    assert_eq!(raw.modifier, 0x0100_0000_0000_0001);
+    assert_eq!((raw.size.width, raw.size.height), (1920, 1080));
+    assert_eq!((raw.framerate.num, raw.framerate.denom), (30, 1));
+    assert_eq!(raw.color_range, VideoColorRange::Full as u32);
+
+    // Built by libspa
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_video_raw_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+
+    assert_eq!(sbuf, pod);
+    assert_eq!(VideoInfoRaw::parse(&mut Parser::new(&sbuf)).unwrap(), info);
+}
+
+#[test]
+fn test_video_encoded() {
+    let h264 = VideoInfoH264 {
+        size: Rectangle {
+            width: 1280,
+            height: 720,
+        },
+        framerate: Fraction { num: 30, denom: 1 },
+        stream_format: H264StreamFormat::ByteStream,
+        alignment: H264Alignment::Au,
+        ..VideoInfoH264::default()
+    };
+
+    let pod = round_trip(&h264, VideoInfoH264::build_format, VideoInfoH264::parse);
+    assert_dump(
+        &pod,
+        &[
+            "Object Format, id Format:",
+            "  MediaType: Id Video (2)",
+            "  MediaSubtype: Id H264 (131073)",
+            "  VideoSize: Rectangle 1280x720",
+            "  VideoFramerate: Fraction 30/1",
+            "  VideoH264StreamFormat: Id ByteStream (3)",
+            "  VideoH264Alignment: Id Au (1)",
+        ],
+    );
+
+    // Other subtypes are rejected
+    let raw = round_trip(&hd(), VideoInfoRaw::build_format, VideoInfoRaw::parse);
+    assert_rejects(VideoInfoH264::parse, &pod, &[&raw]);
+    assert_rejects(VideoInfoH265::parse, &pod, &[]);
+    assert_rejects(VideoInfoMjpg::parse, &pod, &[]);
+    assert_rejects(VideoInfoRaw::parse, &raw, &[&pod]);
+
+    // Cameras offer a range of sizes and frame rates
+    let choices = VideoChoices {
+        size: Some(Choice::Step {
+            default: Rectangle {
+                width: 640,
+                height: 480,
+            },
+            min: Rectangle {
+                width: 160,
+                height: 120,
+            },
+            max: Rectangle {
+                width: 1920,
+                height: 1080,
+            },
+            step: Rectangle {
+                width: 16,
+                height: 16,
+            },
+        }),
+        framerate: Some(Choice::Enum {
+            default: Fraction { num: 30, denom: 1 },
+            alternatives: vec![
+                Fraction { num: 30, denom: 1 },
+                Fraction { num: 15, denom: 1 },
+            ],
+        }),
+        ..VideoChoices::default()
+    };
+    let mjpg = VideoInfoMjpg::default();
+    let mut buf = [0u8; 1024];
+    let pod = mjpg
+        .build_enum_format(Builder::new(&mut buf), &choices)
+        .build()
+        .unwrap();
+
+    let object = ObjectRef::wrap(pod).unwrap();
+    assert_eq!(object.id(), ParamType::EnumFormat as u32);
+    assert_eq!(
+        object
+            .get_as::<Id<MediaSubtype>>(Format::MediaSubtype)
+            .unwrap(),
+        Some(Id(MediaSubtype::Mjpg))
+    );
+    assert_eq!(
+        object
+            .get_as::<Choice<Rectangle>>(Format::VideoSize)
+            .unwrap(),
+        choices.size
+    );
+    assert_eq!(
+        object
+            .get_as::<Choice<Fraction>>(Format::VideoFramerate)
+            .unwrap(),
+        choices.framerate
+    );
+
+    let h265 = VideoInfoH265 {
+        size: Rectangle {
+            width: 3840,
+            height: 2160,
+        },
+        framerate: Fraction { num: 60, denom: 1 },
+        max_framerate: Fraction { num: 60, denom: 1 },
+        stream_format: H265StreamFormat::Hvc1,
+        alignment: H265Alignment::Nal,
+        profile: 1,
+        level: 153,
+    };
+    round_trip(&h265, VideoInfoH265::build_format, VideoInfoH265::parse);
+
+    let vp9 = VideoInfoEncoded {
+        size: Rectangle {
+            width: 1920,
+            height: 1080,
+        },
+        framerate: Fraction { num: 25, denom: 1 },
+        profile: 2,
+        ..VideoInfoEncoded::new(MediaSubtype::Vp9)
+    };
+    let pod = round_trip(
+        &vp9,
+        VideoInfoEncoded::build_format,
+        VideoInfoEncoded::parse,
+    );
+
+    // Raw video is not an encoded format
+    assert_rejects(VideoInfoEncoded::parse, &pod, &[&raw]);
+}
+
+#[test]
+fn test_video_encoded_libspa() {
+    let h264 = VideoInfoH264 {
+        size: Rectangle {
+            width: 1280,
+            height: 720,
+        },
+        framerate: Fraction { num: 30, denom: 1 },
+        max_framerate: Fraction { num: 60, denom: 1 },
+        stream_format: H264StreamFormat::Avc,
+        alignment: H264Alignment::Nal,
+        ..VideoInfoH264::default()
+    };
+    let mut buf = [0u8; 1024];
+    let pod = h264.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_video_info_h264 = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_video_h264_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!((raw.size.width, raw.size.height), (1280, 720));
+    assert_eq!((raw.max_framerate.num, raw.max_framerate.denom), (60, 1));
+    assert_eq!(raw.stream_format, H264StreamFormat::Avc as u32);
+    assert_eq!(raw.alignment, H264Alignment::Nal as u32);
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_video_h264_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+
+    let mjpg = VideoInfoMjpg {
+        size: Rectangle {
+            width: 640,
+            height: 480,
+        },
+        framerate: Fraction { num: 15, denom: 1 },
+        max_framerate: Fraction::default(),
+    };
+    let mut buf = [0u8; 1024];
+    let pod = mjpg.build_format(Builder::new(&mut buf)).build().unwrap();
+
+    let mut raw: spa_sys::spa_video_info_mjpg = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        spa_sys::spa_format_video_mjpg_parse(pod.as_ptr() as *const spa_sys::spa_pod, &mut raw)
+    };
+    assert!(res >= 0);
+    assert_eq!((raw.size.width, raw.size.height), (640, 480));
+    assert_eq!((raw.framerate.num, raw.framerate.denom), (15, 1));
+
+    let mut sbuf = Vec::with_capacity(1024);
+    let sbuilder = spa_pod::builder::Builder::new(&mut sbuf);
+    let spod = unsafe {
+        spa_sys::spa_format_video_mjpg_build(sbuilder.as_raw_ptr(), ParamType::Format as u32, &raw)
+    };
+    assert!(!spod.is_null());
+    drop(sbuilder);
+    assert_eq!(sbuf, pod);
+}